@@ -83,7 +83,7 @@ elseif ($Action -eq "msi") {
     $WixSource = @"
 <?xml version="1.0" encoding="UTF-8"?>
 <Wix xmlns="http://schemas.microsoft.com/wix/2006/wi">
-    <Product Id="$ProductGuid" Name="Framework Control Native" Language="1033" Version="0.4.3.0" Manufacturer="Framework Control" UpgradeCode="$( [guid]::NewGuid().ToString() )">
+    <Product Id="$ProductGuid" Name="Framework Control Native" Language="1033" Version="0.5.0.0" Manufacturer="Framework Control" UpgradeCode="$( [guid]::NewGuid().ToString() )">
         <Package InstallerVersion="200" Compressed="yes" InstallScope="perMachine" />
         <MajorUpgrade DowngradeErrorMessage="A newer version is already installed." />
         <MediaTemplate EmbedCab="yes" />
@@ -106,7 +106,7 @@ elseif ($Action -eq "msi") {
         <ComponentGroup Id="ProductComponents" Directory="INSTALLFOLDER">
             <Component Id="ProductComponent" Guid="$MainExeGuid">
                 <File Source="$ServiceExePath" Id="FrameworkControlEXE" KeyPath="yes" />
-                <ServiceInstall Id="ServiceInstaller" Type="ownProcess" Name="FrameworkControl" DisplayName="Framework Control Service" Description="Manages Framework Laptop hardware settings" Start="auto" Account="LocalSystem" ErrorControl="normal" />
+                <ServiceInstall Id="ServiceInstaller" Type="ownProcess" Name="FrameworkControl" DisplayName="Framework Control Service" Description="Manages Framework Laptop hardware settings" Start="auto" Account="LocalSystem" ErrorControl="normal" Arguments="--run-as-service" />
                 <ServiceControl Id="StartService" Start="install" Stop="both" Remove="uninstall" Name="FrameworkControl" Wait="yes" />
             </Component>
         </ComponentGroup>
@@ -126,9 +126,9 @@ elseif ($Action -eq "msi") {
 
     Set-Location $WixDir
     & candle.exe "FrameworkControl.wxs" -ext WixUtilExtension
-    & light.exe "FrameworkControl.wixobj" -ext WixUtilExtension -ext WixUIExtension -out "$OutputDir\FrameworkControlNative-0.4.3.msi" -sval
+    & light.exe "FrameworkControl.wixobj" -ext WixUtilExtension -ext WixUIExtension -out "$OutputDir\FrameworkControlNative-0.5.0.msi" -sval
 
-    Write-Host "MSI Created: $OutputDir\FrameworkControlNative-0.4.3.msi" -ForegroundColor Green
+    Write-Host "MSI Created: $OutputDir\FrameworkControlNative-0.5.0.msi" -ForegroundColor Green
     Pop-Location
 }
 elseif ($Action -eq "clean") {
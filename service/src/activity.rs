@@ -0,0 +1,37 @@
+// User-facing history of what the app did; the tracing log stays the place for debugging detail
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::RwLock;
+
+/// Oldest events are dropped beyond this
+pub const MAX_EVENTS: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub at: Instant,
+    pub message: String,
+}
+
+pub type Activity = Arc<RwLock<VecDeque<Event>>>;
+
+pub async fn push(log: &Activity, message: impl Into<String>) {
+    let mut log = log.write().await;
+    if log.len() >= MAX_EVENTS {
+        log.pop_front();
+    }
+    log.push_back(Event {
+        at: Instant::now(),
+        message: message.into(),
+    });
+}
+
+/// Short relative age, e.g. "42s ago" or "3h ago"
+pub fn age(at: Instant) -> String {
+    let secs = at.elapsed().as_secs();
+    match secs {
+        0..=59 => format!("{}s ago", secs),
+        60..=3599 => format!("{}m ago", secs / 60),
+        _ => format!("{}h ago", secs / 3600),
+    }
+}
@@ -0,0 +1,232 @@
+// Diagnostics bundle for bug reports: one zip holding everything a maintainer asks for
+// first. Nothing is redacted; none of it identifies the user beyond the machine model.
+//
+// | File | Contents |
+// |---|---|
+// | `system.txt` | app version, OS, CPU brand and vendor, mainboard, EC and BIOS versions, EC status, elevation, power limit backend |
+// | `config.json` | the settings in effect |
+// | `telemetry.csv` | the last `TELEMETRY_WINDOW_S` of recorded samples |
+// | `ec_map.txt` | decoded EC memory map, as from "EC Diagnostic" |
+// | `activity.txt` | the activity log shown in the app |
+// | `logs/*.log` | the last `LOG_LINES` lines of the GUI and service logs |
+use std::path::Path;
+
+use crate::types::TelemetrySample;
+use crate::AppState;
+
+const TELEMETRY_WINDOW_S: i64 = 10 * 60;
+const LOG_LINES: usize = 1000;
+
+const README: &str = "Framework Control diagnostics
+
+system.txt     app, OS, CPU, mainboard, firmware versions and EC access
+config.json    the settings in effect
+telemetry.csv  the last 10 minutes of temperatures, fans, battery and power
+ec_map.txt     the EC memory map, decoded and raw
+activity.txt   what the app did recently
+logs/          the end of the GUI and service logs
+";
+
+/// Gather the bundle's files as (name, contents)
+pub async fn collect(state: &AppState) -> Vec<(String, Vec<u8>)> {
+    let mut files = vec![("README.txt".to_string(), README.as_bytes().to_vec())];
+
+    files.push((
+        "system.txt".to_string(),
+        system_info(state).await.into_bytes(),
+    ));
+
+    let config = serde_json::to_string_pretty(&*state.config.read().await)
+        .unwrap_or_else(|e| format!("Config not serializable: {}", e));
+    files.push(("config.json".to_string(), config.into_bytes()));
+
+    let samples: Vec<TelemetrySample> = {
+        let samples = state.telemetry_samples.read().await;
+        let start = samples
+            .back()
+            .map_or(0, |s| s.ts_ms - TELEMETRY_WINDOW_S * 1000);
+        samples
+            .iter()
+            .filter(|s| s.ts_ms >= start)
+            .cloned()
+            .collect()
+    };
+    files.push((
+        "telemetry.csv".to_string(),
+        crate::csv_export::telemetry_csv(&samples).into_bytes(),
+    ));
+
+    let ec_map = tokio::task::spawn_blocking(crate::diagnostics::ec_map_report)
+        .await
+        .ok()
+        .flatten()
+        .map_or_else(|| "EC memory map unreadable".to_string(), |r| r.to_text());
+    files.push(("ec_map.txt".to_string(), ec_map.into_bytes()));
+
+    let activity: String = state
+        .activity
+        .read()
+        .await
+        .iter()
+        .map(|e| format!("{:>8}  {}\n", crate::activity::age(e.at), e.message))
+        .collect();
+    files.push(("activity.txt".to_string(), activity.into_bytes()));
+
+    let mut dirs = vec![crate::logging::current_dir(), crate::logging::log_dir()];
+    dirs.dedup();
+    for dir in dirs {
+        for name in ["gui.log", "service.log"] {
+            if let Some(tail) = tail(&dir.join(name), LOG_LINES) {
+                if !files.iter().any(|(n, _)| n.ends_with(name)) {
+                    files.push((format!("logs/{}", name), tail.into_bytes()));
+                }
+            }
+        }
+    }
+    files
+}
+
+async fn system_info(state: &AppState) -> String {
+    let versions = crate::cli::FrameworkTool::new()
+        .await
+        .read_versions()
+        .await
+        .ok();
+    let field = |value: Option<&str>| value.unwrap_or(crate::cli::Versions::UNKNOWN).to_string();
+    let backend = tokio::task::spawn_blocking(crate::power_limits::backend)
+        .await
+        .map_or("unknown", |b| b.label());
+    [
+        format!("Framework Control {}", env!("CARGO_PKG_VERSION")),
+        format!("OS: {} {}", std::env::consts::OS, std::env::consts::ARCH),
+        format!(
+            "CPU: {} ({})",
+            crate::cpu::brand_string(),
+            state.cpu_vendor.label()
+        ),
+        format!(
+            "Mainboard: {}",
+            field(versions.as_ref().and_then(|v| v.mainboard.as_deref()))
+        ),
+        format!(
+            "EC firmware: {}",
+            field(versions.as_ref().map(|v| v.ec_version.as_str()))
+        ),
+        format!(
+            "BIOS: {}",
+            field(versions.as_ref().map(|v| v.bios_version.as_str()))
+        ),
+        format!("EC status: {:?}", *state.ec_status.read().await),
+        format!("Elevated: {}", crate::ec::is_elevated()),
+        format!("Power limits via: {}", backend),
+    ]
+    .join("\n")
+        + "\n"
+}
+
+/// The last `lines` lines of a text file
+fn tail(path: &Path, lines: usize) -> Option<String> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let all: Vec<&str> = text.lines().collect();
+    let start = all.len().saturating_sub(lines);
+    Some(all[start..].join("\n") + "\n")
+}
+
+/// A zip archive holding `files` uncompressed; small text files gain little from
+/// compression, and storing needs nothing beyond a CRC
+pub fn zip(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+    // 1980-01-01 00:00 in MS-DOS format
+    const DOS_TIME: u16 = 0;
+    const DOS_DATE: u16 = 0x21;
+    // Names are UTF-8
+    const FLAGS: u16 = 1 << 11;
+    const VERSION: u16 = 20;
+
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+    for (name, data) in files {
+        let offset = out.len() as u32;
+        let crc = crc32fast::hash(data);
+        let size = data.len() as u32;
+
+        out.extend(0x0403_4b50u32.to_le_bytes());
+        for v in [VERSION, FLAGS, 0, DOS_TIME, DOS_DATE] {
+            out.extend(v.to_le_bytes());
+        }
+        for v in [crc, size, size] {
+            out.extend(v.to_le_bytes());
+        }
+        out.extend((name.len() as u16).to_le_bytes());
+        out.extend(0u16.to_le_bytes());
+        out.extend(name.as_bytes());
+        out.extend(data);
+
+        central.extend(0x0201_4b50u32.to_le_bytes());
+        for v in [VERSION, VERSION, FLAGS, 0, DOS_TIME, DOS_DATE] {
+            central.extend(v.to_le_bytes());
+        }
+        for v in [crc, size, size] {
+            central.extend(v.to_le_bytes());
+        }
+        // Name length, extra, comment, disk, internal attributes
+        for v in [name.len() as u16, 0, 0, 0, 0] {
+            central.extend(v.to_le_bytes());
+        }
+        for v in [0, offset] {
+            central.extend(v.to_le_bytes());
+        }
+        central.extend(name.as_bytes());
+    }
+
+    let central_offset = out.len() as u32;
+    let central_size = central.len() as u32;
+    out.extend(central);
+    out.extend(0x0605_4b50u32.to_le_bytes());
+    let count = files.len() as u16;
+    for v in [0, 0, count, count] {
+        out.extend(v.to_le_bytes());
+    }
+    for v in [central_size, central_offset] {
+        out.extend(v.to_le_bytes());
+    }
+    out.extend(0u16.to_le_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes(bytes[at..at + 2].try_into().unwrap())
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn zip_layout_matches_the_format() {
+        let files = vec![
+            ("a.txt".to_string(), b"hello".to_vec()),
+            ("b.txt".to_string(), b"world!".to_vec()),
+        ];
+        let zip = zip(&files);
+
+        assert_eq!(u32_at(&zip, 0), 0x0403_4b50);
+        assert_eq!(u32_at(&zip, 14), crc32fast::hash(b"hello"));
+        assert_eq!(u32_at(&zip, 18), 5);
+        assert_eq!(&zip[30..35], b"a.txt");
+        assert_eq!(&zip[35..40], b"hello");
+        assert_eq!(u32_at(&zip, 40), 0x0403_4b50);
+
+        // The end of central directory record closes the archive
+        let eocd = zip.len() - 22;
+        assert_eq!(u32_at(&zip, eocd), 0x0605_4b50);
+        assert_eq!(u16_at(&zip, eocd + 10), 2);
+        let central_size = u32_at(&zip, eocd + 12) as usize;
+        let central_offset = u32_at(&zip, eocd + 16) as usize;
+        assert_eq!(central_offset + central_size, eocd);
+        assert_eq!(u32_at(&zip, central_offset), 0x0201_4b50);
+    }
+}
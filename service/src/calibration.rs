@@ -0,0 +1,83 @@
+// Fan calibration: step the duty across its range and record the settled RPM at each step,
+// giving the duty -> RPM table the noise estimate and failing-fan check work from
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::cli::{FrameworkTool, ThermalSensor};
+use crate::types::FanCalibration;
+
+pub const STEP_PCT: usize = 10;
+/// Time for the fans to reach speed after each duty change
+const SETTLE: Duration = Duration::from_secs(4);
+/// Running the fans slowly is only safe while the machine is cool
+pub const ABORT_TEMP_C: f32 = 85.0;
+/// Below this every step is measured; above it the slowest steps are skipped
+const COOL_TEMP_C: f32 = 50.0;
+
+#[derive(Debug, Clone)]
+pub enum Status {
+    Running { duty_pct: u32 },
+    Finished(Result<FanCalibration, String>),
+}
+
+/// Step 0..=100% and average the fans' settled RPM at each duty, skipping steps too slow
+/// for the current temperature. The caller must keep anything else from commanding the
+/// fans meanwhile, and hand them back afterwards.
+pub async fn run(
+    tool: &FrameworkTool,
+    status: &Arc<RwLock<Option<Status>>>,
+) -> Result<FanCalibration, String> {
+    let mut points = Vec::new();
+    for duty_pct in (0..=100).step_by(STEP_PCT) {
+        // Checked before the fans slow down, not only once they have settled
+        let hottest = hottest_c(tool).await?;
+        too_hot(duty_pct, hottest)?;
+        if (duty_pct as f32) < safe_duty(hottest) {
+            continue;
+        }
+        *status.write().await = Some(Status::Running { duty_pct });
+        tool.set_fan_duty(duty_pct, None).await?;
+        tokio::time::sleep(SETTLE).await;
+
+        let thermal = tool.read_thermal().await?;
+        too_hot(duty_pct, hottest_of(&thermal.sensors))?;
+        if thermal.fans.is_empty() {
+            return Err("The EC reports no fans".to_string());
+        }
+        let rpm = thermal.fans.iter().sum::<f32>() / thermal.fans.len() as f32;
+        points.push([duty_pct, rpm.round() as u32]);
+    }
+    let updated_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    Ok(FanCalibration { points, updated_at })
+}
+
+async fn hottest_c(tool: &FrameworkTool) -> Result<f32, String> {
+    Ok(hottest_of(&tool.read_thermal().await?.sensors))
+}
+
+fn hottest_of(sensors: &[ThermalSensor]) -> f32 {
+    sensors
+        .iter()
+        .map(|s| s.temp_c)
+        .fold(f32::NEG_INFINITY, f32::max)
+}
+
+fn too_hot(duty_pct: u32, hottest: f32) -> Result<(), String> {
+    if hottest > ABORT_TEMP_C {
+        return Err(format!(
+            "Stopped at {}%: {:.0}°C is too hot to run the fans slowly; retry once the machine has cooled",
+            duty_pct, hottest
+        ));
+    }
+    Ok(())
+}
+
+/// Lowest duty worth dropping to at `hottest`: none while cool, rising to full speed at
+/// the abort temperature
+fn safe_duty(hottest: f32) -> f32 {
+    ((hottest - COOL_TEMP_C) / (ABORT_TEMP_C - COOL_TEMP_C) * 100.0).clamp(0.0, 100.0)
+}
@@ -0,0 +1,71 @@
+// Embedded "what's new" notes, shown once after the app is upgraded
+
+/// Release notes keyed by version, oldest first.
+pub const ENTRIES: &[(&str, &[&str])] = &[
+    (
+        "0.4.3",
+        &[
+            "Single-page dashboard with temperatures, power and fans",
+            "Grid-based fan curve editor with Auto / Manual / Curve modes",
+            "Custom TDP and thermal limits",
+            "Battery charge limit",
+            "Start on Startup option",
+        ],
+    ),
+    (
+        "0.5.0",
+        &[
+            "Fan curves per fan and per power source, with smoothing, dwell and smooth interpolation",
+            "Fan calibration, noise estimates, stall and low-RPM alerts, and a fan watchdog",
+            "Named profiles with inheritance, shareable presets and fw-fanctrl import",
+            "Charge limit window, charge current and rate limits, and charge to full once",
+            "Separate sustained and burst power limits through ryzenadj, with confirm-or-revert",
+            "Telemetry charts, recording, snapshots, CSV export and a web dashboard",
+            "Tray icon, global hotkeys, gauge view and an activity log",
+            "Background service, command-line control with JSON output, and a diagnostics bundle",
+        ],
+    ),
+];
+
+fn parse_version(v: &str) -> (u32, u32, u32) {
+    let mut parts = v
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .map(|p| p.parse::<u32>().unwrap_or(0));
+    (
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+        parts.next().unwrap_or(0),
+    )
+}
+
+/// Entries newer than `last_seen` up to and including `current`.
+/// With no stored version (first run after this was introduced) only the current entry is returned.
+pub fn since(
+    last_seen: Option<&str>,
+    current: &str,
+) -> Vec<(&'static str, &'static [&'static str])> {
+    let current_v = parse_version(current);
+    match last_seen {
+        Some(seen) => {
+            let seen_v = parse_version(seen);
+            if seen_v >= current_v {
+                return Vec::new();
+            }
+            ENTRIES
+                .iter()
+                .filter(|(v, _)| {
+                    let v = parse_version(v);
+                    v > seen_v && v <= current_v
+                })
+                .copied()
+                .collect()
+        }
+        None => ENTRIES
+            .iter()
+            .filter(|(v, _)| parse_version(v) == current_v)
+            .copied()
+            .collect(),
+    }
+}
@@ -1,5 +1,18 @@
 // Consolidated CLI module for Framework laptop hardware control
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{OnceLock, RwLock};
+
+/// Calibration offsets applied to every decoded sensor reading, so the
+/// display, telemetry and the fan curve all see the same corrected values.
+static SENSOR_OFFSETS: RwLock<BTreeMap<String, f32>> = RwLock::new(BTreeMap::new());
+
+/// Replace the active per-sensor offsets (°C, keyed by sensor name)
+pub fn set_sensor_offsets(offsets: BTreeMap<String, f32>) {
+    if let Ok(mut guard) = SENSOR_OFFSETS.write() {
+        *guard = offsets;
+    }
+}
 
 // Data structures for hardware information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,10 +21,51 @@ pub struct ThermalSensor {
     pub temp_c: f32,
 }
 
+#[derive(Debug, Clone)]
+pub struct MacroRun {
+    pub name: String,
+    pub steps: Vec<MacroStepResult>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MacroStepResult {
+    pub command: u16,
+    pub version: u8,
+    pub result: Result<Vec<u8>, String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThermalParsed {
     pub sensors: Vec<ThermalSensor>,
+    /// RPM per fan; a stalled fan reads 0 and is listed in `stalled_fans`
     pub fans: Vec<f32>,
+    /// Zero-based fans the EC reports as stalled rather than giving an RPM
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stalled_fans: Vec<usize>,
+    /// Undecoded memory-map temperature slots, for the raw sensor view
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub raw_sensors: Vec<u8>,
+}
+
+/// What this app can drive on the current machine; a feature is only reported when the
+/// app implements it and the EC answers the command it uses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub app_version: String,
+    pub ec_connected: bool,
+    pub cpu: String,
+    pub fan_count: usize,
+    pub fan_duty_control: bool,
+    /// Individual fans can be set separately, through the per-fan (v1) duty command
+    pub per_fan_control: bool,
+    pub target_rpm_mode: bool,
+    pub charge_limit: bool,
+    /// A rate-C charge limit can be applied; it is converted to a charge current limit
+    pub charge_rate_limit: bool,
+    pub charge_current_limit: bool,
+    pub keyboard_backlight: bool,
+    pub tdp_control: bool,
+    pub thermal_limit_control: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,17 +74,330 @@ pub struct PowerBatteryInfo {
     pub status: String,
     pub capacity_current: u32,
     pub capacity_design: u32,
+    /// Pack voltage; None when the EC battery block couldn't be read
+    #[serde(default)]
+    pub voltage: Option<f32>,
+    /// Amps, negative while discharging; None when the EC battery block couldn't be read
+    #[serde(default)]
+    pub current: Option<f32>,
+    /// Battery power in watts: positive while charging, negative while discharging
+    #[serde(default)]
+    pub battery_power_w: Option<f32>,
+    /// Estimated power from the charger (None on battery). The EC reports the input current
+    /// limit rather than a measured current, so this is an upper bound.
+    #[serde(default)]
+    pub charger_input_w: Option<f32>,
+    /// System consumption: the charger estimate minus battery charge power, or battery output
+    /// when unplugged
+    #[serde(default)]
+    pub system_power_w: Option<f32>,
+    /// Firmware flags the battery as critically low (not just below the charge limit)
+    #[serde(default)]
+    pub critical: bool,
+    /// Each present pack; the fields above aggregate them. Empty without EC battery data.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub batteries: Vec<BatteryDetail>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatteryDetail {
+    /// Remaining over full-charge capacity, when both are known
+    pub charge_percent: Option<f32>,
     pub voltage: f32,
+    /// Amps; negative while discharging
     pub current: f32,
+    /// Full-charge capacity in mAh (0 when unknown)
+    pub capacity_current: u32,
+    /// Design capacity in mAh (0 when unknown)
+    pub capacity_design: u32,
+    pub critical: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycle_count: Option<u32>,
+}
+
+impl BatteryDetail {
+    fn from_reading(batt: &crate::ec::BatteryReading) -> Self {
+        let amps = batt.rate_ma as f32 / 1000.0;
+        // Only trust capacities that look like a real battery; 0 means unknown
+        let validate = |mah: u32| if PLAUSIBLE_CAPACITY_MAH.contains(&mah) { mah } else { 0 };
+        let capacity_current = validate(batt.last_full_mah);
+        Self {
+            charge_percent: (capacity_current > 0)
+                .then(|| (batt.remaining_mah as f32 / capacity_current as f32 * 100.0).min(100.0)),
+            voltage: batt.voltage_mv as f32 / 1000.0,
+            current: if batt.discharging() { -amps } else { amps },
+            capacity_current,
+            capacity_design: validate(batt.design_mah),
+            critical: batt.critical(),
+            cycle_count: batt.cycle_count,
+        }
+    }
+
+    pub fn health_pct(&self) -> Option<f32> {
+        health_pct(self.capacity_current, self.capacity_design)
+    }
+}
+
+/// Full-charge capacity over design capacity (0-100%), or `None` when either is implausible
+fn health_pct(full_mah: u32, design_mah: u32) -> Option<f32> {
+    if !PLAUSIBLE_CAPACITY_MAH.contains(&design_mah) || !PLAUSIBLE_CAPACITY_MAH.contains(&full_mah)
+    {
+        return None;
+    }
+    // A freshly calibrated pack can report slightly more than design
+    let ratio = full_mah as f32 / design_mah as f32;
+    Some((ratio * 100.0).min(100.0))
+}
+
+// Capacities outside this range (mAh) are treated as EC garbage rather than real data
+const PLAUSIBLE_CAPACITY_MAH: std::ops::RangeInclusive<u32> = 500..=20_000;
+
+impl PowerBatteryInfo {
+    /// Battery health as full-charge capacity over design capacity (0-100%).
+    /// Returns `None` when either capacity is missing or implausible.
+    pub fn health_pct(&self) -> Option<f32> {
+        health_pct(self.capacity_current, self.capacity_design)
+    }
+
+    /// Capacity lost since new, the complement of `health_pct`
+    pub fn wear_pct(&self) -> Option<f32> {
+        self.health_pct().map(|health| 100.0 - health)
+    }
+
+    /// Charge cycles of the most used pack, when the EC reports them
+    pub fn cycle_count(&self) -> Option<u32> {
+        self.batteries.iter().filter_map(|b| b.cycle_count).max()
+    }
+
+    /// Fill in voltage/current from the EC battery block and derive the charger/battery/system split
+    fn apply_ec_readings(&mut self) {
+        self.batteries = crate::ec::read_batteries()
+            .iter()
+            .map(BatteryDetail::from_reading)
+            .collect();
+        // Without a pack reading voltage, current and battery power stay unknown
+        if !self.batteries.is_empty() {
+            // Packs share the system rail: voltage is averaged, currents and capacities add up
+            let packs = &self.batteries;
+            self.voltage = Some(packs.iter().map(|b| b.voltage).sum::<f32>() / packs.len() as f32);
+            self.current = Some(packs.iter().map(|b| b.current).sum());
+            // One unknown capacity makes the total unknown too
+            let total = |mah: fn(&BatteryDetail) -> u32| {
+                packs
+                    .iter()
+                    .map(mah)
+                    .try_fold(0u32, |sum, m| (m > 0).then(|| sum + m))
+                    .unwrap_or(0)
+            };
+            self.capacity_design = total(|b| b.capacity_design);
+            self.capacity_current = total(|b| b.capacity_current);
+            self.critical |= packs.iter().any(|b| b.critical);
+            self.battery_power_w = Some(packs.iter().map(|b| b.voltage * b.current).sum());
+        }
+
+        self.charger_input_w = crate::ec::read_charger_input_mw().map(|mw| mw as f32 / 1000.0);
+        self.system_power_w = match (self.charger_input_w, self.battery_power_w) {
+            (Some(input), Some(battery)) => Some((input - battery.max(0.0)).max(0.0)),
+            // Still an upper bound, the charge power just can't be taken off
+            (Some(input), None) => Some(input),
+            (None, Some(battery)) if battery < 0.0 => Some(-battery),
+            (None, _) => None,
+        };
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Versions {
     pub ec_version: String,
     pub bios_version: String,
+    /// Mainboard model as reported by SMBIOS, e.g. "FRANMDCP07"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mainboard: Option<String>,
 }
 
-// Main Framework laptop control interface
+impl Versions {
+    pub const UNKNOWN: &'static str = "Unknown";
+
+    /// Whether every version string was actually read
+    pub fn complete(&self) -> bool {
+        self.ec_version != Self::UNKNOWN && self.bios_version != Self::UNKNOWN
+    }
+}
+
+// Firmware doesn't change while the app runs; filled by the first complete read
+static VERSIONS: OnceLock<Versions> = OnceLock::new();
+
+/// BIOS version and mainboard model from the SMBIOS copy Windows keeps in the registry
+#[cfg(windows)]
+fn read_bios_info() -> (Option<String>, Option<String>) {
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn RegGetValueW(
+            hkey: isize,
+            subkey: *const u16,
+            value: *const u16,
+            flags: u32,
+            value_type: *mut u32,
+            data: *mut u8,
+            len: *mut u32,
+        ) -> i32;
+    }
+    const HKEY_LOCAL_MACHINE: isize = 0x8000_0002u32 as i32 as isize;
+    const RRF_RT_REG_SZ: u32 = 0x2;
+
+    let wide = |s: &str| s.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+    let subkey = wide("HARDWARE\\DESCRIPTION\\System\\BIOS");
+    let read = |name: &str| {
+        let value = wide(name);
+        let mut buf = [0u16; 256];
+        let mut len = std::mem::size_of_val(&buf) as u32;
+        let rc = unsafe {
+            RegGetValueW(
+                HKEY_LOCAL_MACHINE,
+                subkey.as_ptr(),
+                value.as_ptr(),
+                RRF_RT_REG_SZ,
+                std::ptr::null_mut(),
+                buf.as_mut_ptr() as *mut u8,
+                &mut len,
+            )
+        };
+        if rc != 0 {
+            return None;
+        }
+        let chars = (len as usize / 2).min(buf.len());
+        let text = String::from_utf16_lossy(&buf[..chars]);
+        let text = text.trim_end_matches('\0').trim();
+        (!text.is_empty()).then(|| text.to_string())
+    };
+    (read("BIOSVersion"), read("BaseBoardProduct"))
+}
+
+#[cfg(not(windows))]
+fn read_bios_info() -> (Option<String>, Option<String>) {
+    (None, None)
+}
+
+/// Mainboard model from SMBIOS, e.g. to pick platform quirks
+pub fn mainboard() -> Option<String> {
+    read_bios_info().1
+}
+
+/// Highest command id sent to the EC itself; from 0x4000 up commands are forwarded to the
+/// PD controller, where a mistyped id can do real damage
+pub const MAX_RAW_COMMAND: u16 = 0x3FFF;
+// Commands that rewrite or reboot the EC, refused even inside the allowed range
+const BLOCKED_RAW_COMMANDS: &[(std::ops::RangeInclusive<u16>, &str)] = &[
+    // Flash info, read, write, erase, protect and the rest of the flash family
+    (0x0010..=0x0019, "EC_CMD_FLASH_*"),
+    (0x00D1..=0x00D1, "EC_CMD_REBOOT"),
+    (0x00D2..=0x00D2, "EC_CMD_REBOOT_EC"),
+];
+
+/// An EC host command typed in by the user, checked before anything is sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawCommand {
+    pub command: u16,
+    pub version: u8,
+    pub data: Vec<u8>,
+}
+
+impl RawCommand {
+    /// Parse `<command> [version] [payload hex...]`, e.g. `0x0002` or `0x3E0D 1 01 ff`.
+    /// The command is hex with a `0x` prefix or decimal; the payload may be split into
+    /// bytes or written as one hex string.
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let (command, rest) = args
+            .split_first()
+            .ok_or("Enter a command id, e.g. 0x0002")?;
+        let command = match command.strip_prefix("0x").or(command.strip_prefix("0X")) {
+            Some(hex) => u16::from_str_radix(hex, 16),
+            None => command.parse::<u16>(),
+        }
+        .map_err(|_| format!("Invalid command id '{}'", command))?;
+        if command > MAX_RAW_COMMAND {
+            return Err(format!(
+                "Command 0x{:04X} is outside 0x0000-0x{:04X}",
+                command, MAX_RAW_COMMAND
+            ));
+        }
+        if let Some((_, name)) = BLOCKED_RAW_COMMANDS
+            .iter()
+            .find(|(range, _)| range.contains(&command))
+        {
+            return Err(format!("Command 0x{:04X} ({}) is blocked", command, name));
+        }
+
+        let (version, payload) = match rest.split_first() {
+            Some((v, payload)) => {
+                let version = v
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|v| *v < 32)
+                    .ok_or_else(|| format!("Invalid version '{}' (0-31)", v))?;
+                (version, payload)
+            }
+            None => (0, rest),
+        };
+
+        let hex: String = payload
+            .iter()
+            .map(|p| p.trim_start_matches("0x").trim_start_matches("0X"))
+            .collect();
+        if !hex.len().is_multiple_of(2) {
+            return Err("Payload must be whole bytes (an even number of hex digits)".to_string());
+        }
+        let data = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| format!("Invalid hex payload '{}'", payload.join(" ")))?;
+        if data.len() > crate::ec::EC_MAX_PARAM_LEN {
+            return Err(format!(
+                "Payload is {} bytes; the EC takes at most {}",
+                data.len(),
+                crate::ec::EC_MAX_PARAM_LEN
+            ));
+        }
+        Ok(Self {
+            command,
+            version,
+            data,
+        })
+    }
+}
+
+const KEYBOARD_BACKLIGHT_UNSUPPORTED: &str =
+    "This EC has no keyboard backlight control (the keyboard may not be backlit)";
+
+/// Space-separated hex bytes, as shown for EC responses
+pub fn hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Main Framework laptop control interface, and the only one: every method goes through
+/// the CrosEC driver in `crate::ec`, run on the blocking pool, except power limits, which
+/// the EC has no command for and go through ryzenadj on AMD (see `crate::power_limits`).
+///
+/// | Method | EC access |
+/// |---|---|
+/// | `read_thermal` | memory map temps (0x00, 0x18) and fan RPMs (0x10); sensor names via `EC_CMD_TEMP_SENSOR_GET_INFO` (0x70) |
+/// | `read_power_info` | `GetSystemPowerStatus`, refined by memory map battery data |
+/// | `set_fan_duty` | `EC_CMD_PWM_SET_FAN_DUTY` (0x24), version 1 for a single fan |
+/// | `set_fan_control_auto` | `EC_CMD_THERMAL_AUTO_FAN_CTRL` (0x52) |
+/// | `set_tdp_watts` / `set_power_limits` | none; `ryzenadj --stapm/fast/slow-limit` |
+/// | `set_thermal_limit_c` | none; `ryzenadj --tctl-temp` |
+/// | `charge_limit_set` | `EC_CMD_CHARGE_LIMIT_CONTROL` (0x3E03), set-limit mode |
+/// | `charge_limit_get` | `EC_CMD_CHARGE_LIMIT_CONTROL` (0x3E03), get-limit mode |
+/// | `set_charge_current_limit` | `EC_CMD_CHARGE_CURRENT_LIMIT` (0xA1), read back via 0xA0 |
+/// | `get_keyboard_backlight` / `set_keyboard_backlight` | `EC_CMD_PWM_GET_KEYBOARD_BACKLIGHT` (0x22) / `EC_CMD_PWM_SET_KEYBOARD_BACKLIGHT` (0x23) |
+/// | `charge_rate_limit_ma` | battery memory map (design capacity, state of charge) |
+/// | `capabilities` | `EC_CMD_GET_CMD_VERSIONS` (0x08) per command |
+/// | `send_raw_command` / `run_macro` / `run_raw_command` | any command, as given |
 #[derive(Clone)]
 pub struct FrameworkTool;
 
@@ -39,13 +406,27 @@ impl FrameworkTool {
         Self
     }
 
+    /// EC build and BIOS version; whatever can't be read comes back as `Versions::UNKNOWN`
     pub async fn read_versions(&self) -> Result<Versions, String> {
-        // TODO: Read actual BIOS/EC versions from system
-        // For now, return placeholder since EC doesn't expose this easily
-        Ok(Versions {
-            ec_version: "3.06".to_string(),
-            bios_version: "3.09".to_string(),
+        if let Some(versions) = VERSIONS.get() {
+            return Ok(versions.clone());
+        }
+        tokio::task::spawn_blocking(|| {
+            let (bios_version, mainboard) = read_bios_info();
+            let versions = Versions {
+                ec_version: crate::ec::read_ec_version()
+                    .unwrap_or_else(|| Versions::UNKNOWN.to_string()),
+                bios_version: bios_version.unwrap_or_else(|| Versions::UNKNOWN.to_string()),
+                mainboard,
+            };
+            // A partial read is retried next time, e.g. once the EC driver is up
+            if versions.complete() {
+                let _ = VERSIONS.set(versions.clone());
+            }
+            versions
         })
+        .await
+        .map_err(|e| format!("Task error: {:?}", e))
     }
 
     pub async fn read_power_info(&self) -> Result<PowerBatteryInfo, String> {
@@ -84,26 +465,41 @@ impl FrameworkTool {
                         "Discharging"
                     };
 
-                    return Ok(PowerBatteryInfo {
+                    let mut info = PowerBatteryInfo {
                         charge_percent,
                         status: status_str.to_string(),
-                        capacity_current: 3500,
-                        capacity_design: 4000,
-                        voltage: 11.4,
-                        current: if is_charging { 2.5 } else { -2.5 },
-                    });
+                        capacity_current: 0,
+                        capacity_design: 0,
+                        voltage: None,
+                        current: None,
+                        battery_power_w: None,
+                        charger_input_w: None,
+                        system_power_w: None,
+                        // BATTERY_FLAG_CRITICAL; 255 means the status is unknown
+                        critical: status.battery_flag != 255 && status.battery_flag & 0x04 != 0,
+                        batteries: Vec::new(),
+                    };
+                    info.apply_ec_readings();
+                    return Ok(info);
                 }
             }
 
             // Fallback if API fails
-            Ok(PowerBatteryInfo {
+            let mut info = PowerBatteryInfo {
                 charge_percent: 100.0,
                 status: "Unknown".to_string(),
-                capacity_current: 3500,
-                capacity_design: 4000,
-                voltage: 11.4,
-                current: 0.0,
-            })
+                capacity_current: 0,
+                capacity_design: 0,
+                voltage: None,
+                current: None,
+                battery_power_w: None,
+                charger_input_w: None,
+                system_power_w: None,
+                critical: false,
+                batteries: Vec::new(),
+            };
+            info.apply_ec_readings();
+            Ok(info)
         })
         .await
         .map_err(|e| format!("Task error: {:?}", e))?
@@ -111,32 +507,53 @@ impl FrameworkTool {
 
     pub async fn read_thermal(&self) -> Result<ThermalParsed, String> {
         tokio::task::spawn_blocking(|| {
-            let temps = crate::ec::read_temps();
-            let fans = crate::ec::read_fans();
+            let raw_sensors = crate::ec::read_raw_temps();
+            let temps = crate::ec::decode_temps(&raw_sensors);
+            let readings = crate::ec::read_fans();
+            let stalled_fans = (0..readings.len())
+                .filter(|&fan| readings[fan].is_none())
+                .collect();
+            let fans = readings.iter().map(|rpm| rpm.unwrap_or(0.0)).collect();
 
-            const SENSOR_NAMES: &[&str] = &[
+            // Only for firmware that can't name its sensors
+            const FALLBACK_NAMES: &[&str] = &[
                 "CPU", "GPU", "Battery", "Charger", "Memory", "VRM", "Ambient", "SSD",
             ];
 
+            let offsets = SENSOR_OFFSETS.read().map(|o| o.clone()).unwrap_or_default();
             let sensors = temps
                 .into_iter()
-                .enumerate()
-                .map(|(i, temp_c)| ThermalSensor {
-                    name: SENSOR_NAMES.get(i).unwrap_or(&"Unknown").to_string(),
-                    temp_c,
+                .map(|(slot, temp_c)| {
+                    let name = crate::ec::sensor_name(slot).unwrap_or_else(|| {
+                        FALLBACK_NAMES
+                            .get(slot)
+                            .map_or(format!("Sensor {}", slot), |n| n.to_string())
+                    });
+                    let temp_c = temp_c + offsets.get(&name).copied().unwrap_or(0.0);
+                    ThermalSensor { name, temp_c }
                 })
                 .collect();
 
-            Ok(ThermalParsed { sensors, fans })
+            Ok(ThermalParsed {
+                sensors,
+                fans,
+                stalled_fans,
+                raw_sensors,
+            })
         })
         .await
         .map_err(|e| format!("Task error: {:?}", e))?
     }
 
-    pub async fn set_fan_duty(&self, percent: u32, _fan_index: Option<u32>) -> Result<(), String> {
+    /// Duty for every fan, or only fan `fan_index` when given
+    pub async fn set_fan_duty(&self, percent: u32, fan_index: Option<u32>) -> Result<(), String> {
         tokio::task::spawn_blocking(move || {
             println!("🌀 Setting fan duty to {}%", percent);
-            if crate::ec::set_fan_duty(percent) {
+            let ok = match fan_index {
+                Some(fan) => crate::ec::set_fan_duty_for(percent, fan as u8),
+                None => crate::ec::set_fan_duty(percent),
+            };
+            if ok {
                 println!("✅ Fan duty set successfully to {}%", percent);
                 Ok(())
             } else {
@@ -163,9 +580,10 @@ impl FrameworkTool {
         .map_err(|e| format!("Task error: {:?}", e))?
     }
 
-    pub async fn charge_limit_set(&self, max_pct: u8) -> Result<(), String> {
+    /// Charging stops at `max_pct` and resumes once the battery drops below `min_pct`
+    pub async fn charge_limit_set(&self, min_pct: u8, max_pct: u8) -> Result<(), String> {
         tokio::task::spawn_blocking(move || {
-            if crate::ec::set_charge_limit(max_pct) {
+            if crate::ec::set_charge_limit(min_pct, max_pct) {
                 Ok(())
             } else {
                 Err("Failed to set charge limit".to_string())
@@ -175,34 +593,231 @@ impl FrameworkTool {
         .map_err(|e| format!("Task error: {:?}", e))?
     }
 
-    #[allow(dead_code)]
+    /// Charge window the EC currently enforces, as (min_pct, max_pct); 100% max means no limit
     pub async fn charge_limit_get(&self) -> Result<(u8, u8), String> {
-        Ok((0, 100))
+        tokio::task::spawn_blocking(|| {
+            crate::ec::read_charge_limit().ok_or_else(|| "Failed to read charge limit".to_string())
+        })
+        .await
+        .map_err(|e| format!("Task error: {:?}", e))?
+    }
+
+    /// Keyboard backlight brightness in percent
+    pub async fn get_keyboard_backlight(&self) -> Result<u8, String> {
+        tokio::task::spawn_blocking(|| {
+            if !crate::ec::keyboard_backlight_supported() {
+                return Err(KEYBOARD_BACKLIGHT_UNSUPPORTED.to_string());
+            }
+            crate::ec::read_keyboard_backlight()
+                .ok_or_else(|| "Failed to read keyboard backlight".to_string())
+        })
+        .await
+        .map_err(|e| format!("Task error: {:?}", e))?
+    }
+
+    pub async fn set_keyboard_backlight(&self, level: u8) -> Result<(), String> {
+        tokio::task::spawn_blocking(move || {
+            if !crate::ec::keyboard_backlight_supported() {
+                Err(KEYBOARD_BACKLIGHT_UNSUPPORTED.to_string())
+            } else if crate::ec::set_keyboard_backlight(level) {
+                Ok(())
+            } else {
+                Err("Failed to set keyboard backlight".to_string())
+            }
+        })
+        .await
+        .map_err(|e| format!("Task error: {:?}", e))?
+    }
+
+    /// Probe the EC for every feature the app offers
+    pub async fn capabilities(&self) -> Result<Capabilities, String> {
+        tokio::task::spawn_blocking(|| {
+            use crate::ec;
+            let ec_connected = ec::check_connection().is_ok();
+            let supported = |cmd| ec_connected && ec::command_supported(cmd);
+            let current_limit = ec_connected && ec::charge_current_limit_supported();
+            let power_limits =
+                crate::power_limits::backend() != crate::power_limits::Backend::Unavailable;
+            Capabilities {
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+                ec_connected,
+                cpu: crate::cpu::brand_string().to_string(),
+                fan_count: if ec_connected {
+                    ec::read_fans().len()
+                } else {
+                    0
+                },
+                fan_duty_control: supported(ec::EC_CMD_FAN_DUTY),
+                per_fan_control: ec_connected && ec::per_fan_duty_supported(),
+                target_rpm_mode: false,
+                charge_limit: supported(ec::EC_CMD_CHARGE_LIMIT),
+                // Applied through the charge current limit
+                charge_rate_limit: current_limit,
+                charge_current_limit: current_limit,
+                keyboard_backlight: ec_connected && ec::keyboard_backlight_supported(),
+                tdp_control: power_limits,
+                thermal_limit_control: power_limits,
+            }
+        })
+        .await
+        .map_err(|e| format!("Task error: {:?}", e))
     }
 
-    #[allow(dead_code)]
-    pub async fn charge_rate_limit_set(
+    /// Return fans, charge limits and charge current to firmware control
+    pub async fn restore_firmware_defaults(&self) -> Result<Vec<crate::ec::ResetStep>, String> {
+        tokio::task::spawn_blocking(crate::ec::restore_firmware_defaults)
+            .await
+            .map_err(|e| format!("Task error: {:?}", e))
+    }
+
+    /// What TDP and thermal limits would be applied through
+    pub async fn power_limits_backend(&self) -> crate::power_limits::Backend {
+        tokio::task::spawn_blocking(crate::power_limits::backend)
+            .await
+            .unwrap_or(crate::power_limits::Backend::Unavailable)
+    }
+
+    /// Whether the EC accepts an absolute charge current limit
+    pub async fn charge_current_limit_supported(&self) -> bool {
+        tokio::task::spawn_blocking(crate::ec::charge_current_limit_supported)
+            .await
+            .unwrap_or(false)
+    }
+
+    /// Cap charge current at `limit_ma` (`None` removes the cap), then read back the
+    /// charger's programmed current to confirm the EC honours it
+    pub async fn set_charge_current_limit(&self, limit_ma: Option<u32>) -> Result<(), String> {
+        tokio::task::spawn_blocking(move || {
+            if !crate::ec::set_charge_current_limit(limit_ma.unwrap_or(u32::MAX)) {
+                return Err("Failed to set charge current limit".to_string());
+            }
+            let (Some(limit), Some((current_ma, true))) =
+                (limit_ma, crate::ec::read_charge_current_ma())
+            else {
+                // Nothing to check without a cap, or while not on AC
+                return Ok(());
+            };
+            // Allow for charger step size
+            if current_ma > limit + limit / 20 {
+                return Err(format!(
+                    "EC accepted a {} mA limit but the charger is still set to {} mA",
+                    limit, current_ma
+                ));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("Task error: {:?}", e))?
+    }
+
+    /// Charge current (mA) a `rate_c` limit works out to for the installed packs, or `None`
+    /// while the charge is still below `soc_threshold` and charging runs uncapped
+    pub async fn charge_rate_limit_ma(
         &self,
-        _rate_c: f32,
-        _soc_threshold: Option<u8>,
-    ) -> Result<(), String> {
-        Ok(())
+        rate_c: f32,
+        soc_threshold: Option<u8>,
+    ) -> Result<Option<u32>, String> {
+        tokio::task::spawn_blocking(move || {
+            let packs = crate::ec::read_batteries();
+            let design: u32 = packs.iter().map(|b| b.design_mah).sum();
+            if design == 0 {
+                return Err("Battery design capacity unknown".to_string());
+            }
+            if let Some(threshold) = soc_threshold {
+                let full: u32 = packs.iter().map(|b| b.last_full_mah).sum();
+                let remaining: u32 = packs.iter().map(|b| b.remaining_mah).sum();
+                if full > 0 && (remaining as f32 * 100.0 / full as f32) < threshold as f32 {
+                    return Ok(None);
+                }
+            }
+            Ok(Some((rate_c.clamp(0.0, 1.0) * design as f32) as u32))
+        })
+        .await
+        .map_err(|e| format!("Task error: {:?}", e))?
     }
 
-    pub async fn run_raw_command(&self, _args: Vec<String>) -> Result<String, String> {
-        Ok("Not supported".to_string())
+    /// Send one EC host command and return the response payload
+    pub async fn send_raw_command(
+        &self,
+        command: u16,
+        version: u8,
+        data: Vec<u8>,
+    ) -> Result<Vec<u8>, String> {
+        tokio::task::spawn_blocking(move || {
+            crate::ec::send_ec_command(command, version, &data).map_err(|e| format!("{:?}", e))
+        })
+        .await
+        .map_err(|e| format!("Task error: {:?}", e))?
     }
 
+    /// Run a macro's steps in order, stopping after the first one that fails
+    pub async fn run_macro(&self, ec_macro: &crate::types::EcMacro) -> MacroRun {
+        let mut results = Vec::new();
+        for step in &ec_macro.steps {
+            let result = self
+                .send_raw_command(step.command, step.version, step.data.clone())
+                .await;
+            let failed = result.is_err();
+            results.push(MacroStepResult {
+                command: step.command,
+                version: step.version,
+                result,
+            });
+            if failed {
+                break;
+            }
+            if let Some(ms) = step.delay_ms {
+                tokio::time::sleep(tokio::time::Duration::from_millis(ms)).await;
+            }
+        }
+        MacroRun {
+            name: ec_macro.name.clone(),
+            steps: results,
+        }
+    }
+
+    /// Parse and send a typed command (see [`RawCommand::parse`]), returning the response as hex
+    pub async fn run_raw_command(&self, args: Vec<String>) -> Result<String, String> {
+        let raw = RawCommand::parse(&args)?;
+        let resp = self
+            .send_raw_command(raw.command, raw.version, raw.data)
+            .await?;
+        if resp.is_empty() {
+            return Ok("OK (empty response)".to_string());
+        }
+        Ok(hex_bytes(&resp))
+    }
+
+    /// Sustained, fast and slow limits set to the same value
     pub async fn set_tdp_watts(&self, tdp: u32) -> Result<(), String> {
+        self.set_power_limits(tdp, tdp, tdp).await
+    }
+
+    /// Sustained (STAPM), fast and slow power limits in watts, through ryzenadj
+    pub async fn set_power_limits(&self, stapm: u32, fast: u32, slow: u32) -> Result<(), String> {
+        // Never pass a value the CPU can't sustain, whatever the caller asked for
+        let range = crate::cpu::tdp_range_watts();
+        let [stapm, fast, slow] =
+            [("STAPM", stapm), ("fast", fast), ("slow", slow)].map(|(name, watts)| {
+                let (watts, clamped) = crate::cpu::clamp_tdp(watts, &range);
+                if clamped {
+                    tracing::warn!(
+                        "Requested {} limit outside supported {}-{}W; clamped to {}W",
+                        name,
+                        range.start(),
+                        range.end(),
+                        watts
+                    );
+                }
+                watts
+            });
         tokio::task::spawn_blocking(move || {
-            println!("🔧 Setting TDP to {} watts", tdp);
-            if crate::ec::set_tdp_watts(tdp) {
-                println!("✅ TDP set successfully to {} watts", tdp);
-                Ok(())
-            } else {
-                println!("❌ Failed to set TDP to {} watts", tdp);
-                Err("Failed to set TDP".to_string())
+            let result = crate::power_limits::set_power_limits(stapm, fast, slow);
+            match &result {
+                Ok(()) => tracing::info!("TDP set to {}W (fast {}W, slow {}W)", stapm, fast, slow),
+                Err(e) => tracing::warn!("Failed to set TDP to {}W: {}", stapm, e),
             }
+            result
         })
         .await
         .map_err(|e| format!("Task error: {:?}", e))?
@@ -210,16 +825,77 @@ impl FrameworkTool {
 
     pub async fn set_thermal_limit_c(&self, thermal: u32) -> Result<(), String> {
         tokio::task::spawn_blocking(move || {
-            println!("🌡️ Setting thermal limit to {}°C", thermal);
-            if crate::ec::set_thermal_limit(thermal) {
-                println!("✅ Thermal limit set successfully to {}°C", thermal);
-                Ok(())
-            } else {
-                println!("❌ Failed to set thermal limit to {}°C", thermal);
-                Err("Failed to set thermal limit".to_string())
+            let result = crate::power_limits::set_thermal_limit_c(thermal);
+            match &result {
+                Ok(()) => tracing::info!("Thermal limit set to {}°C", thermal),
+                Err(e) => tracing::warn!("Failed to set thermal limit to {}°C: {}", thermal, e),
             }
+            result
         })
         .await
         .map_err(|e| format!("Task error: {:?}", e))?
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn battery(current: u32, design: u32) -> PowerBatteryInfo {
+        PowerBatteryInfo {
+            charge_percent: 50.0,
+            status: "Discharging".to_string(),
+            capacity_current: current,
+            capacity_design: design,
+            voltage: Some(15.4),
+            current: Some(-1.0),
+            battery_power_w: Some(-15.4),
+            charger_input_w: None,
+            system_power_w: Some(15.4),
+            critical: false,
+            batteries: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn health_is_unknown_for_zero_design_capacity() {
+        assert_eq!(battery(3500, 0).health_pct(), None);
+        assert_eq!(battery(0, 0).health_pct(), None);
+    }
+
+    #[test]
+    fn health_is_unknown_for_implausible_capacities() {
+        assert_eq!(battery(0, 3572).health_pct(), None);
+        assert_eq!(battery(3500, u32::MAX).health_pct(), None);
+    }
+
+    #[test]
+    fn health_is_capped_when_current_exceeds_design() {
+        assert_eq!(battery(3700, 3572).health_pct(), Some(100.0));
+    }
+
+    #[test]
+    fn health_is_ratio_of_full_to_design() {
+        let health = battery(3000, 4000).health_pct().unwrap();
+        assert!((health - 75.0).abs() < 0.01);
+    }
+
+    fn raw(args: &[&str]) -> Result<RawCommand, String> {
+        RawCommand::parse(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn raw_commands_refuse_the_flash_family() {
+        assert!(raw(&["0x0013"]).unwrap_err().contains("blocked"));
+        assert!(raw(&["0x0015", "0", "00"]).unwrap_err().contains("blocked"));
+        assert!(raw(&["0x00D2"]).is_err());
+        assert_eq!(
+            raw(&["0x0002"]),
+            Ok(RawCommand {
+                command: 0x0002,
+                version: 0,
+                data: Vec::new(),
+            })
+        );
+    }
+}
@@ -0,0 +1,158 @@
+// Headless subcommands (`framework-control set-fan 60`) for scripts and automation. They
+// drive the EC through the same `FrameworkTool` as the GUI, so a running GUI or service
+// will re-apply its own settings over anything set here.
+use clap::{CommandFactory, Parser, Subcommand};
+
+use crate::cli::FrameworkTool;
+
+#[derive(Parser)]
+#[command(
+    name = "framework-control",
+    version,
+    about = "Framework laptop fan, power and battery control. Run without a command to open the app."
+)]
+struct Cli {
+    /// Config file shared with the service
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<std::path::PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Set the fan duty in percent
+    SetFan {
+        #[arg(value_parser = clap::value_parser!(u32).range(0..=100))]
+        percent: u32,
+        /// Only this fan, counting from 0
+        #[arg(long)]
+        fan: Option<u32>,
+    },
+    /// Hand the fans back to the EC's automatic control
+    AutoFan,
+    /// Stop charging at this percentage; 100 removes the limit
+    SetChargeLimit {
+        #[arg(value_parser = clap::value_parser!(u8).range(50..=100))]
+        percent: u8,
+    },
+    /// Set the sustained power limit in watts, clamped to what the CPU supports
+    SetTdp { watts: u32 },
+    /// Set the CPU temperature limit in °C
+    SetThermalLimit { celsius: u32 },
+    /// Print temperatures and fan speeds
+    GetThermal {
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Print battery and power readings
+    GetPower {
+        #[command(flatten)]
+        output: Output,
+    },
+    /// Print EC and BIOS versions
+    GetVersions {
+        #[command(flatten)]
+        output: Output,
+    },
+}
+
+#[derive(clap::Args)]
+struct Output {
+    /// Print the reading as JSON, for status bars and monitoring scripts
+    #[arg(long)]
+    json: bool,
+}
+
+impl Output {
+    /// Print `value` as one line of JSON if asked to; false to print it as text instead
+    fn json(&self, value: &impl serde::Serialize) -> Result<bool, String> {
+        if self.json {
+            println!(
+                "{}",
+                serde_json::to_string(value).map_err(|e| e.to_string())?
+            );
+        }
+        Ok(self.json)
+    }
+}
+
+/// Whether `arg` names a subcommand, so `main` runs it instead of the GUI
+pub fn is_command(arg: &str) -> bool {
+    arg == "help" || Cli::command().find_subcommand(arg).is_some()
+}
+
+/// Parse `args` (including the program name) and run the command
+pub fn run(args: &[String]) -> Result<(), String> {
+    let cli = Cli::parse_from(args);
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    runtime.block_on(execute(cli.command))
+}
+
+async fn execute(command: Command) -> Result<(), String> {
+    let tool = FrameworkTool::new().await;
+    match command {
+        Command::SetFan { percent, fan } => tool.set_fan_duty(percent, fan).await,
+        Command::AutoFan => tool.set_fan_control_auto(None).await,
+        Command::SetChargeLimit { percent } => {
+            let (min, max) = crate::types::charge_window(percent, None);
+            tool.charge_limit_set(min, max).await
+        }
+        Command::SetTdp { watts } => tool.set_tdp_watts(watts).await,
+        Command::SetThermalLimit { celsius } => tool.set_thermal_limit_c(celsius).await,
+        Command::GetThermal { output } => {
+            let thermal = tool.read_thermal().await?;
+            if output.json(&thermal)? {
+                return Ok(());
+            }
+            for sensor in &thermal.sensors {
+                println!("{}: {:.1}°C", sensor.name, sensor.temp_c);
+            }
+            for (i, rpm) in thermal.fans.iter().enumerate() {
+                if thermal.stalled_fans.contains(&i) {
+                    println!("Fan {}: stalled", i + 1);
+                } else {
+                    println!("Fan {}: {:.0} RPM", i + 1, rpm);
+                }
+            }
+            Ok(())
+        }
+        Command::GetPower { output } => {
+            let power = tool.read_power_info().await?;
+            if output.json(&power)? {
+                return Ok(());
+            }
+            println!("Battery: {:.0}% ({})", power.charge_percent, power.status);
+            // Unknown when the EC battery block couldn't be read
+            let or_unknown = |text: Option<String>| text.unwrap_or_else(|| "unknown".to_string());
+            let voltage = power.voltage.map(|v| format!("{:.2} V", v));
+            println!("Voltage: {}", or_unknown(voltage));
+            let current = power.current.map(|a| format!("{:.2} A", a));
+            println!("Current: {}", or_unknown(current));
+            let battery = power.battery_power_w.map(|w| format!("{:+.1} W", w));
+            println!("Battery power: {}", or_unknown(battery));
+            if let Some(watts) = power.charger_input_w {
+                println!("Charger input: ≤{:.1} W (estimate)", watts);
+            }
+            if let Some(watts) = power.system_power_w {
+                println!("System power: {:.1} W", watts);
+            }
+            if let Some(health) = power.health_pct() {
+                println!("Health: {:.0}%", health);
+            }
+            Ok(())
+        }
+        Command::GetVersions { output } => {
+            let versions = tool.read_versions().await?;
+            if output.json(&versions)? {
+                return Ok(());
+            }
+            println!("EC: {}", versions.ec_version);
+            println!("BIOS: {}", versions.bios_version);
+            if let Some(mainboard) = &versions.mainboard {
+                println!("Mainboard: {}", mainboard);
+            }
+            Ok(())
+        }
+    }
+}
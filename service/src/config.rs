@@ -1,38 +1,145 @@
 use std::fs::{create_dir_all, File};
-use std::io::Read;
-use std::path::PathBuf;
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use crate::types::Config;
 
+/// Why the last save didn't land at `config_path()`; cleared by the next clean save
+static SAVE_PROBLEM: Mutex<Option<String>> = Mutex::new(None);
+
+/// The one config file the GUI and the service share:
+/// 1. `FRAMEWORK_CONTROL_CONFIG`, also set by `--config <path>`
+/// 2. `%PROGRAMDATA%\FrameworkControl\config.json`, the same for every account including
+///    the LocalSystem service
+/// 3. `%APPDATA%\FrameworkControl\config.json` where there is no ProgramData
 pub fn config_path() -> PathBuf {
     if let Ok(p) = std::env::var("FRAMEWORK_CONTROL_CONFIG") {
         return PathBuf::from(p);
     }
-    let appdata = std::env::var("APPDATA").unwrap_or_else(|_| ".".to_string());
-    PathBuf::from(appdata)
+    let base = std::env::var("PROGRAMDATA")
+        .or_else(|_| std::env::var("APPDATA"))
+        .unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(base)
         .join("FrameworkControl")
         .join("config.json")
 }
 
+/// Per-user file used before the config moved to ProgramData; read until the first save
+/// writes the shared one
+fn legacy_path() -> Option<PathBuf> {
+    let appdata = std::env::var("APPDATA").ok()?;
+    Some(
+        PathBuf::from(appdata)
+            .join("FrameworkControl")
+            .join("config.json"),
+    )
+}
+
+/// User-writable copy used when `config_path()` can't be written, e.g. when it sits in a
+/// shared directory owned by an elevated install
+pub fn fallback_path() -> PathBuf {
+    let base = std::env::var("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| std::env::temp_dir());
+    base.join("FrameworkControl").join("config.json")
+}
+
+/// The file the next load reads: a fallback written after the primary holds changes the
+/// primary couldn't take
+fn current_path() -> PathBuf {
+    let primary = config_path();
+    let fallback = fallback_path();
+    if fallback != primary && modified(&fallback) > modified(&primary) {
+        return fallback;
+    }
+    match legacy_path() {
+        Some(legacy) if !primary.exists() && legacy.exists() => legacy,
+        _ => primary,
+    }
+}
+
 pub fn load() -> Config {
-    let path = config_path();
-    if let Ok(mut f) = File::open(&path) {
-        let mut buf = String::new();
-        if f.read_to_string(&mut buf).is_ok() {
-            if let Ok(cfg) = serde_json::from_str::<Config>(&buf) {
-                return cfg;
-            }
-        }
+    try_load().unwrap_or_default()
+}
+
+/// Like `load`, but reports a missing or unreadable file instead of using defaults, e.g.
+/// after a hand edit left invalid JSON
+pub fn try_load() -> Result<Config, String> {
+    let path = current_path();
+    let mut buf = String::new();
+    File::open(&path)
+        .and_then(|mut f| f.read_to_string(&mut buf))
+        .map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+    let mut cfg = serde_json::from_str::<Config>(&buf)
+        .map_err(|e| format!("Can't parse {}: {}", path.display(), e))?;
+    // A hand edit can leave a curve that would divide by zero between points
+    for fix in cfg.fan.validate_and_normalize_curves() {
+        tracing::warn!("{} in {}", fix, path.display());
     }
-    Config::default()
+    Ok(cfg)
+}
+
+/// When the config was last written, to notice edits made by another process
+pub fn last_modified() -> Option<std::time::SystemTime> {
+    modified(&current_path())
+}
+
+fn modified(path: &Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Write to a sibling temp file and rename it over `path`, so a crash, a full disk or a
+/// reader polling the file never sees half a config
+fn write(path: &Path, json: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    let result = File::create(&tmp)
+        .and_then(|mut f| {
+            f.write_all(json.as_bytes())?;
+            f.sync_all()
+        })
+        .and_then(|()| std::fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
 }
 
 pub fn save(cfg: &Config) {
+    let Ok(json) = serde_json::to_string_pretty(cfg) else {
+        return;
+    };
     let path = config_path();
-    if let Some(parent) = path.parent() {
-        let _ = create_dir_all(parent);
+    let problem = match write(&path, &json) {
+        Ok(()) => None,
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+            let fallback = fallback_path();
+            match write(&fallback, &json) {
+                Ok(()) => Some(format!(
+                    "{} is not writable (it may be owned by another process or an elevated install); settings were saved to {} instead",
+                    path.display(),
+                    fallback.display()
+                )),
+                Err(_) => Some(format!(
+                    "Can't save settings: {} is not writable (it may be owned by another process or an elevated install). Run as the owner to change them.",
+                    path.display()
+                )),
+            }
+        }
+        Err(e) => Some(format!("Can't save settings to {}: {}", path.display(), e)),
+    };
+    if let Some(problem) = &problem {
+        tracing::warn!("{}", problem);
     }
-    if let Ok(json) = serde_json::to_string_pretty(cfg) {
-        let _ = std::fs::write(&path, json);
+    if let Ok(mut guard) = SAVE_PROBLEM.lock() {
+        *guard = problem;
     }
-}
\ No newline at end of file
+}
+
+/// Describes why the most recent save didn't reach the main config file, if it didn't
+pub fn save_problem() -> Option<String> {
+    SAVE_PROBLEM.lock().ok().and_then(|p| p.clone())
+}
@@ -0,0 +1,222 @@
+// CPU identification and the TDP range it supports
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+
+/// Used when the CPU is not in the table; matches the GUI slider bounds
+pub const DEFAULT_TDP_RANGE_W: RangeInclusive<u32> = 5..=28;
+
+// Brand substring -> supported sustained power range (W), most specific first
+const TDP_TABLE: &[(&str, RangeInclusive<u32>)] = &[
+    ("Ryzen AI 9", 15..=54),
+    ("Ryzen AI 7", 15..=54),
+    ("Ryzen AI 5", 15..=54),
+    ("7940HS", 35..=54),
+    ("7840HS", 35..=54),
+    ("7840U", 15..=30),
+    ("7640U", 15..=30),
+    ("Core Ultra", 15..=64),
+    ("i7-1370P", 20..=64),
+    ("i5-1340P", 20..=64),
+    ("i7-1280P", 20..=64),
+    ("i5-1240P", 20..=64),
+];
+
+static BRAND: OnceLock<String> = OnceLock::new();
+
+/// CPU brand string from CPUID, e.g. "AMD Ryzen 7 7840U w/ Radeon 780M Graphics"
+pub fn brand_string() -> &'static str {
+    BRAND.get_or_init(read_brand_string)
+}
+
+#[cfg(target_arch = "x86_64")]
+fn read_brand_string() -> String {
+    use std::arch::x86_64::__cpuid;
+    let max_ext = __cpuid(0x8000_0000).eax;
+    if max_ext < 0x8000_0004 {
+        return String::new();
+    }
+    let mut bytes = Vec::with_capacity(48);
+    for leaf in 0x8000_0002u32..=0x8000_0004 {
+        let r = __cpuid(leaf);
+        for reg in [r.eax, r.ebx, r.ecx, r.edx] {
+            bytes.extend_from_slice(&reg.to_le_bytes());
+        }
+    }
+    String::from_utf8_lossy(&bytes)
+        .trim_matches(char::from(0))
+        .trim()
+        .to_string()
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn read_brand_string() -> String {
+    String::new()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vendor {
+    Amd,
+    Intel,
+    Other,
+}
+
+impl Vendor {
+    fn from_id(id: &str) -> Self {
+        match id {
+            "AuthenticAMD" => Vendor::Amd,
+            "GenuineIntel" => Vendor::Intel,
+            _ => Vendor::Other,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Vendor::Amd => "AMD",
+            Vendor::Intel => "Intel",
+            Vendor::Other => "unrecognised CPU",
+        }
+    }
+}
+
+static VENDOR: OnceLock<Vendor> = OnceLock::new();
+
+/// CPU maker from the CPUID vendor string, falling back to the brand string
+pub fn vendor() -> Vendor {
+    *VENDOR.get_or_init(|| match Vendor::from_id(&read_vendor_id()) {
+        Vendor::Other if brand_string().starts_with("AMD") => Vendor::Amd,
+        Vendor::Other if brand_string().contains("Intel") => Vendor::Intel,
+        v => v,
+    })
+}
+
+#[cfg(target_arch = "x86_64")]
+fn read_vendor_id() -> String {
+    use std::arch::x86_64::__cpuid;
+    let r = __cpuid(0);
+    let mut bytes = Vec::with_capacity(12);
+    for reg in [r.ebx, r.edx, r.ecx] {
+        bytes.extend_from_slice(&reg.to_le_bytes());
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn read_vendor_id() -> String {
+    String::new()
+}
+
+pub fn tdp_range_for(brand: &str) -> RangeInclusive<u32> {
+    TDP_TABLE
+        .iter()
+        .find(|(needle, _)| brand.contains(needle))
+        .map(|(_, range)| range.clone())
+        .unwrap_or(DEFAULT_TDP_RANGE_W)
+}
+
+/// Supported TDP range for the CPU in this machine
+pub fn tdp_range_watts() -> RangeInclusive<u32> {
+    tdp_range_for(brand_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CpuClocks {
+    /// Average current clock across all logical processors
+    pub avg_mhz: f32,
+    /// Highest rated clock reported for any processor
+    pub max_mhz: f32,
+}
+
+/// Current CPU clocks as reported by the Windows power manager
+pub fn clocks() -> Option<CpuClocks> {
+    #[cfg(windows)]
+    {
+        #[repr(C)]
+        #[derive(Clone, Copy, Default)]
+        #[allow(non_camel_case_types, dead_code)]
+        struct PROCESSOR_POWER_INFORMATION {
+            number: u32,
+            max_mhz: u32,
+            current_mhz: u32,
+            mhz_limit: u32,
+            max_idle_state: u32,
+            current_idle_state: u32,
+        }
+
+        #[link(name = "powrprof")]
+        extern "system" {
+            fn CallNtPowerInformation(
+                information_level: i32,
+                input_buffer: *const std::ffi::c_void,
+                input_buffer_length: u32,
+                output_buffer: *mut std::ffi::c_void,
+                output_buffer_length: u32,
+            ) -> i32;
+        }
+        const PROCESSOR_INFORMATION: i32 = 11;
+
+        let count = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let mut info = vec![PROCESSOR_POWER_INFORMATION::default(); count];
+        let len = std::mem::size_of_val(info.as_slice()) as u32;
+        let status = unsafe {
+            CallNtPowerInformation(
+                PROCESSOR_INFORMATION,
+                std::ptr::null(),
+                0,
+                info.as_mut_ptr().cast(),
+                len,
+            )
+        };
+        if status != 0 {
+            return None;
+        }
+        let avg_mhz = info.iter().map(|p| p.current_mhz as f32).sum::<f32>() / count as f32;
+        let max_mhz = info.iter().map(|p| p.max_mhz).max()? as f32;
+        (avg_mhz > 0.0).then_some(CpuClocks { avg_mhz, max_mhz })
+    }
+    #[cfg(not(windows))]
+    {
+        None
+    }
+}
+
+/// Clamp a requested TDP into `range`; the flag is true when the value was changed
+pub fn clamp_tdp(requested: u32, range: &RangeInclusive<u32>) -> (u32, bool) {
+    let clamped = requested.clamp(*range.start(), *range.end());
+    (clamped, clamped != requested)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_keeps_values_inside_range() {
+        let range = 15..=30;
+        assert_eq!(clamp_tdp(15, &range), (15, false));
+        assert_eq!(clamp_tdp(22, &range), (22, false));
+        assert_eq!(clamp_tdp(30, &range), (30, false));
+    }
+
+    #[test]
+    fn clamp_limits_values_outside_range() {
+        let range = 15..=30;
+        assert_eq!(clamp_tdp(14, &range), (15, true));
+        assert_eq!(clamp_tdp(0, &range), (15, true));
+        assert_eq!(clamp_tdp(31, &range), (30, true));
+        assert_eq!(clamp_tdp(u32::MAX, &range), (30, true));
+    }
+
+    #[test]
+    fn unknown_cpu_uses_conservative_default() {
+        assert_eq!(tdp_range_for(""), DEFAULT_TDP_RANGE_W);
+        assert_eq!(tdp_range_for("AMD Ryzen 7 7840U w/ Radeon 780M Graphics"), 15..=30);
+        assert_eq!(tdp_range_for("AMD Ryzen 9 7940HS w/ Radeon 780M Graphics"), 35..=54);
+    }
+
+    #[test]
+    fn vendor_ids_map_to_makers() {
+        assert_eq!(Vendor::from_id("AuthenticAMD"), Vendor::Amd);
+        assert_eq!(Vendor::from_id("GenuineIntel"), Vendor::Intel);
+        assert_eq!(Vendor::from_id("HygonGenuine"), Vendor::Other);
+    }
+}
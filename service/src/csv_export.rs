@@ -0,0 +1,110 @@
+// Telemetry history as CSV for spreadsheets and plotting tools: one row per sample,
+// one column per temperature sensor and fan
+use crate::types::TelemetrySample;
+
+/// UTC ISO-8601 timestamp with milliseconds, e.g. `2024-05-01T12:34:56.789Z`
+pub fn iso8601(ts_ms: i64) -> String {
+    let (days, ms) = (ts_ms.div_euclid(86_400_000), ts_ms.rem_euclid(86_400_000));
+    // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+// Quote a field only when it would otherwise break the row
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The whole history with a header row. Sensors and fans missing from a sample leave
+/// their cells empty, as do readings that weren't available.
+pub fn telemetry_csv(samples: &[TelemetrySample]) -> String {
+    // Every sensor seen, in the order it first appeared
+    let mut sensors: Vec<&str> = Vec::new();
+    for sample in samples {
+        for (name, _) in &sample.temps {
+            if !sensors.contains(&name.as_str()) {
+                sensors.push(name);
+            }
+        }
+    }
+    let fans = samples.iter().map(|s| s.fan_rpms.len()).max().unwrap_or(0);
+
+    let mut header = vec!["timestamp".to_string()];
+    header.extend(sensors.iter().map(|name| field(&format!("{} (°C)", name))));
+    header.extend((1..=fans).map(|i| format!("Fan {} (RPM)", i)));
+    header.extend(
+        [
+            "Battery (%)",
+            "Charging",
+            "Charger est. (W)",
+            "Battery (W)",
+            "TDP (W)",
+        ]
+        .map(String::from),
+    );
+
+    let mut csv = header.join(",") + "\n";
+    for sample in samples {
+        let mut row = vec![iso8601(sample.ts_ms)];
+        row.extend(sensors.iter().map(|name| {
+            sample
+                .temps
+                .iter()
+                .find(|(n, _)| n == name)
+                .map_or(String::new(), |(_, t)| format!("{:.1}", t))
+        }));
+        row.extend((0..fans).map(|i| {
+            sample
+                .fan_rpms
+                .get(i)
+                .map_or(String::new(), |rpm| format!("{:.0}", rpm))
+        }));
+        row.push(
+            sample
+                .battery_percent
+                .map_or(String::new(), |p| format!("{:.1}", p)),
+        );
+        row.push(sample.charging.map_or(String::new(), |c| c.to_string()));
+        for watts in [sample.charger_w, sample.battery_w] {
+            row.push(watts.map_or(String::new(), |w| format!("{:.1}", w)));
+        }
+        row.push(sample.tdp_w.map_or(String::new(), |w| w.to_string()));
+        csv += &row.join(",");
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_format_as_utc() {
+        assert_eq!(iso8601(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(iso8601(951_782_400_000), "2000-02-29T00:00:00.000Z");
+        assert_eq!(iso8601(1_700_000_000_123), "2023-11-14T22:13:20.123Z");
+        assert_eq!(iso8601(-1), "1969-12-31T23:59:59.999Z");
+    }
+}
@@ -0,0 +1,458 @@
+// Fan curve evaluation shared by the GUI, the background task and previews
+use crate::types::{FanCurve, Interpolation, TelemetrySample};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Duty used when the curve has no points at all
+pub const DEFAULT_DUTY: f32 = 50.0;
+
+/// Duty (%) for `temp` on a curve of (temp °C, duty %) points, filled in between the
+/// points according to `mode`. Every mode passes through the points.
+///
+/// Points may be in any order. Below the first point and above the last the curve is flat.
+/// Points sharing a temperature form a step: at and above that temperature the later
+/// point's duty applies, so a zero-width segment never divides by zero.
+pub fn interpolate(points: &[(f32, f32)], temp: f32, mode: Interpolation) -> f32 {
+    if mode == Interpolation::Linear {
+        return curve_duty(points, temp);
+    }
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (Some(first), Some(last)) = (sorted.first(), sorted.last()) else {
+        return DEFAULT_DUTY;
+    };
+
+    // Index of the first point strictly above `temp`; everything before it is at or below
+    let upper = sorted.partition_point(|p| p.0 <= temp);
+    if upper == 0 {
+        return first.1;
+    }
+    if upper == sorted.len() {
+        return last.1;
+    }
+    let (t1, d1) = sorted[upper - 1];
+    let (t2, d2) = sorted[upper];
+    // t1 <= temp < t2, so the segment always has a width
+    let width = t2 - t1;
+    let t = (temp - t1) / width;
+    let duty = match mode {
+        Interpolation::Linear => unreachable!("linear curves return through curve_duty"),
+        Interpolation::Smoothstep => d1 + (d2 - d1) * t * t * (3.0 - 2.0 * t),
+        Interpolation::CatmullRom => {
+            // Neighbours beyond the ends repeat the end point, which flattens the tangent
+            let (t0, d0) = if upper >= 2 {
+                sorted[upper - 2]
+            } else {
+                (t1, d1)
+            };
+            let (t3, d3) = sorted.get(upper + 1).copied().unwrap_or((t2, d2));
+            // Tangents in duty per segment, allowing for unevenly spaced temperatures
+            let m1 = (d2 - d0) / (t2 - t0) * width;
+            let m2 = (d3 - d1) / (t3 - t1) * width;
+            let (t2p, t3p) = (t * t, t * t * t);
+            (2.0 * t3p - 3.0 * t2p + 1.0) * d1
+                + (t3p - 2.0 * t2p + t) * m1
+                + (-2.0 * t3p + 3.0 * t2p) * d2
+                + (t3p - t2p) * m2
+        }
+    };
+    // The spline can overshoot between points
+    duty.clamp(0.0, 100.0)
+}
+
+/// Duty (%) for `temp` on a curve of (temp °C, duty %) points, straight lines between
+/// them; what [`interpolate`] does for [`Interpolation::Linear`]
+pub fn curve_duty(points: &[(f32, f32)], temp: f32) -> f32 {
+    lerp_table(points, temp).map_or(DEFAULT_DUTY, |duty| duty.clamp(0.0, 100.0))
+}
+
+/// Straight-line lookup in any `(x, y)` table, flat beyond the ends like a curve but not
+/// limited to 0-100, so it serves RPM and loudness maps. `None` for an empty table.
+pub fn lerp_table(points: &[(f32, f32)], x: f32) -> Option<f32> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let upper = sorted.partition_point(|p| p.0 <= x);
+    if upper == 0 || upper == sorted.len() {
+        return sorted.get(upper.saturating_sub(1)).map(|p| p.1);
+    }
+    let (x1, y1) = sorted[upper - 1];
+    let (x2, y2) = sorted[upper];
+    Some(y1 + (y2 - y1) * (x - x1) / (x2 - x1))
+}
+
+/// At or above this the duty cap no longer applies and the fans run at full speed
+pub const EMERGENCY_TEMP_C: f32 = 95.0;
+
+/// Apply the user's duty cap to the controller output, unless `temp` is at the emergency cutoff
+pub fn cap_duty(duty: f32, max_duty_pct: Option<u32>, temp: f32) -> f32 {
+    if temp >= EMERGENCY_TEMP_C {
+        return 100.0;
+    }
+    match max_duty_pct {
+        Some(cap) => duty.min(cap as f32),
+        None => duty,
+    }
+}
+
+/// Same as [`interpolate`] for config-style `[temp, duty]` points
+pub fn curve_duty_u32(points: &[[u32; 2]], temp: f32, mode: Interpolation) -> f32 {
+    let points: Vec<(f32, f32)> = points.iter().map(|p| (p[0] as f32, p[1] as f32)).collect();
+    interpolate(&points, temp, mode)
+}
+
+/// Duty per fan at `temp`. Fans with their own curve use it and the rest follow `shared`;
+/// with no per-fan curves (or a single fan) one entry with `None` covers every fan.
+pub fn fan_duties(
+    shared: &[[u32; 2]],
+    per_fan: &[FanCurve],
+    fan_count: usize,
+    temp: f32,
+    mode: Interpolation,
+) -> Vec<(Option<u32>, f32)> {
+    if per_fan.is_empty() || fan_count <= 1 {
+        return vec![(None, curve_duty_u32(shared, temp, mode))];
+    }
+    (0..fan_count as u32)
+        .map(|fan| {
+            let points = per_fan
+                .iter()
+                .find(|c| c.fan == fan)
+                .map_or(shared, |c| &c.points);
+            (Some(fan), curve_duty_u32(points, temp, mode))
+        })
+        .collect()
+}
+
+/// Holds the duty for a while after it rises, so a temperature hovering around a curve
+/// point can't ramp the fan up and down every poll
+#[derive(Debug, Default)]
+pub struct Dwell {
+    raised_at: Option<Instant>,
+}
+
+impl Dwell {
+    /// Duty to aim for given the `last` applied one: rises pass straight through and restart
+    /// the hold, drops keep `last` until `min_dwell` has passed since the latest rise
+    pub fn apply(&mut self, last: Option<f32>, target: f32, min_dwell: Duration) -> f32 {
+        let Some(last) = last else {
+            return target;
+        };
+        if target > last {
+            self.raised_at = Some(Instant::now());
+            return target;
+        }
+        match self.raised_at {
+            Some(at) if target < last && at.elapsed() < min_dwell => last,
+            _ => target,
+        }
+    }
+}
+
+/// Seconds without a successful fan command before the watchdog trips, unless configured
+pub const DEFAULT_WATCHDOG_S: u64 = 30;
+/// Slack on top of two curve polls, covering the watchdog's own poll
+const WATCHDOG_MARGIN: Duration = Duration::from_secs(5);
+
+/// Time without a successful fan command before the watchdog trips, `None` when disabled.
+/// Never shorter than two curve polls, so a slow poll isn't mistaken for a hang.
+pub fn watchdog_timeout(watchdog_secs: Option<u64>, curve_poll: Duration) -> Option<Duration> {
+    match watchdog_secs.unwrap_or(DEFAULT_WATCHDOG_S) {
+        0 => None,
+        secs => Some(Duration::from_secs(secs).max(curve_poll * 2 + WATCHDOG_MARGIN)),
+    }
+}
+
+/// What the watchdog should do after one check, see [`watchdog_step`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogStep {
+    Healthy,
+    /// Fan commands succeed again after a trip
+    Recovered,
+    /// Hand the fans to EC auto control
+    Trip,
+    /// Already handed over; nothing more to do
+    Tripped,
+}
+
+/// Judge the time since the last successful fan command against the timeout
+pub fn watchdog_step(stale: Duration, timeout: Option<Duration>, tripped: bool) -> WatchdogStep {
+    let late = timeout.is_some_and(|t| stale >= t);
+    match (late, tripped) {
+        (false, false) => WatchdogStep::Healthy,
+        (false, true) => WatchdogStep::Recovered,
+        (true, false) => WatchdogStep::Trip,
+        (true, true) => WatchdogStep::Tripped,
+    }
+}
+
+/// Consecutive polls without a usable temperature before the fallback duty is forced
+pub const EMPTY_POLLS_BEFORE_FALLBACK: u32 = 3;
+/// Duty forced while no sensor data is usable, unless configured
+pub const DEFAULT_FALLBACK_DUTY: u32 = 40;
+
+/// What the controller should do after one poll, see [`SensorFallback::update`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FallbackStep {
+    /// Drive the curve from this temperature
+    Curve(f32),
+    /// No usable data yet, but not for long enough to act; leave the fan alone
+    Hold,
+    /// Force the fallback duty; `engaged` is true on the poll that switched over
+    Fallback { engaged: bool },
+}
+
+/// Tracks polls that returned no usable temperature so the curve can't silently stall
+#[derive(Debug, Default)]
+pub struct SensorFallback {
+    empty_polls: u32,
+}
+
+impl SensorFallback {
+    /// Feed the hottest reading of a poll (`None` or non-finite when there was nothing usable)
+    pub fn update(&mut self, hottest: Option<f32>) -> FallbackStep {
+        if let Some(temp) = hottest.filter(|t| t.is_finite()) {
+            self.empty_polls = 0;
+            return FallbackStep::Curve(temp);
+        }
+        self.empty_polls = self.empty_polls.saturating_add(1);
+        match self.empty_polls {
+            n if n < EMPTY_POLLS_BEFORE_FALLBACK => FallbackStep::Hold,
+            n => FallbackStep::Fallback {
+                engaged: n == EMPTY_POLLS_BEFORE_FALLBACK,
+            },
+        }
+    }
+
+    pub fn active(&self) -> bool {
+        self.empty_polls >= EMPTY_POLLS_BEFORE_FALLBACK
+    }
+}
+
+/// Samples needed before suggesting a curve (about two minutes of history)
+pub const MIN_SUGGEST_SAMPLES: usize = 60;
+// Width of the temperature bands the history is grouped into
+const BAND_C: f32 = 5.0;
+// Heating slower than this (°C/s) counts as the fan holding the temperature
+const HOLDING_SLOPE_C_PER_S: f32 = 0.02;
+// Longer gaps between samples are treated as a break in the history
+const MAX_SAMPLE_GAP_S: f32 = 30.0;
+
+/// Suggest curve points from telemetry history that keep temperatures at or below
+/// `target_max_c` with as little fan as the history shows is enough.
+///
+/// For each 5°C band the quietest duty that still held the temperature (not heating up)
+/// becomes the duty for that band. Bands are then made non-decreasing, the target gets at
+/// least the duty that held the hottest observed band, and the curve reaches 100% one band
+/// above the target so temperatures can't run away past it.
+pub fn suggest_curve(
+    samples: &[TelemetrySample],
+    target_max_c: f32,
+) -> Result<Vec<[u32; 2]>, String> {
+    // (time, hottest sensor, duty) while the app was driving the fans
+    let history: Vec<(i64, f32, u32)> = samples
+        .iter()
+        .filter_map(|s| {
+            let duty = s.fan_duty_pct?;
+            let temp = s.temps.iter().map(|t| t.1).reduce(f32::max)?;
+            Some((s.ts_ms, temp, duty))
+        })
+        .collect();
+    if history.len() < MIN_SUGGEST_SAMPLES {
+        return Err(format!(
+            "Need at least {} samples with the app controlling the fans (have {})",
+            MIN_SUGGEST_SAMPLES,
+            history.len()
+        ));
+    }
+
+    let mut holding: BTreeMap<i32, u32> = BTreeMap::new();
+    for w in history.windows(2) {
+        let (t0, temp0, duty) = w[0];
+        let (t1, temp1, _) = w[1];
+        let dt = (t1 - t0) as f32 / 1000.0;
+        if dt <= 0.0 || dt > MAX_SAMPLE_GAP_S {
+            continue;
+        }
+        if (temp1 - temp0) / dt > HOLDING_SLOPE_C_PER_S {
+            continue;
+        }
+        let band = (temp0 / BAND_C).floor() as i32;
+        holding
+            .entry(band)
+            .and_modify(|d| *d = (*d).min(duty))
+            .or_insert(duty);
+    }
+    if holding.is_empty() {
+        return Err(
+            "No stable periods in the history yet; let the machine run a while".to_string(),
+        );
+    }
+
+    let target_band = (target_max_c / BAND_C).floor() as i32;
+    let mut points: Vec<[u32; 2]> = Vec::new();
+    let mut floor = 0;
+    for (band, duty) in holding.range(..target_band) {
+        // Never spin slower at a higher temperature
+        floor = floor.max(*duty);
+        if points.last().is_some_and(|p| p[1] == floor) {
+            continue;
+        }
+        points.push([(*band as f32 * BAND_C).max(0.0) as u32, floor]);
+    }
+
+    // Quietest duty seen holding the hottest bands; if the history never got that hot,
+    // go halfway to full speed rather than guess low
+    let hot_duty = holding
+        .range(target_band..)
+        .map(|(_, d)| *d)
+        .min()
+        .unwrap_or((floor + 100) / 2);
+    let target_c = (target_band as f32 * BAND_C) as u32;
+    points.push([target_c, hot_duty.max(floor).min(100)]);
+    points.push([target_c + BAND_C as u32, 100]);
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_adjacent_temperatures_step_to_the_later_point() {
+        let points = [(50.0, 20.0), (60.0, 30.0), (60.0, 80.0), (70.0, 90.0)];
+        assert_eq!(interpolate(&points, 55.0, Interpolation::Linear), 25.0);
+        assert_eq!(
+            interpolate(&points, 59.9, Interpolation::Linear).round(),
+            30.0
+        );
+        assert_eq!(interpolate(&points, 60.0, Interpolation::Linear), 80.0);
+        assert_eq!(interpolate(&points, 65.0, Interpolation::Linear), 85.0);
+        for t in 0..=100 {
+            assert!(interpolate(&points, t as f32, Interpolation::Linear).is_finite());
+        }
+    }
+
+    const POINTS: [(f32, f32); 3] = [(40.0, 20.0), (60.0, 40.0), (80.0, 100.0)];
+
+    #[test]
+    fn curve_duty_is_flat_outside_the_points() {
+        assert_eq!(curve_duty(&POINTS, 20.0), 20.0);
+        assert_eq!(curve_duty(&POINTS, 95.0), 100.0);
+    }
+
+    #[test]
+    fn curve_duty_passes_through_each_point() {
+        for (temp, duty) in POINTS {
+            assert_eq!(curve_duty(&POINTS, temp), duty);
+        }
+    }
+
+    #[test]
+    fn curve_duty_interpolates_between_points() {
+        assert_eq!(curve_duty(&POINTS, 50.0), 30.0);
+        assert_eq!(curve_duty(&POINTS, 70.0), 70.0);
+        let reversed: Vec<(f32, f32)> = POINTS.iter().rev().copied().collect();
+        assert_eq!(curve_duty(&reversed, 70.0), 70.0);
+    }
+
+    #[test]
+    fn curve_duty_with_one_point_is_constant() {
+        for temp in [0.0, 55.0, 100.0] {
+            assert_eq!(curve_duty(&[(55.0, 35.0)], temp), 35.0);
+        }
+    }
+
+    #[test]
+    fn curve_duty_without_points_uses_default() {
+        assert_eq!(curve_duty(&[], 60.0), DEFAULT_DUTY);
+    }
+
+    #[test]
+    fn table_lookup_is_not_limited_to_duty_range() {
+        let rpm = [(30.0, 2000.0), (60.0, 4000.0)];
+        assert_eq!(lerp_table(&rpm, 45.0), Some(3000.0));
+        assert_eq!(lerp_table(&rpm, 10.0), Some(2000.0));
+        assert_eq!(lerp_table(&rpm, 90.0), Some(4000.0));
+        assert_eq!(lerp_table(&[], 45.0), None);
+    }
+
+    #[test]
+    fn duty_cap_is_respected_below_emergency() {
+        assert_eq!(cap_duty(90.0, Some(70), 80.0), 70.0);
+        assert_eq!(cap_duty(50.0, Some(70), 80.0), 50.0);
+        assert_eq!(cap_duty(90.0, None, 80.0), 90.0);
+        assert_eq!(cap_duty(100.0, Some(70), EMERGENCY_TEMP_C - 0.1), 70.0);
+    }
+
+    #[test]
+    fn emergency_overrides_duty_cap() {
+        assert_eq!(cap_duty(60.0, Some(70), EMERGENCY_TEMP_C), 100.0);
+        assert_eq!(cap_duty(90.0, Some(40), 102.0), 100.0);
+    }
+
+    #[test]
+    fn empty_sensor_data_falls_back_then_recovers() {
+        let mut fallback = SensorFallback::default();
+        for _ in 1..EMPTY_POLLS_BEFORE_FALLBACK {
+            assert_eq!(fallback.update(None), FallbackStep::Hold);
+        }
+        assert_eq!(
+            fallback.update(Some(f32::NEG_INFINITY)),
+            FallbackStep::Fallback { engaged: true }
+        );
+        assert_eq!(
+            fallback.update(None),
+            FallbackStep::Fallback { engaged: false }
+        );
+        assert!(fallback.active());
+
+        assert_eq!(fallback.update(Some(55.0)), FallbackStep::Curve(55.0));
+        assert!(!fallback.active());
+        assert_eq!(fallback.update(None), FallbackStep::Hold);
+    }
+
+    #[test]
+    fn all_points_at_one_temperature_never_produce_nan() {
+        let points = [(60.0, 10.0), (60.0, 90.0)];
+        assert_eq!(interpolate(&points, 40.0, Interpolation::Linear), 10.0);
+        assert_eq!(interpolate(&points, 60.0, Interpolation::Linear), 90.0);
+        assert_eq!(interpolate(&points, 80.0, Interpolation::Linear), 90.0);
+    }
+
+    #[test]
+    fn watchdog_timeout_covers_slow_polls() {
+        let poll = Duration::from_secs(2);
+        assert_eq!(watchdog_timeout(None, poll), Some(Duration::from_secs(30)));
+        assert_eq!(watchdog_timeout(Some(0), poll), None);
+        let slow = Duration::from_secs(45);
+        assert_eq!(watchdog_timeout(None, slow), Some(Duration::from_secs(95)));
+    }
+
+    #[test]
+    fn watchdog_trips_once_and_recovers() {
+        let timeout = watchdog_timeout(None, Duration::from_secs(2));
+        let (fresh, stale) = (Duration::from_secs(3), Duration::from_secs(31));
+        assert_eq!(watchdog_step(fresh, timeout, false), WatchdogStep::Healthy);
+        assert_eq!(watchdog_step(stale, timeout, false), WatchdogStep::Trip);
+        assert_eq!(watchdog_step(stale, timeout, true), WatchdogStep::Tripped);
+        assert_eq!(watchdog_step(fresh, timeout, true), WatchdogStep::Recovered);
+        assert_eq!(
+            watchdog_step(Duration::MAX, None, false),
+            WatchdogStep::Healthy
+        );
+    }
+
+    #[test]
+    fn dwell_holds_drops_after_a_rise() {
+        let hold = Duration::from_secs(60);
+        let mut dwell = Dwell::default();
+        // Nothing applied yet, and no rise to hold for
+        assert_eq!(dwell.apply(None, 30.0, hold), 30.0);
+        assert_eq!(dwell.apply(Some(60.0), 40.0, hold), 40.0);
+
+        assert_eq!(dwell.apply(Some(40.0), 70.0, hold), 70.0);
+        assert_eq!(dwell.apply(Some(70.0), 50.0, hold), 70.0);
+        assert_eq!(dwell.apply(Some(70.0), 80.0, hold), 80.0);
+        assert_eq!(dwell.apply(Some(80.0), 50.0, Duration::ZERO), 50.0);
+    }
+}
@@ -0,0 +1,87 @@
+// Debouncing for flaky inputs such as the AC-present flag on a marginal charger
+use std::time::{Duration, Instant};
+
+/// Reports a change only once the new value has been seen continuously for the hold time
+#[derive(Debug, Default)]
+pub struct Debounce<T> {
+    stable: Option<T>,
+    pending: Option<(T, Instant)>,
+}
+
+impl<T: PartialEq + Copy> Debounce<T> {
+    /// Feed one reading; returns the new value on a confirmed transition.
+    /// The first reading becomes the stable value without counting as a transition.
+    pub fn update(&mut self, value: T, now: Instant, hold: Duration) -> Option<T> {
+        let Some(stable) = self.stable else {
+            self.stable = Some(value);
+            return None;
+        };
+        if value == stable {
+            self.pending = None;
+            return None;
+        }
+        let since = match self.pending {
+            Some((pending, since)) if pending == value => since,
+            _ => {
+                self.pending = Some((value, now));
+                now
+            }
+        };
+        if now.duration_since(since) < hold {
+            return None;
+        }
+        self.stable = Some(value);
+        self.pending = None;
+        Some(value)
+    }
+
+    /// The settled value, once there has been a reading
+    pub fn stable(&self) -> Option<T> {
+        self.stable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flapping_ac_only_switches_once_stable() {
+        let hold = Duration::from_secs(10);
+        let start = Instant::now();
+        let at = |s: u64| start + Duration::from_secs(s);
+        let mut ac = Debounce::default();
+        let mut transitions = Vec::new();
+
+        // Plugged in, then a flaky contact toggles every 5s for a while
+        let readings = [
+            (0, true),
+            (5, false),
+            (10, true),
+            (15, false),
+            (20, true),
+            (25, false),
+            (30, true),
+            // Finally unplugged for good
+            (35, false),
+            (40, false),
+            (45, false),
+            (50, false),
+        ];
+        for (s, on_ac) in readings {
+            if let Some(state) = ac.update(on_ac, at(s), hold) {
+                transitions.push((s, state));
+            }
+        }
+        assert_eq!(transitions, vec![(45, false)]);
+    }
+
+    #[test]
+    fn zero_hold_switches_immediately() {
+        let now = Instant::now();
+        let mut ac = Debounce::default();
+        assert_eq!(ac.update(true, now, Duration::ZERO), None);
+        assert_eq!(ac.update(false, now, Duration::ZERO), Some(false));
+        assert_eq!(ac.update(false, now, Duration::ZERO), None);
+    }
+}
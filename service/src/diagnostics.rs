@@ -0,0 +1,259 @@
+// One-shot EC memory map diagnostic for support reports
+use serde::Serialize;
+
+// Chrome EC memory map layout (ec_commands.h)
+const EC_MEMMAP_TEMP_SENSOR: usize = 0x00;
+const EC_MEMMAP_FAN: usize = 0x10;
+const EC_MEMMAP_TEMP_SENSOR_B: usize = 0x18;
+const EC_MEMMAP_ID: usize = 0x20;
+const EC_MEMMAP_THERMAL_VERSION: usize = 0x23;
+const EC_MEMMAP_BATTERY_VERSION: usize = 0x24;
+const EC_MEMMAP_BATT_VOLT: usize = 0x40;
+const EC_MEMMAP_BATT_FLAG: usize = 0x4C;
+const EC_MEMMAP_BATT_DCAP: usize = 0x50;
+const EC_MEMMAP_BATT_LFCC: usize = 0x58;
+const EC_MEMMAP_BATT_CCNT: usize = 0x5C;
+const EC_MEMMAP_BATT_MFGR: usize = 0x60;
+const EC_MEMMAP_BATT_MODEL: usize = 0x68;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SensorSlot {
+    pub offset: u8,
+    pub raw: u8,
+    pub status: String,
+    pub temp_c: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FanSlot {
+    pub index: u8,
+    pub raw: u16,
+    pub status: String,
+    pub rpm: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EcMapReport {
+    pub ec_id_ok: bool,
+    pub thermal_version: u8,
+    pub battery_version: u8,
+    pub sensors: Vec<SensorSlot>,
+    pub fans: Vec<FanSlot>,
+    pub battery_flags: u8,
+    pub battery_voltage_mv: u32,
+    pub battery_design_mah: u32,
+    pub battery_last_full_mah: u32,
+    pub battery_cycle_count: u32,
+    pub battery_manufacturer: String,
+    pub battery_model: String,
+    pub anomalies: Vec<String>,
+    /// Full memory map as hex, 16 bytes per line
+    pub raw_hex: Vec<String>,
+}
+
+pub fn decode_sensor(raw: u8) -> (&'static str, Option<i32>) {
+    match raw {
+        0xFF => ("not present", None),
+        0xFE => ("error", None),
+        0xFD => ("not powered", None),
+        0xFC => ("not calibrated", None),
+        t => (
+            "ok",
+            Some(t as i32 - crate::ec::temp_conversion().offset as i32),
+        ),
+    }
+}
+
+fn decode_fan(raw: u16) -> (&'static str, Option<u16>) {
+    match raw {
+        0xFFFF => ("not present", None),
+        0xFFFE => ("stalled", None),
+        rpm => ("ok", Some(rpm)),
+    }
+}
+
+fn ec_string(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .take_while(|b| **b != 0)
+        .map(|b| {
+            if b.is_ascii_graphic() || *b == b' ' {
+                *b as char
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+pub fn hex_lines(mem: &[u8]) -> Vec<String> {
+    mem.chunks(16)
+        .enumerate()
+        .map(|(i, row)| {
+            let bytes: Vec<String> = row.iter().map(|b| format!("{:02X}", b)).collect();
+            format!("{:02X}: {}", i * 16, bytes.join(" "))
+        })
+        .collect()
+}
+
+/// Classic hex dump: offset, 16 bytes in hex, then the same bytes as ASCII
+pub fn hex_dump(mem: &[u8]) -> String {
+    mem.chunks(16)
+        .enumerate()
+        .map(|(i, row)| {
+            let bytes: Vec<String> = row.iter().map(|b| format!("{:02X}", b)).collect();
+            let ascii: String = row
+                .iter()
+                .map(|b| {
+                    if b.is_ascii_graphic() || *b == b' ' {
+                        *b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!("{:02X}: {:<47}  |{}|\n", i * 16, bytes.join(" "), ascii)
+        })
+        .collect()
+}
+
+pub fn ec_map_report() -> Option<EcMapReport> {
+    let mem = crate::ec::read_ec_memory(0x00, 0xFF)?;
+    let u16_at = |o: usize| u16::from_le_bytes([mem[o], mem[o + 1]]);
+    let u32_at = |o: usize| u32::from_le_bytes([mem[o], mem[o + 1], mem[o + 2], mem[o + 3]]);
+    let mut anomalies = Vec::new();
+
+    let ec_id_ok = &mem[EC_MEMMAP_ID..EC_MEMMAP_ID + 2] == b"EC";
+    if !ec_id_ok {
+        anomalies.push("Memory map ID is not 'EC'; the map may be unreadable".to_string());
+    }
+    let thermal_version = mem[EC_MEMMAP_THERMAL_VERSION];
+    let battery_version = mem[EC_MEMMAP_BATTERY_VERSION];
+
+    // The second sensor bank only exists from thermal map version 2
+    let mut sensor_offsets: Vec<usize> = (EC_MEMMAP_TEMP_SENSOR..EC_MEMMAP_FAN).collect();
+    if thermal_version >= 2 {
+        sensor_offsets.extend(EC_MEMMAP_TEMP_SENSOR_B..EC_MEMMAP_ID);
+    }
+    let sensors: Vec<SensorSlot> = sensor_offsets
+        .into_iter()
+        .map(|o| {
+            let (status, temp_c) = decode_sensor(mem[o]);
+            if matches!(status, "error" | "not calibrated") {
+                anomalies.push(format!("Sensor 0x{:02X} reports {}", o, status));
+            }
+            if let Some(t) = temp_c.filter(|t| !(-20..=120).contains(t)) {
+                anomalies.push(format!("Sensor 0x{:02X} reads implausible {}°C", o, t));
+            }
+            SensorSlot {
+                offset: o as u8,
+                raw: mem[o],
+                status: status.to_string(),
+                temp_c,
+            }
+        })
+        .collect();
+    if !sensors.iter().any(|s| s.temp_c.is_some()) {
+        anomalies.push("No temperature sensor reports a valid value".to_string());
+    }
+
+    let fans: Vec<FanSlot> = (0..4u8)
+        .map(|i| {
+            let raw = u16_at(EC_MEMMAP_FAN + i as usize * 2);
+            let (status, rpm) = decode_fan(raw);
+            if status == "stalled" {
+                anomalies.push(format!("Fan {} is stalled", i + 1));
+            }
+            FanSlot {
+                index: i,
+                raw,
+                status: status.to_string(),
+                rpm,
+            }
+        })
+        .collect();
+    if !fans.iter().any(|f| f.status != "not present") {
+        anomalies.push("No fans reported present".to_string());
+    }
+
+    let battery_design_mah = u32_at(EC_MEMMAP_BATT_DCAP);
+    let battery_last_full_mah = u32_at(EC_MEMMAP_BATT_LFCC);
+    if battery_design_mah == 0 || battery_design_mah == u32::MAX {
+        anomalies.push("Battery design capacity is missing".to_string());
+    } else if battery_last_full_mah > battery_design_mah + battery_design_mah / 10 {
+        anomalies.push("Battery full-charge capacity is well above design".to_string());
+    }
+
+    Some(EcMapReport {
+        ec_id_ok,
+        thermal_version,
+        battery_version,
+        sensors,
+        fans,
+        battery_flags: mem[EC_MEMMAP_BATT_FLAG],
+        battery_voltage_mv: u32_at(EC_MEMMAP_BATT_VOLT),
+        battery_design_mah,
+        battery_last_full_mah,
+        battery_cycle_count: u32_at(EC_MEMMAP_BATT_CCNT),
+        battery_manufacturer: ec_string(&mem[EC_MEMMAP_BATT_MFGR..EC_MEMMAP_BATT_MFGR + 8]),
+        battery_model: ec_string(&mem[EC_MEMMAP_BATT_MODEL..EC_MEMMAP_BATT_MODEL + 8]),
+        anomalies,
+        raw_hex: hex_lines(&mem),
+    })
+}
+
+impl EcMapReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!(
+            "EC memory map (id ok: {}, thermal v{}, battery v{})\n",
+            self.ec_id_ok, self.thermal_version, self.battery_version
+        ));
+
+        out.push_str("\nSensors:\n");
+        for s in &self.sensors {
+            match s.temp_c {
+                Some(t) => out.push_str(&format!(
+                    "  0x{:02X}  raw 0x{:02X}  {}°C\n",
+                    s.offset, s.raw, t
+                )),
+                None => out.push_str(&format!(
+                    "  0x{:02X}  raw 0x{:02X}  {}\n",
+                    s.offset, s.raw, s.status
+                )),
+            }
+        }
+
+        out.push_str("\nFans:\n");
+        for f in &self.fans {
+            match f.rpm {
+                Some(rpm) => out.push_str(&format!("  Fan {}  {} RPM\n", f.index + 1, rpm)),
+                None => out.push_str(&format!("  Fan {}  {}\n", f.index + 1, f.status)),
+            }
+        }
+
+        out.push_str(&format!(
+            "\nBattery: {} {} | flags 0x{:02X} | {} mV | design {} mAh | full {} mAh | {} cycles\n",
+            self.battery_manufacturer,
+            self.battery_model,
+            self.battery_flags,
+            self.battery_voltage_mv,
+            self.battery_design_mah,
+            self.battery_last_full_mah,
+            self.battery_cycle_count
+        ));
+
+        out.push_str("\nAnomalies:\n");
+        if self.anomalies.is_empty() {
+            out.push_str("  none\n");
+        }
+        for a in &self.anomalies {
+            out.push_str(&format!("  ⚠ {}\n", a));
+        }
+        out
+    }
+}
@@ -1,280 +1,1138 @@
-use std::sync::OnceLock;
-use windows::core::{w, PCWSTR};
-use windows::Win32::Foundation::*;
-use windows::Win32::Storage::FileSystem::*;
-use windows::Win32::System::IO::*;
-use windows::Win32::UI::Shell::ShellExecuteW;
-use windows::Win32::UI::WindowsAndMessaging::SW_SHOW;
-
-// Flag to avoid repeated "opened" logs
-static EC_OPEN_LOGGED: OnceLock<bool> = OnceLock::new();
+use std::collections::BTreeMap;
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub enum EcError {
     AccessDenied,
     DriverMissing,
-    IoError(String),
-}
-
-// Open EC device fresh each time - no caching to avoid permission and thread-safety issues
-fn get_ec_handle() -> Result<HANDLE, EcError> {
-    // Try multiple known CrosEC / crosecbus device paths
-    let paths = [
-        w!(r"\\.\GLOBALROOT\Device\CrosEC"),
-        w!(r"\\.\CrosEC"),
-        w!(r"\\.\GLOBALROOT\Device\CrosECDevice"),
-        w!(r"\\.\crosecbus"),
-        w!(r"\\.\GLOBALROOT\Device\crosecbus"),
-        w!(r"\\.\GLOBALROOT\Device\CrosEcBus"),
-        w!(r"\\.\crossecbus"),
-        w!(r"\\.\GLOBALROOT\Device\crossecbus"),
-        w!(r"\\.\GLOBALROOT\Device\CrosSecBus"),
-    ];
+    /// The EC answered with a non-success result code (EC_RES_*)
+    Rejected(u32),
+}
 
-    for p in paths.iter() {
-        let res = unsafe {
-            CreateFileW(
-                *p,
-                FILE_GENERIC_READ.0 | FILE_GENERIC_WRITE.0,
-                FILE_SHARE_READ | FILE_SHARE_WRITE,
-                None,
-                OPEN_EXISTING,
-                FILE_FLAGS_AND_ATTRIBUTES(0),
-                None,
-            )
-        };
+/// How the EC is reached on this platform; everything else in this module is shared
+pub trait EcBackend: Sync {
+    fn check_connection(&self) -> Result<(), EcError>;
+    /// `length` bytes of the EC memory map from `offset`
+    fn read_memory(&self, offset: u16, length: u16) -> Option<Vec<u8>>;
+    fn send_command(&self, command: u16, version: u8, data: &[u8]) -> Result<Vec<u8>, EcError>;
+    /// Fan RPMs from an OS sensor driver, for when the memory map can't be read
+    fn os_fan_rpms(&self) -> Option<Vec<f32>> {
+        None
+    }
+    /// Temperatures (°C) from an OS sensor driver, for when the memory map can't be read
+    fn os_temps(&self) -> Option<Vec<f32>> {
+        None
+    }
+}
 
-        match res {
-            Ok(h) => {
-                if EC_OPEN_LOGGED.get().is_none() {
-                    let _ = EC_OPEN_LOGGED.set(true);
-                    println!("✅ EC device opened");
-                }
-                return Ok(h);
+fn backend() -> &'static dyn EcBackend {
+    static BACKEND: platform::Backend = platform::Backend;
+    &BACKEND
+}
+
+const EC_MEMMAP_SIZE: usize = 255;
+const HEADER_LEN: usize = 8;
+const CROSEC_CMD_MAX_REQUEST: usize = 0x100;
+/// Largest request payload a single host command can carry
+pub const EC_MAX_PARAM_LEN: usize = CROSEC_CMD_MAX_REQUEST - HEADER_LEN;
+
+/// Tries per EC operation; the driver now and then fails a request that succeeds moments later
+const IO_ATTEMPTS: u32 = 3;
+/// Wait before the first retry, doubled for each one after
+const IO_BACKOFF: Duration = Duration::from_millis(20);
+
+// EC_RES_* codes for a busy or unreachable EC, plus the 0xFF placeholder left when the
+// transfer failed before the EC answered
+const TRANSIENT_RESULTS: &[u32] = &[10, 15, 16, 0xFF];
+
+impl EcError {
+    /// Whether the same request may succeed if sent again
+    fn is_transient(&self) -> bool {
+        matches!(self, EcError::Rejected(code) if TRANSIENT_RESULTS.contains(code))
+    }
+}
+
+fn backoff(attempt: u32) {
+    std::thread::sleep(IO_BACKOFF * (1 << attempt));
+}
+
+pub fn read_ec_memory(offset: u16, length: u16) -> Option<Vec<u8>> {
+    for attempt in 0..IO_ATTEMPTS {
+        if let Some(data) = backend().read_memory(offset, length) {
+            return Some(data);
+        }
+        // Without a driver every retry fails the same way
+        if attempt + 1 == IO_ATTEMPTS || backend().check_connection().is_err() {
+            break;
+        }
+        backoff(attempt);
+    }
+    None
+}
+
+pub fn send_ec_command(command: u16, version: u8, data: &[u8]) -> Result<Vec<u8>, EcError> {
+    let mut attempt = 0;
+    loop {
+        match backend().send_command(command, version, data) {
+            Err(e) if e.is_transient() && attempt + 1 < IO_ATTEMPTS => {
+                tracing::debug!("EC command 0x{:04X} failed ({:?}), retrying", command, e);
+                backoff(attempt);
+                attempt += 1;
             }
-            Err(e) => {
-                // Check specifically for Access Denied
-                if e.code() == ERROR_ACCESS_DENIED.into() {
-                    return Err(EcError::AccessDenied);
+            result => return result,
+        }
+    }
+}
+
+pub fn check_connection() -> Result<(), EcError> {
+    backend().check_connection()
+}
+
+/// Relaunch with the rights the EC needs (UAC on Windows)
+pub fn restart_as_admin() {
+    platform::restart_as_admin()
+}
+
+/// Whether the process already runs with administrator (root) rights, so relaunching
+/// elevated can't help
+pub fn is_elevated() -> bool {
+    platform::is_elevated()
+}
+
+/// Fan control state as last acknowledged by the EC
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FanControlState {
+    Auto,
+    Duty(u32),
+}
+
+// The EC has no query for its fan mode, so remember the last command it accepted
+static FAN_CONTROL_STATE: Mutex<Option<FanControlState>> = Mutex::new(None);
+
+pub fn fan_control_state() -> Option<FanControlState> {
+    FAN_CONTROL_STATE.lock().ok().and_then(|s| *s)
+}
+
+fn record_fan_control_state(state: FanControlState) {
+    if let Ok(mut s) = FAN_CONTROL_STATE.lock() {
+        *s = Some(state);
+    }
+}
+
+// Nor for the TDP, so the last accepted value stands in for it
+static TDP_WATTS: Mutex<Option<u32>> = Mutex::new(None);
+
+/// TDP (W) last applied through ryzenadj
+pub fn tdp_watts() -> Option<u32> {
+    TDP_WATTS.lock().ok().and_then(|t| *t)
+}
+
+pub fn remember_tdp_watts(tdp: u32) {
+    if let Ok(mut t) = TDP_WATTS.lock() {
+        *t = Some(tdp);
+    }
+}
+
+// Commands this app drives its controls with
+/// EC_CMD_PWM_SET_FAN_DUTY
+pub const EC_CMD_FAN_DUTY: u16 = 0x24;
+/// EC_CMD_THERMAL_AUTO_FAN_CTRL
+pub const EC_CMD_FAN_AUTO: u16 = 0x52;
+/// Framework's charge limit control (EC_CMD_CHARGE_LIMIT_CONTROL)
+pub const EC_CMD_CHARGE_LIMIT: u16 = 0x3E03;
+
+pub fn set_fan_duty(percent: u32) -> bool {
+    // ec_params_pwm_set_fan_duty_v0: percent (u32), applied to every fan
+    let ok = send_ec_command(EC_CMD_FAN_DUTY, 0, &percent.to_le_bytes()).is_ok();
+    if ok {
+        record_fan_control_state(FanControlState::Duty(percent));
+    }
+    ok
+}
+
+/// Duty for a single fan: version 1 of the command takes the fan index after the duty
+pub fn set_fan_duty_for(percent: u32, fan: u8) -> bool {
+    // ec_params_pwm_set_fan_duty_v1: percent (u32), fan_idx (u8)
+    let mut data = [0u8; 5];
+    data[..4].copy_from_slice(&percent.to_le_bytes());
+    data[4] = fan;
+    let ok = send_ec_command(EC_CMD_FAN_DUTY, 1, &data).is_ok();
+    if ok {
+        record_fan_control_state(FanControlState::Duty(percent));
+    }
+    ok
+}
+
+pub fn set_fan_auto() -> bool {
+    let ok = send_versioned(EC_CMD_FAN_AUTO, &[]).is_ok();
+    if ok {
+        record_fan_control_state(FanControlState::Auto);
+    }
+    ok
+}
+
+const EC_MEMMAP_TEMP_SENSOR: u16 = 0x00;
+const EC_MEMMAP_FAN: u16 = 0x10;
+const EC_MEMMAP_TEMP_SENSOR_B: u16 = 0x18;
+const EC_MEMMAP_THERMAL_VERSION: u16 = 0x23;
+const EC_TEMP_SENSOR_ENTRIES: u16 = 16;
+const EC_TEMP_SENSOR_B_ENTRIES: u16 = 8;
+const EC_FAN_SPEED_ENTRIES: usize = 4;
+const EC_FAN_SPEED_NOT_PRESENT: u16 = 0xFFFF;
+const EC_FAN_SPEED_STALLED: u16 = 0xFFFE;
+const EC_CMD_TEMP_SENSOR_GET_INFO: u16 = 0x0070;
+
+/// Raw temperature slots of the memory map, undecoded: 0x00..0x0F, followed by the
+/// second bank at 0x18..0x1F on thermal map version 2 and later
+pub fn read_raw_temps() -> Vec<u8> {
+    read_ec_memory(EC_MEMMAP_TEMP_SENSOR, EC_TEMP_SENSOR_ENTRIES)
+        .map(|mut raw| {
+            let version = read_ec_memory(EC_MEMMAP_THERMAL_VERSION, 1)
+                .and_then(|v| v.first().copied())
+                .unwrap_or(0);
+            if version >= 2 {
+                if let Some(bank_b) =
+                    read_ec_memory(EC_MEMMAP_TEMP_SENSOR_B, EC_TEMP_SENSOR_B_ENTRIES)
+                {
+                    raw.extend(bank_b);
                 }
             }
+            raw
+        })
+        .or_else(|| {
+            // Re-encoded as memory map bytes so both sources decode alike
+            let temps = backend().os_temps()?;
+            let conversion = temp_conversion();
+            Some(temps.iter().map(|t| conversion.to_raw(*t)).collect())
+        })
+        .unwrap_or_default()
+}
+
+/// Raw temperature byte minus this is °C: the Chrome EC reports Kelvin minus 200
+pub const DEFAULT_TEMP_OFFSET: i16 = 73;
+/// Readings outside this range (°C) are sensor faults rather than temperatures
+pub const DEFAULT_VALID_TEMP_C: (f32, f32) = (-50.0, 150.0);
+// Mainboards (SMBIOS baseboard product) whose EC reports temperatures with another offset.
+// Every board known so far uses the default.
+const PLATFORM_TEMP_OFFSETS: &[(&str, i16)] = &[];
+
+/// How this machine's EC temperature bytes map to °C
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TempConversion {
+    pub offset: i16,
+    /// (min, max) °C accepted as real readings
+    pub valid_c: (f32, f32),
+}
+
+impl Default for TempConversion {
+    fn default() -> Self {
+        Self {
+            offset: DEFAULT_TEMP_OFFSET,
+            valid_c: DEFAULT_VALID_TEMP_C,
+        }
+    }
+}
+
+impl TempConversion {
+    /// The platform table entry for `mainboard`, with any configured overrides on top
+    pub fn resolve(mainboard: Option<&str>, config: &crate::types::TempConversionConfig) -> Self {
+        let platform = mainboard
+            .and_then(|board| {
+                PLATFORM_TEMP_OFFSETS
+                    .iter()
+                    .find(|(name, _)| board.eq_ignore_ascii_case(name))
+            })
+            .map_or(DEFAULT_TEMP_OFFSET, |(_, offset)| *offset);
+        Self {
+            offset: config.offset.unwrap_or(platform),
+            valid_c: config
+                .valid_range_c
+                .map_or(DEFAULT_VALID_TEMP_C, |[min, max]| (min, max)),
         }
     }
 
-    // If we get here, we either couldn't find the device or had other errors
-    // But if we saw at least one AccessDenied, we should probably report that?
-    // For now, if we can't open any, assume driver missing or general failure
-    // unless we want to be more specific.
-    // Let's return DriverMissing if we simply couldn't find it.
-    println!("❌ EC device open failed (all known paths). Ensure the Framework EC driver (crosecbus/crossecbus) is installed.");
-    Err(EcError::DriverMissing)
+    /// °C for a raw byte, or None for the EC's status codes (0xFC..) and implausible values
+    pub fn to_celsius(self, raw: u8) -> Option<f32> {
+        if raw >= 0xFC {
+            return None;
+        }
+        let temp_c = (raw as i16 - self.offset) as f32;
+        (temp_c > self.valid_c.0 && temp_c < self.valid_c.1).then_some(temp_c)
+    }
+
+    /// Raw byte for `temp_c`, the inverse of [`Self::to_celsius`]
+    pub fn to_raw(self, temp_c: f32) -> u8 {
+        (temp_c + self.offset as f32).clamp(0.0, 0xFB as f32) as u8
+    }
 }
 
-fn close_ec_handle(handle: HANDLE) {
-    unsafe {
-        let _ = CloseHandle(handle);
+static TEMP_CONVERSION: RwLock<TempConversion> = RwLock::new(TempConversion {
+    offset: DEFAULT_TEMP_OFFSET,
+    valid_c: DEFAULT_VALID_TEMP_C,
+});
+
+pub fn set_temp_conversion(conversion: TempConversion) {
+    if let Ok(mut guard) = TEMP_CONVERSION.write() {
+        *guard = conversion;
     }
 }
 
-const EC_MEMMAP_SIZE: usize = 255;
-const HEADER_LEN: usize = 8;
-const CROSEC_CMD_MAX_REQUEST: usize = 0x100;
-const FILE_DEVICE_CROS_EC: u32 = 0x80EC;
+pub fn temp_conversion() -> TempConversion {
+    TEMP_CONVERSION.read().map(|c| *c).unwrap_or_default()
+}
 
-const IOCTL_CROSEC_XCMD: u32 = ((FILE_DEVICE_CROS_EC) << 16) + ((0x3) << 14) + ((0x801) << 2) + 0;
-const IOCTL_CROSEC_RDMEM: u32 = ((FILE_DEVICE_CROS_EC) << 16) + ((0x1) << 14) + ((0x802) << 2) + 0;
+/// (slot, °C) for every slot holding a plausible reading; absent, unpowered and failed
+/// sensors are skipped, but the slot numbers stay those of the memory map
+pub fn decode_temps(raw: &[u8]) -> Vec<(usize, f32)> {
+    let conversion = temp_conversion();
+    raw.iter()
+        .enumerate()
+        .filter_map(|(slot, &t)| Some((slot, conversion.to_celsius(t)?)))
+        .collect()
+}
 
-pub fn read_ec_memory(offset: u16, length: u16) -> Option<Vec<u8>> {
-    let handle = get_ec_handle().ok()?;
+// Sensor names don't change while the app runs; None records a slot the EC couldn't name
+static SENSOR_NAMES: Mutex<BTreeMap<usize, Option<String>>> = Mutex::new(BTreeMap::new());
 
-    #[repr(C)]
-    struct ReadMem {
-        offset: u32,
-        bytes: u32,
-        buffer: [u8; EC_MEMMAP_SIZE],
+/// Name the EC gives temperature sensor `slot` (EC_CMD_TEMP_SENSOR_GET_INFO), asked once
+pub fn sensor_name(slot: usize) -> Option<String> {
+    if let Some(name) = SENSOR_NAMES.lock().ok()?.get(&slot) {
+        return name.clone();
+    }
+    let name = match send_ec_command(EC_CMD_TEMP_SENSOR_GET_INFO, 0, &[slot as u8]) {
+        Ok(resp) => {
+            // struct ec_response_temp_sensor_get_info { char sensor_name[32]; uint8_t sensor_type; }
+            let name: String = resp
+                .iter()
+                .take(32)
+                .take_while(|b| **b != 0)
+                .map(|b| *b as char)
+                .collect();
+            Some(name.trim().to_string()).filter(|n| !n.is_empty())
+        }
+        // Not reachable right now; ask again next time rather than remember a gap
+        Err(EcError::AccessDenied) | Err(EcError::DriverMissing) => return None,
+        Err(EcError::Rejected(_)) => None,
+    };
+    if let Ok(mut names) = SENSOR_NAMES.lock() {
+        names.insert(slot, name.clone());
     }
+    name
+}
 
-    let mut rm = ReadMem {
-        offset: offset as u32,
-        bytes: length as u32,
-        buffer: [0u8; EC_MEMMAP_SIZE],
+/// RPM of each fan the EC reports, in order, or `None` for a fan the EC flags as stalled.
+/// The EC fills the slots from the first, so the first "not present" slot ends the list.
+pub fn read_fans() -> Vec<Option<f32>> {
+    let Some(data) = read_ec_memory(EC_MEMMAP_FAN, (EC_FAN_SPEED_ENTRIES * 2) as u16) else {
+        let rpms = backend().os_fan_rpms().unwrap_or_default();
+        return rpms.into_iter().map(Some).collect();
     };
+    data.chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|rpm| *rpm != EC_FAN_SPEED_NOT_PRESENT)
+        .map(|rpm| match rpm {
+            EC_FAN_SPEED_STALLED => None,
+            rpm => Some(rpm as f32),
+        })
+        .collect()
+}
 
-    unsafe {
-        let _ = DeviceIoControl(
-            handle,
-            IOCTL_CROSEC_RDMEM,
-            Some(&mut rm as *mut _ as *mut _),
-            std::mem::size_of::<ReadMem>() as u32,
-            Some(&mut rm as *mut _ as *mut _),
-            std::mem::size_of::<ReadMem>() as u32,
-            None,
-            None,
-        );
+// Battery block of the EC memory map (EC_MEMMAP_BATT_*)
+const EC_MEMMAP_BATT_VOLT: u16 = 0x40;
+const EC_MEMMAP_BATTERY_VERSION: u16 = 0x24;
+const EC_MEMMAP_BATT_COUNT: u16 = 0x4D;
+const EC_CMD_BATTERY_GET_STATIC: u16 = 0x0600;
+const EC_CMD_BATTERY_GET_DYNAMIC: u16 = 0x0601;
+const EC_BATT_FLAG_BATT_PRESENT: u8 = 0x02;
+const EC_BATT_FLAG_DISCHARGING: u8 = 0x04;
+const EC_BATT_FLAG_LEVEL_CRITICAL: u8 = 0x10;
+
+#[derive(Debug, Clone, Default)]
+pub struct BatteryReading {
+    pub voltage_mv: u32,
+    /// Magnitude of the battery current; direction comes from the flags
+    pub rate_ma: u32,
+    pub flags: u8,
+    pub remaining_mah: u32,
+    pub design_mah: u32,
+    pub last_full_mah: u32,
+    /// Charge cycles, when the EC reports them
+    pub cycle_count: Option<u32>,
+}
+
+impl BatteryReading {
+    pub fn present(&self) -> bool {
+        self.flags & EC_BATT_FLAG_BATT_PRESENT != 0
+    }
+    pub fn discharging(&self) -> bool {
+        self.flags & EC_BATT_FLAG_DISCHARGING != 0
+    }
+    pub fn critical(&self) -> bool {
+        self.flags & EC_BATT_FLAG_LEVEL_CRITICAL != 0
     }
+}
 
-    close_ec_handle(handle);
-    Some(rm.buffer[..(length as usize)].to_vec())
+pub fn read_battery() -> Option<BatteryReading> {
+    let data = read_ec_memory(EC_MEMMAP_BATT_VOLT, 0x20)?;
+    let u32_at = |o: usize| u32::from_le_bytes([data[o], data[o + 1], data[o + 2], data[o + 3]]);
+    let reading = BatteryReading {
+        voltage_mv: u32_at(0x00),
+        rate_ma: u32_at(0x04),
+        flags: data[0x0C],
+        remaining_mah: u32_at(0x08),
+        design_mah: u32_at(0x10),
+        last_full_mah: u32_at(0x18),
+        // EC_MEMMAP_BATT_CCNT; all ones when the gauge doesn't count cycles
+        cycle_count: Some(u32_at(0x1C)).filter(|&c| c != u32::MAX),
+    };
+    // An all-ones value means the EC has no battery data yet
+    if reading.voltage_mv == u32::MAX || reading.rate_ma == u32::MAX {
+        return None;
+    }
+    Some(reading)
 }
 
-pub fn send_ec_command(command: u16, version: u8, data: &[u8]) -> Result<Vec<u8>, EcError> {
-    let handle = get_ec_handle()?;
+/// Every present battery. The memory map only describes the first one; further packs are
+/// read by index through the battery info host commands.
+pub fn read_batteries() -> Vec<BatteryReading> {
+    let mut batteries: Vec<BatteryReading> =
+        read_battery().filter(|b| b.present()).into_iter().collect();
+    // The count byte only exists from battery memory map version 2
+    let version = read_ec_memory(EC_MEMMAP_BATTERY_VERSION, 1).and_then(|v| v.first().copied());
+    let count = match version {
+        Some(v) if v >= 2 => read_ec_memory(EC_MEMMAP_BATT_COUNT, 1)
+            .and_then(|c| c.first().copied())
+            .unwrap_or(1),
+        _ => 1,
+    };
+    batteries.extend((1..count).filter_map(read_battery_by_index));
+    batteries
+}
 
-    println!(
-        "📤 Sending EC command: 0x{:02X}, version: {}, data len: {}",
-        command,
-        version,
-        data.len()
-    );
+fn read_battery_by_index(index: u8) -> Option<BatteryReading> {
+    // ec_response_battery_dynamic_info: voltage, current, remaining, full, flags (all i16)
+    // ec_response_battery_static_info: design capacity, design voltage, four 8-byte strings,
+    // cycle count
+    let dynamic = send_ec_command(EC_CMD_BATTERY_GET_DYNAMIC, 0, &[index]).ok()?;
+    let fixed = send_ec_command(EC_CMD_BATTERY_GET_STATIC, 0, &[index]).ok()?;
+    if dynamic.len() < 10 || fixed.len() < 2 {
+        return None;
+    }
+    let i16_at = |o: usize| i16::from_le_bytes([dynamic[o], dynamic[o + 1]]);
+    let current_ma = i16_at(2);
+    let reading = BatteryReading {
+        voltage_mv: i16_at(0).max(0) as u32,
+        rate_ma: current_ma.unsigned_abs() as u32,
+        flags: i16_at(8) as u8,
+        remaining_mah: i16_at(4).max(0) as u32,
+        design_mah: u16::from_le_bytes([fixed[0], fixed[1]]) as u32,
+        last_full_mah: i16_at(6).max(0) as u32,
+        cycle_count: fixed
+            .get(36..40)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]])),
+    };
+    reading.present().then_some(reading)
+}
 
-    #[repr(C)]
-    struct EcCommand {
-        version: u32,
-        command: u32,
-        outsize: u32,
-        insize: u32,
-        result: u32,
-        buffer: [u8; CROSEC_CMD_MAX_REQUEST],
+const EC_CMD_GET_CMD_VERSIONS: u16 = 0x0008;
+const EC_CMD_USB_PD_POWER_INFO: u16 = 0x0103;
+const EC_CMD_CHARGE_STATE: u16 = 0x00A0;
+const EC_CMD_CHARGE_CURRENT_LIMIT: u16 = 0x00A1;
+const USB_PD_PORT_POWER_SINK: u8 = 2;
+
+/// Estimated charger input power in milliwatts: negotiated PD voltage times the charger's
+/// input current limit, since the EC doesn't measure the adapter current. An upper bound
+/// on the real draw. Returns `None` when no port is sinking power.
+pub fn read_charger_input_mw() -> Option<u32> {
+    let voltage_mv = (0u8..4).find_map(|port| {
+        let resp = send_ec_command(EC_CMD_USB_PD_POWER_INFO, 0, &[port]).ok()?;
+        if resp.len() < 8 || resp[0] != USB_PD_PORT_POWER_SINK {
+            return None;
+        }
+        Some(u16::from_le_bytes([resp[6], resp[7]]) as u32)
+    })?;
+
+    // CHARGE_STATE_CMD_GET_STATE: ac, chg_voltage, chg_current, chg_input_current, batt_state_of_charge
+    let resp = send_ec_command(EC_CMD_CHARGE_STATE, 0, &[0]).ok()?;
+    if resp.len() < 16 {
+        return None;
     }
+    let input_ma = u32::from_le_bytes([resp[12], resp[13], resp[14], resp[15]]);
+    Some(((voltage_mv as u64 * input_ma as u64) / 1000) as u32)
+}
+
+/// Bitmask of versions the EC implements for `command`; `None` if it doesn't know the command
+pub fn command_versions(command: u16) -> Option<u32> {
+    // Version 1 takes a 16-bit command number
+    let resp = send_ec_command(EC_CMD_GET_CMD_VERSIONS, 1, &command.to_le_bytes()).ok()?;
+    let mask = u32::from_le_bytes(resp.get(..4)?.try_into().ok()?);
+    (mask != 0).then_some(mask)
+}
+
+const EC_CMD_GET_VERSION: u16 = 0x0002;
+const EC_RES_INVALID_PARAM: u32 = 3;
+const EC_RES_INVALID_VERSION: u32 = 6;
+
+/// Commands whose version byte is chosen per firmware. Fan duty isn't one: its versions
+/// take different parameters, so the caller picks the version along with the layout.
+const VERSIONED_COMMANDS: &[u16] = &[
+    EC_CMD_FAN_AUTO,
+    EC_CMD_CHARGE_LIMIT,
+    EC_CMD_CHARGE_CURRENT_LIMIT,
+];
+
+/// Firmware version prefix -> (command, version) overrides. Anything not listed uses
+/// version 0; add entries here as firmware-specific encodings are confirmed.
+const FIRMWARE_COMMAND_VERSIONS: &[(&str, &[(u16, u8)])] = &[];
+
+// Version byte chosen for each command; missing entries mean version 0
+static COMMAND_VERSIONS: Mutex<BTreeMap<u16, u8>> = Mutex::new(BTreeMap::new());
 
-    let mut cmd = EcCommand {
-        version: version as u32,
-        command: command as u32,
-        outsize: data.len() as u32,
-        insize: (CROSEC_CMD_MAX_REQUEST - HEADER_LEN) as u32,
-        result: 0xFF,
-        buffer: [0u8; CROSEC_CMD_MAX_REQUEST],
+fn command_version(command: u16) -> u8 {
+    COMMAND_VERSIONS
+        .lock()
+        .ok()
+        .and_then(|v| v.get(&command).copied())
+        .unwrap_or(0)
+}
+
+fn remember_command_version(command: u16, version: u8) {
+    if let Ok(mut versions) = COMMAND_VERSIONS.lock() {
+        versions.insert(command, version);
+    }
+}
+
+/// Running firmware version string, e.g. "hx30_v0.0.1-7a61a89"
+pub fn read_ec_version() -> Option<String> {
+    // ec_response_get_version: version_string_ro[32], version_string_rw[32], reserved[32], current_image
+    let resp = send_ec_command(EC_CMD_GET_VERSION, 0, &[]).ok()?;
+    if resp.len() < 100 {
+        return None;
+    }
+    const EC_IMAGE_RW: u32 = 2;
+    let image = u32::from_le_bytes([resp[96], resp[97], resp[98], resp[99]]);
+    let field = if image == EC_IMAGE_RW {
+        &resp[32..64]
+    } else {
+        &resp[..32]
     };
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    let version = String::from_utf8_lossy(&field[..end]).trim().to_string();
+    (!version.is_empty()).then_some(version)
+}
 
-    cmd.buffer[..data.len()].copy_from_slice(data);
-
-    let result = unsafe {
-        let mut returned: u32 = 0;
-        let io_result = DeviceIoControl(
-            handle,
-            IOCTL_CROSEC_XCMD,
-            Some(&mut cmd as *mut _ as *mut _),
-            (std::mem::size_of::<EcCommand>() - HEADER_LEN) as u32,
-            Some(&mut cmd as *mut _ as *mut _),
-            (std::mem::size_of::<EcCommand>() - HEADER_LEN) as u32,
-            Some(&mut returned),
-            None,
-        );
+/// Pick version bytes for the commands this app sends: the firmware table first, then the
+/// lowest version the EC reports if the table's choice isn't one of them.
+/// Returns the firmware version and the (command, version) pairs chosen.
+pub fn detect_command_versions() -> (Option<String>, Vec<(u16, u8)>) {
+    let firmware = read_ec_version();
+    let overrides = firmware
+        .as_deref()
+        .and_then(|fw| {
+            FIRMWARE_COMMAND_VERSIONS
+                .iter()
+                .find(|(prefix, _)| fw.starts_with(prefix))
+        })
+        .map_or(&[][..], |(_, table)| *table);
 
-        if let Err(ref e) = io_result {
-            println!("📥 EC IOCTL error: {:?}", e);
-            if e.code() == ERROR_ACCESS_DENIED.into() {
-                println!("🔒 EC access denied.");
-                close_ec_handle(handle);
-                return Err(EcError::AccessDenied);
+    let chosen: Vec<(u16, u8)> = VERSIONED_COMMANDS
+        .iter()
+        .map(|&command| {
+            let wanted = overrides
+                .iter()
+                .find(|(c, _)| *c == command)
+                .map_or(0, |(_, v)| *v);
+            let version = match command_versions(command) {
+                Some(mask) if mask & (1 << wanted) == 0 => mask.trailing_zeros() as u8,
+                _ => wanted,
+            };
+            (command, version)
+        })
+        .collect();
+    for &(command, version) in &chosen {
+        remember_command_version(command, version);
+    }
+    (firmware, chosen)
+}
+
+/// Send with the version chosen for this firmware. If the EC rejects the version or its
+/// parameters, the other versions it reports are tried; one that works is only kept when
+/// the EC rejected the version itself, since bad parameters say nothing about the version.
+fn send_versioned(command: u16, data: &[u8]) -> Result<Vec<u8>, EcError> {
+    let version = command_version(command);
+    let err = match send_ec_command(command, version, data) {
+        Err(EcError::Rejected(code))
+            if code == EC_RES_INVALID_PARAM || code == EC_RES_INVALID_VERSION =>
+        {
+            EcError::Rejected(code)
+        }
+        other => return other,
+    };
+    let mask = command_versions(command).unwrap_or(0) & !(1 << version);
+    for alternate in (0..32u8).filter(|v| mask & (1 << v) != 0) {
+        if let Ok(resp) = send_ec_command(command, alternate, data) {
+            if matches!(err, EcError::Rejected(EC_RES_INVALID_VERSION)) {
+                tracing::warn!(
+                    "EC command 0x{:04X} rejected version {}; using version {} from now on",
+                    command,
+                    version,
+                    alternate
+                );
+                remember_command_version(command, alternate);
             }
+            return Ok(resp);
         }
+    }
+    Err(err)
+}
 
-        println!(
-            "📥 EC command result: {:?}, returned bytes: {}, cmd.result: {}",
-            io_result, returned, cmd.result
-        );
+pub fn command_supported(command: u16) -> bool {
+    command_versions(command).is_some()
+}
 
-        if cmd.result != 0 {
-            if cmd.result == 255 {
-                // EC_RES_ACCESS_DENIED often maps to this or similar
-                println!("❌ EC command blocked by permissions.");
-            } else {
-                println!("❌ EC command failed with result code: {}", cmd.result);
-            }
-            close_ec_handle(handle);
-            return Err(EcError::IoError(format!("EC result code: {}", cmd.result)));
-        }
+/// Whether the EC takes a duty per fan (version 1 of the fan duty command)
+pub fn per_fan_duty_supported() -> bool {
+    command_versions(EC_CMD_FAN_DUTY).is_some_and(|mask| mask & (1 << 1) != 0)
+}
 
-        let end = returned.min(CROSEC_CMD_MAX_REQUEST as u32) as usize;
-        println!("✅ EC command succeeded");
-        Ok(cmd.buffer[..end].to_vec())
-    };
+pub fn charge_current_limit_supported() -> bool {
+    command_supported(EC_CMD_CHARGE_CURRENT_LIMIT)
+}
 
-    close_ec_handle(handle);
-    result
+/// Cap battery charge current in mA; `u32::MAX` removes the cap
+pub fn set_charge_current_limit(limit_ma: u32) -> bool {
+    send_versioned(EC_CMD_CHARGE_CURRENT_LIMIT, &limit_ma.to_le_bytes()).is_ok()
 }
 
-pub fn set_fan_duty(percent: u32) -> bool {
-    let data = [percent as u8];
-    send_ec_command(0x13, 0, &data).is_ok()
+/// Current the charger is programmed to deliver, and whether AC is present
+pub fn read_charge_current_ma() -> Option<(u32, bool)> {
+    let resp = send_ec_command(EC_CMD_CHARGE_STATE, 0, &[0]).ok()?;
+    if resp.len() < 12 {
+        return None;
+    }
+    let ac = u32::from_le_bytes([resp[0], resp[1], resp[2], resp[3]]) != 0;
+    let current_ma = u32::from_le_bytes([resp[8], resp[9], resp[10], resp[11]]);
+    Some((current_ma, ac))
 }
 
-pub fn set_fan_auto() -> bool {
-    send_ec_command(0x14, 0, &[]).is_ok()
+// Charge limit control modes: "set" programs the window, "get" reports the one enforced
+const CHG_LIMIT_SET_LIMIT: u8 = 1 << 1;
+const CHG_LIMIT_GET_LIMIT: u8 = 1 << 3;
+
+pub fn set_charge_limit(min_pct: u8, max_pct: u8) -> bool {
+    // ec_params_ec_chg_limit_control: modes, max_percentage, min_percentage
+    let data = [CHG_LIMIT_SET_LIMIT, max_pct, min_pct];
+    send_versioned(EC_CMD_CHARGE_LIMIT, &data).is_ok()
+}
+
+/// Charge window the EC enforces as (resume below, stop at); a stop point of 100% (or the
+/// 0 some firmware reports with no limit set) means charging is unlimited
+pub fn read_charge_limit() -> Option<(u8, u8)> {
+    // ec_params_ec_chg_limit_control: modes, max_percentage, min_percentage
+    let resp = send_ec_command(EC_CMD_CHARGE_LIMIT, 0, &[CHG_LIMIT_GET_LIMIT, 0, 0]).ok()?;
+    // ec_response_chg_limit_control: max_percentage, min_percentage
+    let (max, min) = (*resp.first()?, *resp.get(1)?);
+    let max = if max == 0 { 100 } else { max.min(100) };
+    Some((min.min(max), max))
+}
+
+const EC_CMD_PWM_GET_KEYBOARD_BACKLIGHT: u16 = 0x0022;
+const EC_CMD_PWM_SET_KEYBOARD_BACKLIGHT: u16 = 0x0023;
+
+/// Boards without a backlit keyboard don't know the command
+pub fn keyboard_backlight_supported() -> bool {
+    command_supported(EC_CMD_PWM_GET_KEYBOARD_BACKLIGHT)
+}
+
+/// Keyboard backlight brightness in percent, 0 while it is switched off
+pub fn read_keyboard_backlight() -> Option<u8> {
+    // ec_response_pwm_get_keyboard_backlight: percent, enabled
+    let resp = send_ec_command(EC_CMD_PWM_GET_KEYBOARD_BACKLIGHT, 0, &[]).ok()?;
+    let (percent, enabled) = (*resp.first()?, resp.get(1).is_none_or(|&e| e != 0));
+    Some(if enabled { percent.min(100) } else { 0 })
+}
+
+pub fn set_keyboard_backlight(percent: u8) -> bool {
+    send_ec_command(EC_CMD_PWM_SET_KEYBOARD_BACKLIGHT, 0, &[percent.min(100)]).is_ok()
+}
+
+/// Outcome of one action in [`restore_firmware_defaults`]
+#[derive(Debug, Clone)]
+pub struct ResetStep {
+    pub action: &'static str,
+    pub result: Result<(), String>,
+}
+
+/// Hand everything this app controls back to the firmware, straight through the EC.
+/// Every step is attempted even when an earlier one fails.
+pub fn restore_firmware_defaults() -> Vec<ResetStep> {
+    let step = |action, ok: bool| ResetStep {
+        action,
+        result: if ok {
+            Ok(())
+        } else {
+            Err("EC rejected the command".to_string())
+        },
+    };
+    let (min, max) = crate::types::charge_window(100, None);
+    let mut steps = vec![
+        step("Fans to EC auto", set_fan_auto()),
+        step("Charge limit cleared", set_charge_limit(min, max)),
+    ];
+    if charge_current_limit_supported() {
+        steps.push(step(
+            "Charge current limit cleared",
+            set_charge_current_limit(u32::MAX),
+        ));
+    }
+    // There is no EC command to restore the firmware's own TDP/thermal limits
+    steps.push(ResetStep {
+        action: "Power limits",
+        result: Err("not resettable at runtime; firmware limits return after a reboot".to_string()),
+    });
+    steps
 }
 
-pub fn read_temps() -> Vec<f32> {
-    let mut temps = Vec::new();
-    if let Some(data) = read_ec_memory(0x00, 0x0F) {
-        for &t in &data {
-            if t < 0xFC {
-                let temp_c = (t as i16 - 73) as f32;
-                if temp_c > -50.0 && temp_c < 150.0 {
-                    temps.push(temp_c);
+#[cfg(windows)]
+mod platform {
+    use super::{EcBackend, EcError, CROSEC_CMD_MAX_REQUEST, EC_MEMMAP_SIZE, HEADER_LEN};
+    use std::sync::OnceLock;
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::*;
+    use windows::Win32::Storage::FileSystem::*;
+    use windows::Win32::System::IO::*;
+    use windows::Win32::UI::Shell::ShellExecuteW;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOW;
+
+    // Flag to avoid repeated "opened" logs
+    static EC_OPEN_LOGGED: OnceLock<bool> = OnceLock::new();
+
+    /// The CrosEC driver's IOCTL interface
+    pub struct Backend;
+
+    impl EcBackend for Backend {
+        fn check_connection(&self) -> Result<(), EcError> {
+            let handle = get_ec_handle()?;
+            close_ec_handle(handle);
+            Ok(())
+        }
+
+        fn read_memory(&self, offset: u16, length: u16) -> Option<Vec<u8>> {
+            read_ec_memory(offset, length)
+        }
+
+        fn send_command(&self, command: u16, version: u8, data: &[u8]) -> Result<Vec<u8>, EcError> {
+            send_ec_command(command, version, data)
+        }
+    }
+
+    // Open EC device fresh each time - no caching to avoid permission and thread-safety issues
+    fn get_ec_handle() -> Result<HANDLE, EcError> {
+        // Try multiple known CrosEC / crosecbus device paths
+        let paths = [
+            w!(r"\\.\GLOBALROOT\Device\CrosEC"),
+            w!(r"\\.\CrosEC"),
+            w!(r"\\.\GLOBALROOT\Device\CrosECDevice"),
+            w!(r"\\.\crosecbus"),
+            w!(r"\\.\GLOBALROOT\Device\crosecbus"),
+            w!(r"\\.\GLOBALROOT\Device\CrosEcBus"),
+            w!(r"\\.\crossecbus"),
+            w!(r"\\.\GLOBALROOT\Device\crossecbus"),
+            w!(r"\\.\GLOBALROOT\Device\CrosSecBus"),
+        ];
+
+        for p in paths.iter() {
+            let res = unsafe {
+                CreateFileW(
+                    *p,
+                    FILE_GENERIC_READ.0 | FILE_GENERIC_WRITE.0,
+                    FILE_SHARE_READ | FILE_SHARE_WRITE,
+                    None,
+                    OPEN_EXISTING,
+                    FILE_FLAGS_AND_ATTRIBUTES(0),
+                    None,
+                )
+            };
+
+            match res {
+                Ok(h) => {
+                    if EC_OPEN_LOGGED.get().is_none() {
+                        let _ = EC_OPEN_LOGGED.set(true);
+                        eprintln!("✅ EC device opened");
+                    }
+                    return Ok(h);
+                }
+                Err(e) => {
+                    // Check specifically for Access Denied
+                    if e.code() == ERROR_ACCESS_DENIED.into() {
+                        return Err(EcError::AccessDenied);
+                    }
                 }
             }
         }
+
+        // If we get here, we either couldn't find the device or had other errors
+        // But if we saw at least one AccessDenied, we should probably report that?
+        // For now, if we can't open any, assume driver missing or general failure
+        // unless we want to be more specific.
+        // Let's return DriverMissing if we simply couldn't find it.
+        eprintln!("❌ EC device open failed (all known paths). Ensure the Framework EC driver (crosecbus/crossecbus) is installed.");
+        Err(EcError::DriverMissing)
+    }
+
+    fn close_ec_handle(handle: HANDLE) {
+        unsafe {
+            let _ = CloseHandle(handle);
+        }
     }
-    temps
-}
 
-pub fn read_fans() -> Vec<f32> {
-    let mut fans = Vec::new();
-    if let Some(data) = read_ec_memory(0x10, 0x08) {
-        for i in 0..4 {
-            let offset = i * 2;
-            if offset + 1 < data.len() {
-                let rpm = u16::from_le_bytes([data[offset], data[offset + 1]]);
-                if rpm != 0xFFFF {
-                    fans.push(rpm as f32);
+    const FILE_DEVICE_CROS_EC: u32 = 0x80EC;
+
+    const IOCTL_CROSEC_XCMD: u32 =
+        ((FILE_DEVICE_CROS_EC) << 16) + ((0x3) << 14) + ((0x801) << 2) + 0;
+    const IOCTL_CROSEC_RDMEM: u32 =
+        ((FILE_DEVICE_CROS_EC) << 16) + ((0x1) << 14) + ((0x802) << 2) + 0;
+
+    fn read_ec_memory(offset: u16, length: u16) -> Option<Vec<u8>> {
+        let handle = get_ec_handle().ok()?;
+
+        #[repr(C)]
+        struct ReadMem {
+            offset: u32,
+            bytes: u32,
+            buffer: [u8; EC_MEMMAP_SIZE],
+        }
+
+        let mut rm = ReadMem {
+            offset: offset as u32,
+            bytes: length as u32,
+            buffer: [0u8; EC_MEMMAP_SIZE],
+        };
+
+        let read = unsafe {
+            DeviceIoControl(
+                handle,
+                IOCTL_CROSEC_RDMEM,
+                Some(&mut rm as *mut _ as *mut _),
+                std::mem::size_of::<ReadMem>() as u32,
+                Some(&mut rm as *mut _ as *mut _),
+                std::mem::size_of::<ReadMem>() as u32,
+                None,
+                None,
+            )
+        };
+
+        close_ec_handle(handle);
+        // A failed read leaves the buffer zeroed, which would look like real sensors and fans
+        read.ok()?;
+        Some(rm.buffer[..(length as usize)].to_vec())
+    }
+
+    fn send_ec_command(command: u16, version: u8, data: &[u8]) -> Result<Vec<u8>, EcError> {
+        let handle = get_ec_handle()?;
+
+        eprintln!(
+            "📤 Sending EC command: 0x{:02X}, version: {}, data len: {}",
+            command,
+            version,
+            data.len()
+        );
+
+        #[repr(C)]
+        struct EcCommand {
+            version: u32,
+            command: u32,
+            outsize: u32,
+            insize: u32,
+            result: u32,
+            buffer: [u8; CROSEC_CMD_MAX_REQUEST],
+        }
+
+        let mut cmd = EcCommand {
+            version: version as u32,
+            command: command as u32,
+            outsize: data.len() as u32,
+            insize: (CROSEC_CMD_MAX_REQUEST - HEADER_LEN) as u32,
+            result: 0xFF,
+            buffer: [0u8; CROSEC_CMD_MAX_REQUEST],
+        };
+
+        cmd.buffer[..data.len()].copy_from_slice(data);
+
+        let result = unsafe {
+            let mut returned: u32 = 0;
+            let io_result = DeviceIoControl(
+                handle,
+                IOCTL_CROSEC_XCMD,
+                Some(&mut cmd as *mut _ as *mut _),
+                (std::mem::size_of::<EcCommand>() - HEADER_LEN) as u32,
+                Some(&mut cmd as *mut _ as *mut _),
+                (std::mem::size_of::<EcCommand>() - HEADER_LEN) as u32,
+                Some(&mut returned),
+                None,
+            );
+
+            if let Err(ref e) = io_result {
+                eprintln!("📥 EC IOCTL error: {:?}", e);
+                if e.code() == ERROR_ACCESS_DENIED.into() {
+                    eprintln!("🔒 EC access denied.");
+                    close_ec_handle(handle);
+                    return Err(EcError::AccessDenied);
                 }
             }
+
+            eprintln!(
+                "📥 EC command result: {:?}, returned bytes: {}, cmd.result: {}",
+                io_result, returned, cmd.result
+            );
+
+            if cmd.result != 0 {
+                if cmd.result == 255 {
+                    // EC_RES_ACCESS_DENIED often maps to this or similar
+                    eprintln!("❌ EC command blocked by permissions.");
+                } else {
+                    eprintln!("❌ EC command failed with result code: {}", cmd.result);
+                }
+                close_ec_handle(handle);
+                return Err(EcError::Rejected(cmd.result));
+            }
+
+            let end = returned.min(CROSEC_CMD_MAX_REQUEST as u32) as usize;
+            eprintln!("✅ EC command succeeded");
+            Ok(cmd.buffer[..end].to_vec())
+        };
+
+        close_ec_handle(handle);
+        result
+    }
+
+    pub fn restart_as_admin() {
+        unsafe {
+            let current_exe = std::env::current_exe().unwrap_or_default();
+            let path_str = current_exe.to_str().unwrap_or_default();
+
+            let path_hstring = windows::core::HSTRING::from(path_str);
+            let args_hstring = windows::core::HSTRING::from(""); // Pass current args if needed
+
+            let _ = ShellExecuteW(
+                None,
+                w!("runas"),
+                PCWSTR(path_hstring.as_ptr()),
+                PCWSTR(args_hstring.as_ptr()),
+                None,
+                SW_SHOW,
+            );
+
+            // Exit current process
+            std::process::exit(0);
         }
     }
-    fans
-}
 
-pub fn set_charge_limit(max_pct: u8) -> bool {
-    let min_pct = if max_pct > 5 { max_pct - 5 } else { 0 };
-    let data = [min_pct, max_pct];
-    send_ec_command(0x30, 0, &data).is_ok()
-}
+    pub fn is_elevated() -> bool {
+        use windows::Win32::Security::{
+            GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+        };
+        use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
 
-pub fn set_tdp_watts(tdp: u32) -> bool {
-    let data = tdp.to_le_bytes();
-    send_ec_command(0x20, 0, &data).is_ok()
+        unsafe {
+            let mut token = HANDLE::default();
+            if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+                return false;
+            }
+            let mut elevation = TOKEN_ELEVATION::default();
+            let mut returned = 0u32;
+            let queried = GetTokenInformation(
+                token,
+                TokenElevation,
+                Some(&mut elevation as *mut _ as *mut _),
+                std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+                &mut returned,
+            );
+            let _ = CloseHandle(token);
+            queried.is_ok() && elevation.TokenIsElevated != 0
+        }
+    }
 }
 
-pub fn set_thermal_limit(limit: u32) -> bool {
-    let data = limit.to_le_bytes();
-    send_ec_command(0x21, 0, &data).is_ok()
-}
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{EcBackend, EcError, CROSEC_CMD_MAX_REQUEST, EC_MEMMAP_SIZE, HEADER_LEN};
+    use std::fs::{File, OpenOptions};
+    use std::io::ErrorKind;
+    use std::os::raw::{c_int, c_ulong};
+    use std::os::unix::io::AsRawFd;
+    use std::path::{Path, PathBuf};
 
-pub fn restart_as_admin() {
-    unsafe {
-        let current_exe = std::env::current_exe().unwrap_or_default();
-        let path_str = current_exe.to_str().unwrap_or_default();
-
-        let path_hstring = windows::core::HSTRING::from(path_str);
-        let args_hstring = windows::core::HSTRING::from(""); // Pass current args if needed
-
-        let _ = ShellExecuteW(
-            None,
-            w!("runas"),
-            PCWSTR(path_hstring.as_ptr()),
-            PCWSTR(args_hstring.as_ptr()),
-            None,
-            SW_SHOW,
+    const DEVICE: &str = "/dev/cros_ec";
+
+    // linux/cros_ec_dev.h: _IOWR(0xEC, nr, size)
+    const fn iowr(nr: c_ulong, size: usize) -> c_ulong {
+        (3 << 30) | ((size as c_ulong) << 16) | (0xEC << 8) | nr
+    }
+    // Sized without cros_ec_command_v2's flexible data array
+    const CROS_EC_DEV_IOCXCMD_V2: c_ulong = iowr(0, 20);
+    const CROS_EC_DEV_IOCRDMEM_V2: c_ulong = iowr(1, std::mem::size_of::<ReadMem>());
+
+    #[repr(C)]
+    struct ReadMem {
+        offset: u32,
+        bytes: u32,
+        buffer: [u8; EC_MEMMAP_SIZE],
+    }
+
+    #[repr(C)]
+    struct Command {
+        version: u32,
+        command: u32,
+        outsize: u32,
+        insize: u32,
+        result: u32,
+        data: [u8; CROSEC_CMD_MAX_REQUEST],
+    }
+
+    extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+        fn geteuid() -> u32;
+    }
+
+    fn open() -> Result<File, EcError> {
+        OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(DEVICE)
+            .map_err(|e| match e.kind() {
+                ErrorKind::PermissionDenied => EcError::AccessDenied,
+                _ => EcError::DriverMissing,
+            })
+    }
+
+    /// The cros_ec kernel driver's character device, with its hwmon sensors as a fallback
+    pub struct Backend;
+
+    impl EcBackend for Backend {
+        fn check_connection(&self) -> Result<(), EcError> {
+            open().map(|_| ())
+        }
+
+        fn read_memory(&self, offset: u16, length: u16) -> Option<Vec<u8>> {
+            let device = open().ok()?;
+            let length = (length as usize).min(EC_MEMMAP_SIZE);
+            let mut rm = ReadMem {
+                offset: offset as u32,
+                bytes: length as u32,
+                buffer: [0u8; EC_MEMMAP_SIZE],
+            };
+            let ret = unsafe {
+                ioctl(
+                    device.as_raw_fd(),
+                    CROS_EC_DEV_IOCRDMEM_V2,
+                    &mut rm as *mut ReadMem,
+                )
+            };
+            (ret >= 0).then(|| rm.buffer[..length].to_vec())
+        }
+
+        fn send_command(&self, command: u16, version: u8, data: &[u8]) -> Result<Vec<u8>, EcError> {
+            let device = open()?;
+            let mut cmd = Command {
+                version: version as u32,
+                command: command as u32,
+                outsize: data.len() as u32,
+                insize: (CROSEC_CMD_MAX_REQUEST - HEADER_LEN) as u32,
+                result: 0xFF,
+                data: [0u8; CROSEC_CMD_MAX_REQUEST],
+            };
+            cmd.data[..data.len()].copy_from_slice(data);
+
+            let ret = unsafe {
+                ioctl(
+                    device.as_raw_fd(),
+                    CROS_EC_DEV_IOCXCMD_V2,
+                    &mut cmd as *mut Command,
+                )
+            };
+            if ret < 0 && std::io::Error::last_os_error().kind() == ErrorKind::PermissionDenied {
+                return Err(EcError::AccessDenied);
+            }
+            // A failed transfer leaves the 0xFF placeholder, as on Windows
+            if ret < 0 || cmd.result != 0 {
+                return Err(EcError::Rejected(cmd.result));
+            }
+            let end = (ret as usize).min(CROSEC_CMD_MAX_REQUEST);
+            Ok(cmd.data[..end].to_vec())
+        }
+
+        fn os_fan_rpms(&self) -> Option<Vec<f32>> {
+            Some(read_series(&hwmon_dir()?, "fan", 1.0))
+        }
+
+        fn os_temps(&self) -> Option<Vec<f32>> {
+            Some(read_series(&hwmon_dir()?, "temp", 1000.0))
+        }
+    }
+
+    /// The cros_ec hwmon device, which exposes the same sensors through the kernel
+    fn hwmon_dir() -> Option<PathBuf> {
+        std::fs::read_dir("/sys/class/hwmon")
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|dir| {
+                std::fs::read_to_string(dir.join("name")).is_ok_and(|name| name.trim() == "cros_ec")
+            })
+    }
+
+    /// `<prefix>1_input`, `<prefix>2_input`, ... up to the first missing one, divided by `scale`
+    fn read_series(dir: &Path, prefix: &str, scale: f32) -> Vec<f32> {
+        (1..)
+            .map_while(|i| std::fs::read_to_string(dir.join(format!("{}{}_input", prefix, i))).ok())
+            .filter_map(|value| value.trim().parse::<f32>().ok())
+            .map(|value| value / scale)
+            .collect()
+    }
+
+    pub fn restart_as_admin() {
+        // There is no elevation prompt to hand off to; access comes from root or a udev rule
+        eprintln!(
+            "🔒 No access to {}. Run as root or grant your user access with a udev rule.",
+            DEVICE
         );
+    }
 
-        // Exit current process
-        std::process::exit(0);
+    pub fn is_elevated() -> bool {
+        unsafe { geteuid() == 0 }
     }
 }
 
-pub fn check_connection() -> Result<(), EcError> {
-    let handle = get_ec_handle()?;
-    close_ec_handle(handle);
-    Ok(())
+#[cfg(not(any(windows, target_os = "linux")))]
+mod platform {
+    use super::{EcBackend, EcError};
+
+    pub struct Backend;
+
+    impl EcBackend for Backend {
+        fn check_connection(&self) -> Result<(), EcError> {
+            Err(EcError::DriverMissing)
+        }
+
+        fn read_memory(&self, _offset: u16, _length: u16) -> Option<Vec<u8>> {
+            None
+        }
+
+        fn send_command(
+            &self,
+            _command: u16,
+            _version: u8,
+            _data: &[u8],
+        ) -> Result<Vec<u8>, EcError> {
+            Err(EcError::DriverMissing)
+        }
+    }
+
+    pub fn restart_as_admin() {}
+
+    pub fn is_elevated() -> bool {
+        false
+    }
 }
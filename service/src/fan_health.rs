@@ -0,0 +1,112 @@
+// Detects fans spinning well below their calibrated speed (failing or obstructed)
+use crate::types::{FanCalibration, TelemetrySample};
+
+/// Default allowed shortfall below the calibrated RPM before alerting (%)
+pub const DEFAULT_TOLERANCE_PCT: u32 = 30;
+/// The commanded duty must have been steady this long before judging the RPM
+pub const SETTLE_SECS: i64 = 20;
+// Below this duty fans may legitimately stop, so the RPM says little about health
+const MIN_CHECK_DUTY_PCT: u32 = 20;
+
+/// Wait after a duty change before reading the fans back, so they have reached speed
+pub const READBACK_SETTLE: std::time::Duration = std::time::Duration::from_secs(3);
+// Slower than this at a duty that should spin the fan counts as standing still
+const STALL_RPM: f32 = 100.0;
+
+/// Fan speeds measured shortly after a duty was commanded
+#[derive(Debug, Clone)]
+pub struct DutyReadback {
+    pub duty_pct: u32,
+    pub rpms: Vec<f32>,
+    pub at: std::time::Instant,
+}
+
+impl DutyReadback {
+    /// Zero-based fans that aren't turning although the duty should spin them
+    pub fn stalled(&self) -> Vec<usize> {
+        if self.duty_pct < MIN_CHECK_DUTY_PCT {
+            return Vec::new();
+        }
+        self.rpms
+            .iter()
+            .enumerate()
+            .filter(|(_, &rpm)| rpm < STALL_RPM)
+            .map(|(fan, _)| fan)
+            .collect()
+    }
+
+    /// e.g. "45% → 2100 / 2050 RPM"
+    pub fn summary(&self) -> String {
+        let rpms: Vec<String> = self.rpms.iter().map(|rpm| format!("{:.0}", rpm)).collect();
+        format!("{}% → {} RPM", self.duty_pct, rpms.join(" / "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FanAlert {
+    /// Zero-based fan index
+    pub fan: usize,
+    pub duty_pct: u32,
+    pub expected_rpm: f32,
+    pub measured_rpm: f32,
+}
+
+impl FanAlert {
+    pub fn message(&self) -> String {
+        format!(
+            "Fan {} at {:.0} RPM, expected ~{:.0} RPM at {}% duty",
+            self.fan + 1,
+            self.measured_rpm,
+            self.expected_rpm,
+            self.duty_pct
+        )
+    }
+}
+
+/// Compare the most recent settled window of `history` against the calibration.
+/// Returns `None` when the fans can't be judged right now (duty not settled or too low).
+pub fn check(
+    history: &[TelemetrySample],
+    calibration: &FanCalibration,
+    tolerance_pct: u32,
+) -> Option<Vec<FanAlert>> {
+    let newest = history.last()?;
+    let duty = newest.fan_duty_pct.filter(|d| *d >= MIN_CHECK_DUTY_PCT)?;
+    let window: Vec<&TelemetrySample> = history
+        .iter()
+        .rev()
+        .take_while(|s| s.fan_duty_pct == Some(duty))
+        .collect();
+    // Only judge once the duty has been held long enough for the fan to settle
+    let held_ms = newest.ts_ms - window.last().map_or(newest.ts_ms, |s| s.ts_ms);
+    if held_ms < SETTLE_SECS * 1000 {
+        return None;
+    }
+    let expected_rpm = crate::noise::rpm_for_duty(duty as f32, calibration)?;
+    let floor = expected_rpm * (1.0 - tolerance_pct.min(100) as f32 / 100.0);
+
+    let settled = window
+        .iter()
+        .filter(|s| newest.ts_ms - s.ts_ms <= SETTLE_SECS * 1000);
+    let fans = newest.fan_rpms.len();
+    let alerts = (0..fans)
+        .filter_map(|fan| {
+            // A fan the EC flags as stalled is recorded at 0 RPM (see `stalled_fans`)
+            let readings: Vec<f32> = settled
+                .clone()
+                .filter_map(|s| s.fan_rpms.get(fan).copied())
+                .collect();
+            if readings.is_empty() {
+                return None;
+            }
+            let measured_rpm = readings.iter().sum::<f32>() / readings.len() as f32;
+            (measured_rpm < floor).then_some(FanAlert {
+                fan,
+                duty_pct: duty,
+                expected_rpm,
+                measured_rpm,
+            })
+        })
+        .collect();
+    Some(alerts)
+}
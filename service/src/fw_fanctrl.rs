@@ -0,0 +1,128 @@
+// Import fan curves from fw-fanctrl (https://github.com/TamtamHero/fw-fanctrl) configs
+use serde_json::Value;
+
+use crate::types::{CurveConfig, MIN_SMOOTHING_ALPHA};
+
+/// Curves taken from an fw-fanctrl config, with notes on anything that has no equivalent
+#[derive(Debug)]
+pub struct Imported {
+    pub curve: CurveConfig,
+    /// From `strategyOnDischarging`, when it names a strategy other than the default
+    pub curve_battery: Option<CurveConfig>,
+    pub warnings: Vec<String>,
+}
+
+/// Convert an fw-fanctrl config (or a single strategy object). The default strategy becomes
+/// the fan curve and the one used on battery, if any, the battery curve.
+pub fn import(json: &str) -> Result<Imported, String> {
+    let root: Value = serde_json::from_str(json).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let mut warnings = Vec::new();
+
+    // A bare strategy
+    if root.get("speedCurve").is_some() {
+        let curve = strategy_curve("strategy", &root, &mut warnings)?;
+        return Ok(Imported {
+            curve,
+            curve_battery: None,
+            warnings,
+        });
+    }
+
+    let strategies = root
+        .get("strategies")
+        .and_then(Value::as_object)
+        .ok_or("No 'strategies' or 'speedCurve' found")?;
+    let name = root
+        .get("defaultStrategy")
+        .and_then(Value::as_str)
+        .filter(|n| strategies.contains_key(*n))
+        .or_else(|| strategies.keys().next().map(String::as_str))
+        .ok_or("Config has no strategies")?;
+    let discharging = match root.get("strategyOnDischarging").and_then(Value::as_str) {
+        Some(n) if n.is_empty() || n == name => None,
+        Some(n) if strategies.contains_key(n) => Some(n),
+        Some(n) => {
+            warnings.push(format!(
+                "'strategyOnDischarging' names unknown strategy '{}'",
+                n
+            ));
+            None
+        }
+        None => None,
+    };
+    let others: Vec<&str> = strategies
+        .keys()
+        .map(String::as_str)
+        .filter(|n| *n != name && Some(*n) != discharging)
+        .collect();
+    if !others.is_empty() {
+        warnings.push(format!("Skipped unused strategies: {}", others.join(", ")));
+    }
+
+    let curve = strategy_curve(name, &strategies[name], &mut warnings)?;
+    let curve_battery = discharging
+        .map(|n| strategy_curve(n, &strategies[n], &mut warnings))
+        .transpose()?;
+    Ok(Imported {
+        curve,
+        curve_battery,
+        warnings,
+    })
+}
+
+fn strategy_curve(
+    name: &str,
+    strategy: &Value,
+    warnings: &mut Vec<String>,
+) -> Result<CurveConfig, String> {
+    let curve = strategy
+        .get("speedCurve")
+        .and_then(Value::as_array)
+        .ok_or_else(|| format!("Strategy '{}' has no speedCurve", name))?;
+    let mut points: Vec<[u32; 2]> = curve
+        .iter()
+        .filter_map(|p| {
+            let temp = p.get("temp")?.as_f64()?;
+            let speed = p.get("speed")?.as_f64()?;
+            Some([
+                temp.clamp(0.0, 120.0).round() as u32,
+                speed.clamp(0.0, 100.0).round() as u32,
+            ])
+        })
+        .collect();
+    if points.len() != curve.len() {
+        warnings.push(format!(
+            "Some '{}' speedCurve entries were malformed and skipped",
+            name
+        ));
+    }
+    points.sort_by_key(|p| p[0]);
+    points.dedup_by_key(|p| p[0]);
+    if points.len() < 2 {
+        return Err(format!(
+            "Strategy '{}' needs at least two curve points",
+            name
+        ));
+    }
+
+    let mut config = CurveConfig {
+        points,
+        ..Default::default()
+    };
+    if let Some(secs) = strategy
+        .get("fanSpeedUpdateFrequency")
+        .and_then(Value::as_f64)
+    {
+        config.poll_ms = (secs * 1000.0).clamp(500.0, 60_000.0) as u64;
+    }
+    // An average over N readings is roughly a moving average weighting each new one 1/N
+    if let Some(interval) = strategy
+        .get("movingAverageInterval")
+        .and_then(Value::as_f64)
+    {
+        if interval > 0.0 {
+            config.smoothing_alpha = ((1.0 / interval) as f32).clamp(MIN_SMOOTHING_ALPHA, 1.0);
+        }
+    }
+    Ok(config)
+}
@@ -0,0 +1,157 @@
+// System-wide hotkeys, registered with the OS so they fire while the window is
+// unfocused or hidden in the tray
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    /// Force every fan to 100% until restored
+    PanicFans,
+    /// Hand fans back to the configured mode
+    RestoreFans,
+}
+
+const MOD_ALT: u32 = 0x0001;
+const MOD_CONTROL: u32 = 0x0002;
+const MOD_SHIFT: u32 = 0x0004;
+const MOD_WIN: u32 = 0x0008;
+
+/// Parse a binding like "Ctrl+Alt+F" into (modifier flags, virtual-key code)
+pub fn parse(spec: &str) -> Result<(u32, u32), String> {
+    let mut modifiers = 0;
+    let mut key = None;
+    for part in spec.split('+').map(str::trim) {
+        match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => modifiers |= MOD_CONTROL,
+            "alt" => modifiers |= MOD_ALT,
+            "shift" => modifiers |= MOD_SHIFT,
+            "win" | "super" => modifiers |= MOD_WIN,
+            _ if key.is_some() => return Err(format!("More than one key in \"{}\"", spec)),
+            _ => key = Some(virtual_key(part).ok_or_else(|| format!("Unknown key \"{}\"", part))?),
+        }
+    }
+    let key = key.ok_or_else(|| format!("No key in \"{}\"", spec))?;
+    // A bare key would swallow normal typing system-wide
+    if modifiers == 0 {
+        return Err(format!("\"{}\" needs at least one modifier", spec));
+    }
+    Ok((modifiers, key))
+}
+
+fn virtual_key(name: &str) -> Option<u32> {
+    let upper = name.to_ascii_uppercase();
+    let mut chars = upper.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphanumeric() => Some(c as u32),
+        (Some('F'), Some(_)) => match upper[1..].parse::<u32>() {
+            Ok(n @ 1..=24) => Some(0x70 + n - 1),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Register `bindings` on a dedicated message-loop thread. Presses arrive on the returned
+/// channel; registration failures (bad syntax, key taken by another app) are returned as
+/// messages and the remaining bindings still work.
+pub fn listen(
+    bindings: Vec<(HotkeyAction, String)>,
+) -> (mpsc::UnboundedReceiver<HotkeyAction>, Vec<String>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut errors = Vec::new();
+    let mut parsed = Vec::new();
+    for (action, spec) in bindings {
+        match parse(&spec) {
+            Ok((modifiers, key)) => parsed.push((action, spec, modifiers, key)),
+            Err(e) => errors.push(e),
+        }
+    }
+    errors.extend(platform::spawn(parsed, tx));
+    (rx, errors)
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::HotkeyAction;
+    use tokio::sync::mpsc;
+
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    struct Msg {
+        hwnd: isize,
+        message: u32,
+        wparam: usize,
+        lparam: isize,
+        time: u32,
+        pt: [i32; 2],
+        private: u32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn RegisterHotKey(hwnd: isize, id: i32, modifiers: u32, vk: u32) -> i32;
+        fn GetMessageW(msg: *mut Msg, hwnd: isize, min: u32, max: u32) -> i32;
+    }
+    const WM_HOTKEY: u32 = 0x0312;
+    const MOD_NOREPEAT: u32 = 0x4000;
+
+    pub fn spawn(
+        bindings: Vec<(HotkeyAction, String, u32, u32)>,
+        tx: mpsc::UnboundedSender<HotkeyAction>,
+    ) -> Vec<String> {
+        if bindings.is_empty() {
+            return Vec::new();
+        }
+        // Hotkeys belong to the thread that registers them, so registration and the
+        // message loop both live on this thread
+        let (result_tx, result_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut errors = Vec::new();
+            let mut actions = Vec::new();
+            for (action, spec, modifiers, key) in bindings {
+                let id = actions.len() as i32 + 1;
+                if unsafe { RegisterHotKey(0, id, modifiers | MOD_NOREPEAT, key) } != 0 {
+                    actions.push(action);
+                } else {
+                    errors.push(format!("{} is already in use by another app", spec));
+                }
+            }
+            let _ = result_tx.send(errors);
+            if actions.is_empty() {
+                return;
+            }
+
+            let mut msg = Msg::default();
+            while unsafe { GetMessageW(&mut msg, 0, 0, 0) } > 0 {
+                if msg.message != WM_HOTKEY {
+                    continue;
+                }
+                if let Some(action) = actions.get(msg.wparam.wrapping_sub(1)) {
+                    if tx.send(*action).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        result_rx
+            .recv()
+            .unwrap_or_else(|_| vec!["Hotkey thread exited".to_string()])
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use super::HotkeyAction;
+    use tokio::sync::mpsc;
+
+    pub fn spawn(
+        bindings: Vec<(HotkeyAction, String, u32, u32)>,
+        _tx: mpsc::UnboundedSender<HotkeyAction>,
+    ) -> Vec<String> {
+        if bindings.is_empty() {
+            Vec::new()
+        } else {
+            vec!["Global hotkeys are only supported on Windows".to_string()]
+        }
+    }
+}
@@ -0,0 +1,83 @@
+// Single-instance guard: a second GUI would poll and command the EC alongside the first,
+// so a repeat launch brings the running window forward instead
+
+/// Skips the guard, e.g. to compare two builds side by side
+pub const ALLOW_MULTIPLE_FLAG: &str = "--allow-multiple";
+
+/// Held for the life of the GUI; the lock is released when it drops
+pub struct Guard {
+    _lock: platform::Lock,
+}
+
+/// Claim the lock, or `None` when another copy already holds it. A lock that can't be
+/// created at all lets this copy run rather than refusing to start.
+pub fn acquire() -> Option<Guard> {
+    platform::acquire().map(|lock| Guard { _lock: lock })
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::core::w;
+    use windows::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ALREADY_EXISTS, HANDLE};
+    use windows::Win32::System::Threading::CreateMutexW;
+
+    pub struct Lock(HANDLE);
+
+    impl Drop for Lock {
+        fn drop(&mut self) {
+            unsafe {
+                let _ = CloseHandle(self.0);
+            }
+        }
+    }
+
+    pub fn acquire() -> Option<Lock> {
+        // Per session, so another signed-in user can run their own copy
+        match unsafe { CreateMutexW(None, false, w!(r"Local\FrameworkControlGui")) } {
+            Ok(handle) if unsafe { GetLastError() } == ERROR_ALREADY_EXISTS => {
+                unsafe {
+                    let _ = CloseHandle(handle);
+                }
+                None
+            }
+            Ok(handle) => Some(Lock(handle)),
+            Err(e) => {
+                tracing::warn!("Single-instance lock unavailable: {}", e);
+                Some(Lock(HANDLE::default()))
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use std::fs::{File, TryLockError};
+    use std::path::PathBuf;
+
+    pub struct Lock {
+        _file: Option<File>,
+    }
+
+    pub fn acquire() -> Option<Lock> {
+        let dir = std::env::var("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| std::env::temp_dir());
+        let path = dir.join("framework-control.lock");
+        let file = match File::create(&path) {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!("Single-instance lock unavailable: {}", e);
+                return Some(Lock { _file: None });
+            }
+        };
+        // The OS drops the lock with the process, so a crash never leaves it held
+        match file.try_lock() {
+            Ok(()) => Some(Lock { _file: Some(file) }),
+            Err(TryLockError::WouldBlock) => None,
+            Err(TryLockError::Error(e)) => {
+                tracing::warn!("Single-instance lock unavailable: {}", e);
+                Some(Lock { _file: None })
+            }
+        }
+    }
+}
@@ -0,0 +1,352 @@
+// Local control channel to the service. While the service runs it owns the EC, and the
+// GUI asks it for readings and hands it commands instead of opening the device as well.
+// Newline-delimited JSON over a named pipe, served on plain threads like the web API.
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli::{PowerBatteryInfo, ThermalParsed};
+use crate::types::MIN_CHARGE_DEADBAND_PCT;
+use crate::AppState;
+
+/// Longest a caller waits for the service before doing the job itself
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Set in the process serving the pipe, so its own tasks never ask themselves
+static SERVING: AtomicBool = AtomicBool::new(false);
+
+/// Set while the service answers this process's requests, so its own control tasks leave
+/// the EC to the service instead of fighting it
+static SERVICE_ANSWERING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum Request {
+    GetTelemetry,
+    SetFanDuty { duty_pct: u32 },
+    SetFanAuto,
+    SetChargeLimit { min_pct: u8, max_pct: u8 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "response", rename_all = "snake_case")]
+pub enum Response {
+    Telemetry {
+        thermal: Option<ThermalParsed>,
+        power: Option<PowerBatteryInfo>,
+    },
+    Done,
+    Failed {
+        error: String,
+    },
+}
+
+/// Answer requests until the process exits. Must be called from the tokio runtime the
+/// requests are handled on.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn serve(state: AppState) {
+    let runtime = tokio::runtime::Handle::current();
+    SERVING.store(true, Ordering::Relaxed);
+    std::thread::spawn(move || {
+        let mut listener = loop {
+            match platform::Listener::bind() {
+                Ok(listener) => break listener,
+                Err(e) if e.kind() == std::io::ErrorKind::Unsupported => return,
+                // Most likely another process already created the pipe; never share it
+                Err(e) => {
+                    tracing::error!("IPC pipe unavailable: {}", e);
+                    std::thread::sleep(Duration::from_secs(5));
+                }
+            }
+        };
+        loop {
+            match listener.accept() {
+                Ok(pipe) => {
+                    let (state, runtime) = (state.clone(), runtime.clone());
+                    std::thread::spawn(move || {
+                        if let Err(e) = handle(pipe, &state, &runtime) {
+                            tracing::debug!("IPC connection failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => {
+                    tracing::error!("IPC pipe unavailable: {}", e);
+                    std::thread::sleep(Duration::from_secs(5));
+                }
+            }
+        }
+    });
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn handle(
+    pipe: impl Read + Write,
+    state: &AppState,
+    runtime: &tokio::runtime::Handle,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(pipe);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => runtime.block_on(answer(state, request)),
+            Err(e) => Response::Failed {
+                error: format!("Bad request: {}", e),
+            },
+        };
+        let mut json = serde_json::to_string(&response)?;
+        json.push('\n');
+        reader.get_mut().write_all(json.as_bytes())?;
+        line.clear();
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+async fn answer(state: &AppState, request: Request) -> Response {
+    if let Err(error) = check(&request) {
+        return Response::Failed { error };
+    }
+    let Some(tool) = state.framework_tool.read().await.clone() else {
+        return Response::Failed {
+            error: "EC not available".to_string(),
+        };
+    };
+    let result = match request {
+        Request::GetTelemetry => {
+            let readings = state.readings.borrow();
+            return Response::Telemetry {
+                thermal: readings.thermal.clone(),
+                power: readings.power.clone(),
+            };
+        }
+        Request::SetFanDuty { duty_pct } => tool.set_fan_duty(duty_pct, None).await,
+        Request::SetFanAuto => tool.set_fan_control_auto(None).await,
+        Request::SetChargeLimit { min_pct, max_pct } => {
+            tool.charge_limit_set(min_pct, max_pct).await
+        }
+    };
+    match result {
+        Ok(()) => Response::Done,
+        Err(error) => Response::Failed { error },
+    }
+}
+
+/// Refuse values the GUI would never send, whoever is on the other end of the pipe
+fn check(request: &Request) -> Result<(), String> {
+    match *request {
+        Request::SetFanDuty { duty_pct } if duty_pct > 100 => {
+            Err(format!("Fan duty {}% is above 100%", duty_pct))
+        }
+        Request::SetChargeLimit { min_pct, max_pct } => {
+            if !(50..=100).contains(&max_pct) {
+                return Err(format!("Charge limit {}% is outside 50-100%", max_pct));
+            }
+            if min_pct > max_pct.saturating_sub(MIN_CHARGE_DEADBAND_PCT) {
+                return Err(format!(
+                    "Charging must resume at least {}% below the {}% limit",
+                    MIN_CHARGE_DEADBAND_PCT, max_pct
+                ));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether this process is the service answering the pipe
+pub fn is_serving() -> bool {
+    SERVING.load(Ordering::Relaxed)
+}
+
+/// Have the service handle `request`; `None` when no service is answering, in which case
+/// the caller talks to the EC itself
+pub async fn request(request: Request) -> Option<Response> {
+    if is_serving() {
+        return None;
+    }
+    let exchange = tokio::task::spawn_blocking(move || exchange(&request));
+    let response = match tokio::time::timeout(REQUEST_TIMEOUT, exchange).await {
+        Ok(Ok(Ok(response))) => Some(response),
+        // Usually just that the service isn't running
+        Ok(Ok(Err(e))) => {
+            tracing::trace!("Service not reachable: {}", e);
+            None
+        }
+        Ok(Err(_)) => None,
+        Err(_) => {
+            tracing::warn!("Service didn't answer within {:?}", REQUEST_TIMEOUT);
+            None
+        }
+    };
+    SERVICE_ANSWERING.store(response.is_some(), Ordering::Relaxed);
+    response
+}
+
+/// Whether the service answered this process's last request. The GUI's fan, power and
+/// battery tasks stand down meanwhile, since the service applies the same settings.
+pub fn service_answering() -> bool {
+    SERVICE_ANSWERING.load(Ordering::Relaxed)
+}
+
+fn exchange(request: &Request) -> std::io::Result<Response> {
+    let mut pipe = platform::connect()?;
+    let mut json = serde_json::to_string(request)?;
+    json.push('\n');
+    pipe.write_all(json.as_bytes())?;
+    let mut line = String::new();
+    BufReader::new(pipe).read_line(&mut line)?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Send a command to the service: `None` when it isn't running, otherwise its outcome
+pub async fn command(request: Request) -> Option<Result<(), String>> {
+    match self::request(request).await? {
+        Response::Done => Some(Ok(())),
+        Response::Failed { error } => Some(Err(error)),
+        Response::Telemetry { .. } => Some(Err("Unexpected reply from service".to_string())),
+    }
+}
+
+/// The service's latest readings, or `None` when it isn't running
+pub async fn telemetry() -> Option<(Option<ThermalParsed>, Option<PowerBatteryInfo>)> {
+    match request(Request::GetTelemetry).await? {
+        Response::Telemetry { thermal, power } => Some((thermal, power)),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::fs::File;
+    use std::os::windows::io::{AsRawHandle, FromRawHandle};
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::{
+        LocalFree, ERROR_PIPE_CONNECTED, HANDLE, HLOCAL, INVALID_HANDLE_VALUE,
+    };
+    use windows::Win32::Security::Authorization::{
+        ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+    };
+    use windows::Win32::Security::{PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES};
+    use windows::Win32::Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX};
+    use windows::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+        PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+
+    const PIPE_NAME: &str = r"\\.\pipe\FrameworkControl";
+
+    // Only SYSTEM and administrators, like the EC driver itself: anyone who can write to
+    // the pipe can drive the fans and charger
+    const PIPE_SDDL: PCWSTR = w!("D:(A;;GA;;;SY)(A;;GA;;;BA)");
+
+    pub fn connect() -> std::io::Result<File> {
+        std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(PIPE_NAME)
+    }
+
+    /// The pipe, always holding one instance that waits for the next client. The first
+    /// instance must be new, so a pipe some other process created first is never served.
+    pub struct Listener {
+        waiting: File,
+    }
+
+    impl Listener {
+        pub fn bind() -> std::io::Result<Self> {
+            Ok(Self {
+                waiting: create(true)?,
+            })
+        }
+
+        /// Wait for a client. The next instance is created before the connected one is
+        /// handed out, so the name is never free for another process to take.
+        pub fn accept(&mut self) -> std::io::Result<File> {
+            let handle = HANDLE(self.waiting.as_raw_handle());
+            let connected = unsafe { ConnectNamedPipe(handle, None) };
+            let next = create(false)?;
+            let pipe = std::mem::replace(&mut self.waiting, next);
+            match connected {
+                // A client that opened the pipe between create and connect is also connected
+                Err(e) if e.code() != ERROR_PIPE_CONNECTED.into() => Err(e.into()),
+                _ => Ok(pipe),
+            }
+        }
+    }
+
+    fn create(first: bool) -> std::io::Result<File> {
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                PIPE_SDDL,
+                SDDL_REVISION_1,
+                &mut descriptor,
+                None,
+            )?;
+        }
+        let attributes = SECURITY_ATTRIBUTES {
+            nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+            lpSecurityDescriptor: descriptor.0,
+            bInheritHandle: false.into(),
+        };
+        let mut open_mode = PIPE_ACCESS_DUPLEX;
+        if first {
+            open_mode |= FILE_FLAG_FIRST_PIPE_INSTANCE;
+        }
+        let name = windows::core::HSTRING::from(PIPE_NAME);
+        let handle = unsafe {
+            CreateNamedPipeW(
+                &name,
+                open_mode,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                4096,
+                4096,
+                0,
+                Some(&attributes as *const _),
+            )
+        };
+        // The pipe keeps its own copy of the descriptor
+        unsafe {
+            let _ = LocalFree(Some(HLOCAL(descriptor.0)));
+        }
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(unsafe { File::from_raw_handle(handle.0) })
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use std::fs::File;
+    use std::io::{Error, ErrorKind};
+
+    pub fn connect() -> std::io::Result<File> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "no service on this platform",
+        ))
+    }
+
+    pub struct Listener;
+
+    #[allow(dead_code)]
+    impl Listener {
+        pub fn bind() -> std::io::Result<Self> {
+            Err(Error::new(
+                ErrorKind::Unsupported,
+                "no service on this platform",
+            ))
+        }
+
+        pub fn accept(&mut self) -> std::io::Result<File> {
+            Err(Error::new(
+                ErrorKind::Unsupported,
+                "no service on this platform",
+            ))
+        }
+    }
+}
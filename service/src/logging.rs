@@ -0,0 +1,144 @@
+// Logging to stdout and to size-capped files. The GUI and the service have no console to
+// read, so EC trouble is only traceable from the files.
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// A log file is rotated once it grows past this
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated files kept besides the current one; older ones are deleted
+const KEPT_FILES: usize = 4;
+
+/// Directory `init` ended up writing to
+static CURRENT_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// `%PROGRAMDATA%\FrameworkControl\logs`, next to the shared config
+pub fn log_dir() -> PathBuf {
+    crate::config::config_path()
+        .parent()
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf)
+        .join("logs")
+}
+
+/// Where logs go when `log_dir()` can't be written, e.g. a GUI run by a standard user
+fn fallback_dir() -> PathBuf {
+    crate::config::fallback_path()
+        .parent()
+        .map_or_else(std::env::temp_dir, Path::to_path_buf)
+        .join("logs")
+}
+
+/// Install the global subscriber, logging to stdout and to `<name>.log`. The level comes
+/// from `RUST_LOG` (e.g. `debug`), defaulting to info.
+pub fn init(name: &str) {
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|spec| spec.trim().parse::<LevelFilter>().ok())
+        .unwrap_or(LevelFilter::INFO);
+    let file = [log_dir(), fallback_dir()]
+        .into_iter()
+        .find_map(|dir| RotatingFile::open(dir.join(format!("{}.log", name))).ok());
+    let path = file.as_ref().map(|f| f.path.clone());
+    if let Some(dir) = path.as_ref().and_then(|p| p.parent()) {
+        let _ = CURRENT_DIR.set(dir.to_path_buf());
+    }
+
+    let stdout = tracing_subscriber::fmt::layer().with_filter(level);
+    let file = file.map(|file| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(file)
+            .with_filter(level)
+    });
+    if tracing_subscriber::registry()
+        .with(stdout)
+        .with(file)
+        .try_init()
+        .is_err()
+    {
+        return;
+    }
+    match path {
+        Some(path) => tracing::info!("Logging to {}", path.display()),
+        None => tracing::warn!("No writable log directory; logging to stdout only"),
+    }
+}
+
+/// The directory this process logs to, for "Open Logs"
+pub fn current_dir() -> PathBuf {
+    CURRENT_DIR.get().cloned().unwrap_or_else(log_dir)
+}
+
+struct RotatingFile {
+    path: PathBuf,
+    state: Mutex<(File, u64)>,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            path,
+            state: Mutex::new((file, len)),
+        })
+    }
+
+    /// `name.log` becomes `name.1.log`, `name.1.log` becomes `name.2.log` and so on, the
+    /// oldest dropping off the end
+    fn rotate(&self, state: &mut (File, u64)) -> std::io::Result<()> {
+        let numbered = |n: usize| self.path.with_extension(format!("{}.log", n));
+        let _ = std::fs::remove_file(numbered(KEPT_FILES));
+        for n in (1..KEPT_FILES).rev() {
+            let _ = std::fs::rename(numbered(n), numbered(n + 1));
+        }
+        std::fs::rename(&self.path, numbered(1))?;
+        state.0 = File::create(&self.path)?;
+        state.1 = 0;
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for RotatingFile {
+    type Writer = RotatingWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RotatingWriter(self)
+    }
+}
+
+struct RotatingWriter<'a>(&'a RotatingFile);
+
+impl Write for RotatingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut state = self
+            .0
+            .state
+            .lock()
+            .map_err(|_| std::io::Error::other("log file lock poisoned"))?;
+        if state.1 > 0 && state.1 + buf.len() as u64 > MAX_FILE_BYTES {
+            // Keep logging to the full file rather than losing lines
+            let _ = self.0.rotate(&mut state);
+        }
+        let written = state.0.write(buf)?;
+        state.1 += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self.0.state.lock() {
+            Ok(mut state) => state.0.flush(),
+            Err(_) => Ok(()),
+        }
+    }
+}
@@ -2,18 +2,104 @@ use eframe::egui;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+mod activity;
+mod bundle;
+mod calibration;
+mod changelog;
 mod cli;
+mod commands;
 mod config;
+mod cpu;
+mod csv_export;
+mod curve;
+mod debounce;
+mod diagnostics;
 mod ec;
+mod fan_health;
+mod fw_fanctrl;
+mod hotkey;
+mod instance;
+mod ipc;
+mod logging;
+mod noise;
+mod notify;
+mod power_limits;
+mod preset;
+mod profiles;
+mod recording;
+mod service;
+mod snapshot;
+mod thermal_alert;
+mod tray;
 mod types;
+mod web;
 
 use types::*;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    // `--config <path>` lets the service share the GUI user's file
+    if let Some(path) = args
+        .iter()
+        .position(|a| a == "--config")
+        .and_then(|i| args.get(i + 1))
+    {
+        std::env::set_var("FRAMEWORK_CONTROL_CONFIG", path);
+    }
+    if args.get(1).is_some_and(|a| commands::is_command(a)) {
+        return commands::run(&args).map_err(|e| e.into());
+    }
+    let flag = |name: &str| args.iter().any(|a| a == name);
+    if flag("--capabilities") {
+        return print_capabilities();
+    }
+    if flag(service::RUN_FLAG) {
+        return service::run().map_err(|e| e.into());
+    }
+    if flag("--install-service") {
+        service::install()?;
+        println!(
+            "Installed and started service {} (config {})",
+            service::SERVICE_NAME,
+            config::config_path().display()
+        );
+        return Ok(());
+    }
+    if flag("--uninstall-service") {
+        service::uninstall()?;
+        println!("Removed service {}", service::SERVICE_NAME);
+        return Ok(());
+    }
     run_gui().map_err(|e| e.into())
 }
 
+/// `--capabilities`: print what this machine supports as JSON, without starting the GUI
+fn print_capabilities() -> Result<(), Box<dyn std::error::Error>> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let caps = runtime.block_on(async { cli::FrameworkTool::new().await.capabilities().await })?;
+    println!("{}", serde_json::to_string_pretty(&caps)?);
+    Ok(())
+}
+
 fn run_gui() -> Result<(), eframe::Error> {
+    let args: Vec<String> = std::env::args().collect();
+    // Dropped when the window closes; until then a second launch only focuses this one
+    let _instance = if args.iter().any(|a| a == instance::ALLOW_MULTIPLE_FLAG) {
+        None
+    } else {
+        match instance::acquire() {
+            Some(guard) => Some(guard),
+            None => {
+                eprintln!(
+                    "Framework Control is already running; pass {} to start another copy",
+                    instance::ALLOW_MULTIPLE_FLAG
+                );
+                tray::show_window();
+                return Ok(());
+            }
+        }
+    };
+
     // Simple .env file loading
     if let Ok(content) = std::fs::read_to_string(".env") {
         for line in content.lines() {
@@ -29,12 +115,14 @@ fn run_gui() -> Result<(), eframe::Error> {
         }
     }
 
+    logging::init("gui");
+    release_fans_on_console_close();
+
     // Create app state
     let runtime = tokio::runtime::Runtime::new().unwrap();
     let state = runtime.block_on(async { AppState::initialize().await });
 
     // Launch GUI directly - no background polling, data fetched on-demand
-    let args: Vec<String> = std::env::args().collect();
     let start_minimized = args.iter().any(|a| a == "--minimized");
 
     let options = eframe::NativeOptions {
@@ -52,13 +140,69 @@ fn run_gui() -> Result<(), eframe::Error> {
     )
 }
 
+/// Return the fans to the EC's own control if the app left them at a commanded duty and
+/// the config doesn't ask to keep it. Returns whether they were handed back.
+fn release_fans(cfg: &Config) -> bool {
+    cfg.restore_auto_on_exit
+        && matches!(ec::fan_control_state(), Some(ec::FanControlState::Duty(_)))
+        && ec::set_fan_auto()
+}
+
+/// Ctrl-C or closing the console window ends the process without `on_exit` running
+#[cfg(windows)]
+fn release_fans_on_console_close() {
+    use windows::core::BOOL;
+    use windows::Win32::System::Console::SetConsoleCtrlHandler;
+
+    unsafe extern "system" fn handler(_ctrl_type: u32) -> BOOL {
+        release_fans(&config::load());
+        // Let the default handler end the process
+        false.into()
+    }
+    unsafe {
+        let _ = SetConsoleCtrlHandler(Some(handler), true);
+    }
+}
+
+#[cfg(not(windows))]
+fn release_fans_on_console_close() {}
+
 // Application state
 #[derive(Clone)]
 pub struct AppState {
     pub framework_tool: Arc<RwLock<Option<cli::FrameworkTool>>>,
     pub config: Arc<RwLock<Config>>,
-    pub cache: Arc<RwLock<CachedData>>,
+    /// Latest EC readings, published by the telemetry task
+    pub readings: tokio::sync::watch::Sender<CachedData>,
     pub ec_status: Arc<RwLock<EcStatus>>,
+    /// While set and in the future, all fans are held at 100% regardless of mode
+    pub fan_boost_until: Arc<RwLock<Option<std::time::Instant>>>,
+    pub toasts: notify::Toasts,
+    pub activity: activity::Activity,
+    pub telemetry_samples: Arc<RwLock<std::collections::VecDeque<TelemetrySample>>>,
+    /// Fans currently running well below their calibrated speed
+    pub fan_alerts: Arc<RwLock<Vec<fan_health::FanAlert>>>,
+    /// Sensors that have stayed above the alert temperature
+    pub thermal_alerts: Arc<RwLock<Vec<thermal_alert::ThermalAlert>>>,
+    /// Active "record to file" session fed by the telemetry task
+    pub recording: Arc<RwLock<Option<recording::Recorder>>>,
+    /// Set while the curve has no usable sensor data and holds the fallback duty
+    pub sensor_fallback: Arc<RwLock<bool>>,
+    /// Hottest sensor and its moving average as the fan curve last used them
+    pub curve_temps: Arc<RwLock<Option<(f32, f32)>>>,
+    /// Set while the service runs but doesn't answer over IPC and direct EC reads keep
+    /// failing, i.e. the service is holding the EC
+    pub ec_owned_by_service: Arc<RwLock<bool>>,
+    /// When the fan task last commanded the fans successfully
+    pub fan_heartbeat: Arc<RwLock<Option<std::time::Instant>>>,
+    /// Set once the watchdog has handed the fans to the EC; cleared when commands succeed again
+    pub fan_watchdog_tripped: Arc<RwLock<bool>>,
+    /// Set after "Restore Firmware Defaults": background tasks stop re-applying the config
+    pub control_paused: Arc<RwLock<bool>>,
+    /// Set while a calibration run drives the fans; the fan task stands aside
+    pub fan_calibrating: Arc<RwLock<bool>>,
+    /// Detected once at startup; decides which power controls the board can offer
+    pub cpu_vendor: cpu::Vendor,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -74,30 +218,84 @@ pub struct CachedData {
     pub thermal: Option<cli::ThermalParsed>,
     pub power: Option<cli::PowerBatteryInfo>,
     pub versions: Option<cli::Versions>,
+    /// Live power draw against the limits, where ryzenadj applies them
+    pub ryzenadj: Option<power_limits::RyzenAdjInfo>,
 }
 
+// A held max-fan boost never expires on its own; the far-off expiry just reuses the
+// timed boost override
+const MAX_FAN_HOLD: std::time::Duration = std::time::Duration::from_secs(365 * 24 * 3600);
+
 impl AppState {
     pub async fn initialize() -> Self {
-        let config = Arc::new(RwLock::new(config::load()));
+        let loaded = config::load();
+        Self::apply_sensor_settings(&loaded);
+        let config = Arc::new(RwLock::new(loaded));
 
         let framework_tool = Arc::new(RwLock::new(Some(cli::FrameworkTool::new().await)));
         let ec_status = Arc::new(RwLock::new(EcStatus::Unknown));
+        let activity = activity::Activity::default();
 
-        Self::spawn_framework_tool_resolver(framework_tool.clone(), ec_status.clone());
+        Self::spawn_framework_tool_resolver(
+            framework_tool.clone(),
+            ec_status.clone(),
+            activity.clone(),
+        );
 
-        let cache = Arc::new(RwLock::new(CachedData::default()));
+        let (readings, _) = tokio::sync::watch::channel(CachedData::default());
 
         Self {
             framework_tool,
             config,
-            cache,
+            readings,
             ec_status,
+            fan_boost_until: Arc::new(RwLock::new(None)),
+            toasts: Arc::new(RwLock::new(Vec::new())),
+            activity,
+            telemetry_samples: Arc::new(RwLock::new(std::collections::VecDeque::new())),
+            fan_alerts: Arc::new(RwLock::new(Vec::new())),
+            thermal_alerts: Arc::new(RwLock::new(Vec::new())),
+            recording: Arc::new(RwLock::new(None)),
+            sensor_fallback: Arc::new(RwLock::new(false)),
+            fan_heartbeat: Arc::new(RwLock::new(None)),
+            ec_owned_by_service: Arc::new(RwLock::new(false)),
+            curve_temps: Arc::new(RwLock::new(None)),
+            fan_watchdog_tripped: Arc::new(RwLock::new(false)),
+            control_paused: Arc::new(RwLock::new(false)),
+            fan_calibrating: Arc::new(RwLock::new(false)),
+            cpu_vendor: cpu::vendor(),
+        }
+    }
+
+    /// Force every fan to 100% until the boost is cleared, overriding any mode
+    pub async fn hold_max_fans(&self) -> Result<(), String> {
+        let mut boost = self.fan_boost_until.write().await;
+        let now = std::time::Instant::now();
+        *boost = Some(now.checked_add(MAX_FAN_HOLD).unwrap_or(now));
+        // Commanded under the lock, so the fan task can't write a curve duty after this
+        cli::FrameworkTool::new()
+            .await
+            .set_fan_duty(100, None)
+            .await
+    }
+
+    /// Stop re-applying the config and release the fans, as the process shuts down
+    pub async fn release_fans(&self, reason: &str) {
+        // Otherwise the fan task could command a duty again right after
+        *self.control_paused.write().await = true;
+        // A fan step already past its pause check holds the read lock through its writes;
+        // waiting for it means nothing lands after the fans are handed back
+        let _boost = self.fan_boost_until.write().await;
+        let cfg = self.config.read().await.clone();
+        if let Ok(true) = tokio::task::spawn_blocking(move || release_fans(&cfg)).await {
+            tracing::info!("Fans returned to EC control on {}", reason);
         }
     }
 
     fn spawn_framework_tool_resolver(
         ft_lock: Arc<RwLock<Option<cli::FrameworkTool>>>,
         ec_status: Arc<RwLock<EcStatus>>,
+        activity: activity::Activity,
     ) {
         tokio::spawn(async move {
             use tokio::time::{sleep, Duration};
@@ -110,7 +308,42 @@ impl AppState {
                     Err(_) => EcStatus::DriverMissing,
                 };
 
-                *ec_status.write().await = status.clone();
+                let previous = std::mem::replace(&mut *ec_status.write().await, status.clone());
+                if previous != status {
+                    let message = match (&previous, &status) {
+                        (_, EcStatus::Connected) if previous != EcStatus::Unknown => {
+                            "EC reconnected"
+                        }
+                        (_, EcStatus::Connected) => "EC connected",
+                        (_, EcStatus::AccessDenied) => "EC access denied",
+                        _ => "EC driver unavailable",
+                    };
+                    activity::push(&activity, message).await;
+
+                    // Firmware may have changed across a reconnect (e.g. after an update)
+                    if status == EcStatus::Connected {
+                        if let Ok((firmware, chosen)) =
+                            tokio::task::spawn_blocking(ec::detect_command_versions).await
+                        {
+                            let versions = chosen
+                                .iter()
+                                .map(|(cmd, ver)| format!("0x{:02X}=v{}", cmd, ver))
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            let firmware = firmware.unwrap_or_else(|| "unknown".to_string());
+                            tracing::info!(
+                                "EC firmware {}; command versions {}",
+                                firmware,
+                                versions
+                            );
+                            activity::push(
+                                &activity,
+                                format!("EC firmware {} (commands {})", firmware, versions),
+                            )
+                            .await;
+                        }
+                    }
+                }
 
                 if status == EcStatus::Connected {
                     let current = { ft_lock.read().await.clone() };
@@ -137,51 +370,20 @@ impl AppState {
         });
     }
 
-    // Update EC command handling to ensure proper fan control
-    pub async fn update_fan_curve(&self) {
-        if let Some(ft) = self.framework_tool.read().await.as_ref() {
-            if let Ok(thermal) = ft.read_thermal().await {
-                let max_temp = thermal
-                    .sensors
-                    .iter()
-                    .map(|s| s.temp_c)
-                    .fold(f32::NEG_INFINITY, f32::max);
-                let target_duty = {
-                    let config = self.config.read().await;
-                    let curve = config.fan.curve.clone().unwrap_or_else(|| CurveConfig {
-                        points: vec![[40, 20], [50, 30], [60, 40], [70, 60], [80, 80], [90, 100]],
-                        ..Default::default()
-                    });
-                    self.calculate_fan_duty(max_temp, &curve)
-                };
-                let _ = ft.set_fan_duty(target_duty as u32, None).await;
-            }
-        }
-    }
-
-    fn calculate_fan_duty(&self, temp: f32, curve: &CurveConfig) -> f32 {
-        let mut sorted_points = curve.points.clone();
-        sorted_points.sort_by(|a, b| a[0].cmp(&b[0]));
-
-        for i in 0..sorted_points.len() {
-            let p1 = sorted_points[i];
-            if i == 0 && temp <= p1[0] as f32 {
-                return p1[1] as f32;
-            }
-            if i == sorted_points.len() - 1 {
-                return p1[1] as f32;
-            }
-            let p2 = sorted_points[i + 1];
-            if temp >= p1[0] as f32 && temp <= p2[0] as f32 {
-                let t1 = p1[0] as f32;
-                let t2 = p2[0] as f32;
-                let d1 = p1[1] as f32;
-                let d2 = p2[1] as f32;
-                let ratio = (temp - t1) / (t2 - t1);
-                return d1 + (d2 - d1) * ratio;
-            }
+    /// Sensor corrections are process-wide rather than read from `config` on every sample
+    pub fn apply_sensor_settings(cfg: &Config) {
+        cli::set_sensor_offsets(cfg.sensor_offsets.clone());
+        let conversion =
+            ec::TempConversion::resolve(cli::mainboard().as_deref(), &cfg.temp_conversion);
+        if conversion != ec::TempConversion::default() {
+            tracing::info!(
+                "EC temperatures: raw - {}, valid {}..{}°C",
+                conversion.offset,
+                conversion.valid_c.0,
+                conversion.valid_c.1
+            );
         }
-        50.0 // Default duty
+        ec::set_temp_conversion(conversion);
     }
 }
 
@@ -192,17 +394,61 @@ mod tasks {
     pub async fn boot(state: &AppState) {
         // Fan curve task
         {
-            let cfg_clone = state.config.clone();
+            let state = state.clone();
             tokio::spawn(async move {
-                fan_curve::run(cfg_clone).await;
+                fan_curve::run(state).await;
+            });
+        }
+
+        // Fan watchdog task
+        {
+            let state = state.clone();
+            tokio::spawn(async move {
+                watchdog::run(state).await;
             });
         }
 
         // Power settings task
         {
+            let ft_clone = state.framework_tool.clone();
+            let cfg_clone = state.config.clone();
+            let activity = state.activity.clone();
+            let paused = state.control_paused.clone();
+            tokio::spawn(async move {
+                power::run(ft_clone, cfg_clone, activity, paused).await;
+            });
+        }
+
+        // Telemetry history task
+        {
+            let ft_clone = state.framework_tool.clone();
             let cfg_clone = state.config.clone();
+            let readings = state.readings.clone();
+            let samples = state.telemetry_samples.clone();
+            let recording = state.recording.clone();
+            let activity = state.activity.clone();
+            let owned = state.ec_owned_by_service.clone();
+            tokio::spawn(async move {
+                telemetry::run(
+                    ft_clone, cfg_clone, readings, samples, recording, activity, owned,
+                )
+                .await;
+            });
+        }
+
+        // Failing fan detection task
+        {
+            let state = state.clone();
+            tokio::spawn(async move {
+                fan_health::run(state).await;
+            });
+        }
+
+        // Overheat alert task
+        {
+            let state = state.clone();
             tokio::spawn(async move {
-                power::run(cfg_clone).await;
+                thermal_alerts::run(state).await;
             });
         }
 
@@ -210,184 +456,1409 @@ mod tasks {
         {
             let ft_clone = state.framework_tool.clone();
             let cfg_clone = state.config.clone();
+            let toasts = state.toasts.clone();
+            let activity = state.activity.clone();
+            let paused = state.control_paused.clone();
+            tokio::spawn(async move {
+                battery::run(ft_clone, cfg_clone, toasts, activity, paused).await;
+            });
+        }
+
+        // Global panic/restore fan hotkeys
+        {
+            let state = state.clone();
+            tokio::spawn(async move {
+                hotkeys::run(state).await;
+            });
+        }
+
+        // Web dashboard and telemetry API
+        {
+            let state = state.clone();
             tokio::spawn(async move {
-                battery::run(ft_clone, cfg_clone).await;
+                let api = state.config.read().await.api.clone();
+                if !api.enabled {
+                    return;
+                }
+                let message = match web::serve(&api.bind, state.telemetry_samples.clone()) {
+                    Ok(addr) => {
+                        tracing::info!("Web dashboard at http://{}/", addr);
+                        format!("Web dashboard at http://{}/", addr)
+                    }
+                    Err(e) => {
+                        tracing::warn!("Web dashboard not started: {}", e);
+                        format!("Web dashboard not started: {}", e)
+                    }
+                };
+                activity::push(&state.activity, message).await;
             });
         }
     }
 
     mod fan_curve {
         use super::*;
-        pub async fn run(cfg: Arc<RwLock<Config>>) {
+        use std::collections::BTreeMap;
+        use std::time::Duration;
+
+        /// Tracks spin-up kicks so a fan that never starts isn't kicked forever
+        #[derive(Default)]
+        struct Kicker {
+            failed: u32,
+            gave_up: bool,
+        }
+
+        impl Kicker {
+            /// Whether to pulse before settling on `target`, given the fastest measured fan
+            fn should_kick(&mut self, kick: &SpinUpKick, target: u32, rpm: Option<f32>) -> bool {
+                let Some(rpm) = rpm else {
+                    return false;
+                };
+                if rpm > 0.0 {
+                    self.failed = 0;
+                    self.gave_up = false;
+                    return false;
+                }
+                if target == 0 || target >= kick.duty_pct {
+                    return false;
+                }
+                if self.failed >= kick.max_attempts {
+                    if !self.gave_up {
+                        tracing::warn!(
+                            "Fan did not start after {} spin-up kicks; not kicking again until it spins",
+                            self.failed
+                        );
+                        self.gave_up = true;
+                    }
+                    return false;
+                }
+                self.failed += 1;
+                true
+            }
+        }
+
+        /// Set `fan` (every fan for `None`), kicking it first if it is stalled below the kick
+        /// duty. True if the EC took the duty.
+        async fn set_duty(
+            fan: Option<u32>,
+            duty: u32,
+            kick: Option<&SpinUpKick>,
+            kicker: &mut Kicker,
+        ) -> bool {
+            let tool = cli::FrameworkTool::new().await;
+            if let Some(kick) = kick {
+                let rpm = tool.read_thermal().await.ok().and_then(|t| match fan {
+                    Some(fan) => t.fans.get(fan as usize).copied(),
+                    None => t.fans.into_iter().reduce(f32::max),
+                });
+                if kicker.should_kick(kick, duty, rpm) {
+                    tracing::info!(
+                        "Spin-up kick: {}% for {}ms",
+                        kick.duty_pct,
+                        kick.duration_ms
+                    );
+                    let _ = tool.set_fan_duty(kick.duty_pct, fan).await;
+                    tokio::time::sleep(tokio::time::Duration::from_millis(kick.duration_ms)).await;
+                }
+            }
+            tool.set_fan_duty(duty, fan).await.is_ok()
+        }
+
+        async fn beat(heartbeat: &RwLock<Option<std::time::Instant>>) {
+            *heartbeat.write().await = Some(std::time::Instant::now());
+        }
+
+        pub async fn run(state: AppState) {
+            let AppState {
+                config: cfg,
+                fan_boost_until: boost_until,
+                activity,
+                sensor_fallback,
+                control_paused: paused,
+                fan_calibrating: calibrating,
+                fan_heartbeat: heartbeat,
+                curve_temps,
+                ..
+            } = state;
             println!("🚀 Fan control background service started");
+            let mut fallback = curve::SensorFallback::default();
+            // Last duty applied in curve mode; the rate limiter steps from here
+            let mut last_duty: Option<f32> = None;
+            // Per-fan duties while individual fan curves are in use
+            let mut last_fan_duties: BTreeMap<u32, f32> = BTreeMap::new();
+            // Temperature the curve was last evaluated at, for the hysteresis band
+            let mut held_temp: Option<f32> = None;
+            // Moving average of the hottest sensor
+            let mut smoothed: Option<f32> = None;
+            let mut last_mode: Option<FanControlMode> = None;
+            let mut kickers: BTreeMap<Option<u32>, Kicker> = BTreeMap::new();
+            let mut dwells: BTreeMap<Option<u32>, curve::Dwell> = BTreeMap::new();
+            let mut ac = debounce::Debounce::default();
             loop {
-                let (mode, curve, manual_duty) = {
+                // Boost overrides whatever mode is configured until it expires. The read
+                // lock is held through this step's writes, so a boost started meanwhile
+                // waits for them and then has the last word; so does a shutdown releasing
+                // the fans, which pauses control first.
+                let boost = boost_until.read().await;
+                if *paused.read().await || *calibrating.read().await || ipc::service_answering() {
+                    drop(boost);
+                    // The firmware, a calibration run or the service owns the fans; the mode is
+                    // re-logged once control resumes
+                    last_mode = None;
+                    last_duty = None;
+                    last_fan_duties.clear();
+                    held_temp = None;
+                    smoothed = None;
+                    *curve_temps.write().await = None;
+                    dwells.clear();
+                    tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
+                    continue;
+                }
+                if boost.is_some_and(|t| std::time::Instant::now() < t) {
+                    last_duty = Some(100.0);
+                    last_fan_duties.clear();
+                    if cli::FrameworkTool::new()
+                        .await
+                        .set_fan_duty(100, None)
+                        .await
+                        .is_ok()
+                    {
+                        beat(&heartbeat).await;
+                    }
+                    drop(boost);
+                    tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
+                    continue;
+                }
+
+                // The power source only matters with separate AC and battery curves
+                let (split, hold) = {
+                    let c = cfg.read().await;
+                    let hold = c
+                        .ac_debounce_s
+                        .map_or(power::DEFAULT_AC_DEBOUNCE, Duration::from_secs);
+                    (c.fan.has_power_source_curves(), hold)
+                };
+                if split {
+                    let tool = cli::FrameworkTool::new().await;
+                    if let Ok(info) = tool.read_power_info().await {
+                        let on_ac = info.status.contains("Charging");
+                        if let Some(on_ac) = ac.update(on_ac, std::time::Instant::now(), hold) {
+                            let source = if on_ac { "AC" } else { "battery" };
+                            tracing::info!("Fan curve following the switch to {}", source);
+                            activity::push(&activity, format!("Fan curve for {} power", source))
+                                .await;
+                        }
+                    }
+                }
+
+                let (mode, curve, fan_curves, manual_duty, kick, max_duty, fallback_duty) = {
                     let c = cfg.read().await;
                     let mode = c.fan.mode.clone().unwrap_or(FanControlMode::Curve);
-                    let curve = c.fan.curve.clone().unwrap_or_else(|| CurveConfig {
-                        points: vec![[40, 20], [50, 30], [60, 40], [70, 60], [80, 80], [90, 100]],
-                        ..Default::default()
-                    });
+                    let on_ac = if split { ac.stable() } else { None };
+                    let curve = c
+                        .fan
+                        .curve_for(on_ac)
+                        .cloned()
+                        .unwrap_or_else(|| CurveConfig {
+                            points: vec![
+                                [40, 20],
+                                [50, 30],
+                                [60, 40],
+                                [70, 60],
+                                [80, 80],
+                                [90, 100],
+                            ],
+                            ..Default::default()
+                        });
                     let manual = c
                         .fan
                         .manual
                         .clone()
                         .unwrap_or(ManualConfig { duty_pct: 50 });
-                    (mode, curve, manual.duty_pct)
+                    (
+                        mode,
+                        curve,
+                        c.fan.fan_curves.clone(),
+                        manual.duty_pct,
+                        c.fan.spin_up_kick.clone(),
+                        c.fan.max_duty_pct,
+                        c.fan
+                            .fallback_duty_pct
+                            .unwrap_or(curve::DEFAULT_FALLBACK_DUTY),
+                    )
                 };
 
                 // println!("📊 Fan mode: {:?}", mode);
+                if last_mode.as_ref() != Some(&mode) {
+                    let message = match mode {
+                        FanControlMode::Curve => "Applied fan curve".to_string(),
+                        FanControlMode::Manual => format!("Fans set to {}%", manual_duty),
+                        FanControlMode::Disabled => "Fans returned to EC auto control".to_string(),
+                    };
+                    activity::push(&activity, message).await;
+                    last_mode = Some(mode.clone());
+                }
 
                 match mode {
                     FanControlMode::Curve => {
-                        if let Ok(thermal) = cli::FrameworkTool::new().await.read_thermal().await {
-                            let max_temp = thermal
-                                .sensors
-                                .iter()
-                                .map(|s| s.temp_c)
-                                .fold(f32::NEG_INFINITY, f32::max);
-
-                            // Interpolate
-                            let mut target_duty = 50.0;
-                            let points = curve.points;
-                            // Sort points just in case
-                            let mut sorted_points = points.clone();
-                            sorted_points.sort_by(|a, b| a[0].cmp(&b[0]));
-
-                            for i in 0..sorted_points.len() {
-                                let p1 = sorted_points[i];
-                                if i == 0 && max_temp <= p1[0] as f32 {
-                                    target_duty = p1[1] as f32;
-                                    break;
+                        let thermal = cli::FrameworkTool::new().await.read_thermal().await.ok();
+                        let hottest = thermal
+                            .as_ref()
+                            .and_then(|t| t.sensors.iter().map(|s| s.temp_c).reduce(f32::max));
+                        let fan_count = thermal.as_ref().map_or(0, |t| t.fans.len());
+                        let step = fallback.update(hottest);
+                        if *sensor_fallback.read().await != fallback.active() {
+                            *sensor_fallback.write().await = fallback.active();
+                            if !fallback.active() {
+                                tracing::info!("Sensor data is back; resuming the fan curve");
+                                activity::push(&activity, "Sensor data is back; fan curve resumed")
+                                    .await;
+                            }
+                        }
+                        match step {
+                            curve::FallbackStep::Fallback { engaged } => {
+                                if engaged {
+                                    tracing::warn!(
+                                        "No usable temperature for {} polls; holding fans at {}%",
+                                        curve::EMPTY_POLLS_BEFORE_FALLBACK,
+                                        fallback_duty
+                                    );
+                                    activity::push(
+                                        &activity,
+                                        format!(
+                                            "No usable sensor data; fans held at {}%",
+                                            fallback_duty
+                                        ),
+                                    )
+                                    .await;
+                                }
+                                // Uncapped: with no temperature there is nothing to judge the cap against
+                                last_duty = Some(fallback_duty as f32);
+                                smoothed = None;
+                                last_fan_duties.clear();
+                                if set_duty(
+                                    None,
+                                    fallback_duty,
+                                    kick.as_ref(),
+                                    kickers.entry(None).or_default(),
+                                )
+                                .await
+                                {
+                                    beat(&heartbeat).await;
+                                }
+                            }
+                            curve::FallbackStep::Curve(max_temp) => {
+                                // The cap still sees the raw reading, so a real spike isn't
+                                // averaged away before it can lift the cap
+                                let smooth_temp = curve.smooth(smoothed, max_temp);
+                                smoothed = Some(smooth_temp);
+                                *curve_temps.write().await = Some((max_temp, smooth_temp));
+                                let curve_temp = curve.hysteresis(held_temp, smooth_temp);
+                                held_temp = Some(curve_temp);
+                                let duties = curve::fan_duties(
+                                    &curve.points,
+                                    &fan_curves,
+                                    fan_count,
+                                    curve_temp,
+                                    curve.interpolation,
+                                );
+                                if duties.iter().all(|(fan, _)| fan.is_none()) {
+                                    last_fan_duties.clear();
                                 }
-                                if i == sorted_points.len() - 1 {
-                                    target_duty = p1[1] as f32;
-                                    break;
+                                let mut highest = 0.0f32;
+                                let mut all_ok = true;
+                                let min_dwell = Duration::from_secs(curve.min_dwell_s as u64);
+                                for (fan, target_duty) in duties {
+                                    let last = fan
+                                        .and_then(|f| last_fan_duties.get(&f).copied())
+                                        .or(last_duty);
+                                    let target_duty = dwells.entry(fan).or_default().apply(
+                                        last,
+                                        target_duty,
+                                        min_dwell,
+                                    );
+                                    let target_duty = match last {
+                                        Some(last) => curve.rate_limit(last, target_duty),
+                                        None => target_duty,
+                                    };
+                                    let target_duty =
+                                        curve::cap_duty(target_duty, max_duty, max_temp);
+                                    if let Some(fan) = fan {
+                                        last_fan_duties.insert(fan, target_duty);
+                                    }
+                                    highest = highest.max(target_duty);
+                                    // println!("🌡️ Max temp: {:.1}°C → Fan: {}%", max_temp, target_duty as u32);
+                                    all_ok &= set_duty(
+                                        fan,
+                                        target_duty as u32,
+                                        kick.as_ref(),
+                                        kickers.entry(fan).or_default(),
+                                    )
+                                    .await;
                                 }
-                                let p2 = sorted_points[i + 1];
-                                if max_temp >= p1[0] as f32 && max_temp <= p2[0] as f32 {
-                                    let t1 = p1[0] as f32;
-                                    let t2 = p2[0] as f32;
-                                    let d1 = p1[1] as f32;
-                                    let d2 = p2[1] as f32;
-                                    let ratio = (max_temp - t1) / (t2 - t1);
-                                    target_duty = d1 + (d2 - d1) * ratio;
-                                    break;
+                                last_duty = Some(highest);
+                                if all_ok {
+                                    beat(&heartbeat).await;
                                 }
                             }
-                            // println!("🌡️ Max temp: {:.1}°C → Fan: {}%", max_temp, target_duty as u32);
-                            let _ = cli::FrameworkTool::new()
-                                .await
-                                .set_fan_duty(target_duty as u32, None)
-                                .await;
+                            // Too soon to act on missing data; leave the fan where it is
+                            curve::FallbackStep::Hold => {}
                         }
                     }
                     FanControlMode::Manual => {
+                        let max_temp = match cli::FrameworkTool::new().await.read_thermal().await {
+                            Ok(thermal) => thermal
+                                .sensors
+                                .iter()
+                                .map(|s| s.temp_c)
+                                .fold(f32::NEG_INFINITY, f32::max),
+                            Err(_) => f32::NEG_INFINITY,
+                        };
+                        let duty = curve::cap_duty(manual_duty as f32, max_duty, max_temp) as u32;
+                        last_duty = Some(duty as f32);
+                        last_fan_duties.clear();
                         // println!("🎛️ Manual mode: {}%", manual_duty);
-                        let _ = cli::FrameworkTool::new()
+                        if set_duty(None, duty, kick.as_ref(), kickers.entry(None).or_default())
                             .await
-                            .set_fan_duty(manual_duty, None)
-                            .await;
+                        {
+                            beat(&heartbeat).await;
+                        }
                     }
                     FanControlMode::Disabled => {
-                        // Auto mode
+                        // Auto mode; the EC's duty is unknown so the next curve step starts fresh
+                        last_duty = None;
+                        last_fan_duties.clear();
+                        held_temp = None;
+                        smoothed = None;
+                        *curve_temps.write().await = None;
+                        dwells.clear();
                         // println!("🔄 Auto mode active");
-                        let _ = cli::FrameworkTool::new()
+                        if cli::FrameworkTool::new()
                             .await
                             .set_fan_control_auto(None)
-                            .await;
+                            .await
+                            .is_ok()
+                        {
+                            beat(&heartbeat).await;
+                        }
                     }
                 }
-                tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
+                drop(boost);
+                tokio::time::sleep(curve.poll_interval()).await;
             }
         }
     }
 
     mod power {
         use super::*;
-        pub async fn run(cfg: Arc<RwLock<Config>>) {
-            let _ = cfg;
+        use std::time::{Duration, SystemTime};
+
+        const POLL: Duration = Duration::from_secs(5);
+        // A wall-clock gap this much longer than the poll means the machine was asleep
+        const RESUME_GAP: Duration = Duration::from_secs(30);
+        pub const DEFAULT_AC_DEBOUNCE: Duration = Duration::from_secs(10);
+
+        /// Limits set through ryzenadj don't survive a reboot or a resume, so the stored
+        /// profile is re-asserted at startup and whenever the machine wakes up. Plugging in
+        /// or unplugging switches profiles once the new AC state has settled. Without a
+        /// backend (any non-AMD CPU) nothing is re-asserted; Intel RAPL isn't supported.
+        pub async fn run(
+            ft: Arc<RwLock<Option<cli::FrameworkTool>>>,
+            cfg: Arc<RwLock<Config>>,
+            activity: activity::Activity,
+            paused: Arc<RwLock<bool>>,
+        ) {
+            let mut last_tick: Option<SystemTime> = None;
+            let mut ac = debounce::Debounce::default();
             loop {
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                if *paused.read().await || ipc::service_answering() {
+                    // Re-assert the stored limits once control resumes
+                    last_tick = None;
+                    tokio::time::sleep(POLL).await;
+                    continue;
+                }
+                if let Some(tool) = ft.read().await.as_ref() {
+                    if let Ok(info) = tool.read_power_info().await {
+                        let (power, hold) = {
+                            let c = cfg.read().await;
+                            let hold = c
+                                .ac_debounce_s
+                                .map_or(DEFAULT_AC_DEBOUNCE, Duration::from_secs);
+                            (c.power.clone(), hold)
+                        };
+                        let on_ac = info.status.contains("Charging");
+                        if let Some(on_ac) = ac.update(on_ac, std::time::Instant::now(), hold) {
+                            let source = if on_ac { "AC" } else { "battery" };
+                            tracing::info!("Power source settled on {}", source);
+                            let profile = if on_ac {
+                                power.ac.as_ref()
+                            } else {
+                                power.battery.as_ref()
+                            };
+                            let applied = match profile {
+                                Some(profile) => apply_limits(tool, profile, source).await,
+                                None => None,
+                            };
+                            let message = match applied {
+                                Some(applied) => {
+                                    format!("Switched to {} power: {}", source, applied)
+                                }
+                                None => format!("Switched to {} power", source),
+                            };
+                            activity::push(&activity, message).await;
+                        }
+                    }
+                }
+
+                let now = SystemTime::now();
+                let reason = match last_tick {
+                    None => Some("startup"),
+                    Some(t) => now
+                        .duration_since(t)
+                        .is_ok_and(|gap| gap > POLL + RESUME_GAP)
+                        .then_some("resume"),
+                };
+                last_tick = Some(now);
+
+                if let Some(reason) = reason {
+                    if let Some(tool) = ft.read().await.as_ref() {
+                        let power = cfg.read().await.power.clone();
+                        if let Some(applied) = reassert(tool, &power, reason).await {
+                            activity::push(
+                                &activity,
+                                format!("Re-applied {} on {}", applied, reason),
+                            )
+                            .await;
+                        }
+                    }
+                }
+                tokio::time::sleep(POLL).await;
+            }
+        }
+
+        async fn reassert(
+            tool: &cli::FrameworkTool,
+            power: &PowerConfig,
+            reason: &str,
+        ) -> Option<String> {
+            let on_ac = tool
+                .read_power_info()
+                .await
+                .is_ok_and(|p| p.status.contains("Charging"));
+            let profile = if on_ac {
+                power.ac.as_ref()
+            } else {
+                power.battery.as_ref()
+            };
+            apply_limits(tool, profile?, reason).await
+        }
+
+        /// Apply a profile's enabled limits; returns a summary of what was applied, if anything
+        pub async fn apply_limits(
+            tool: &cli::FrameworkTool,
+            profile: &PowerProfile,
+            reason: &str,
+        ) -> Option<String> {
+            // Nothing can take the limits, so trying would only log a failure every time
+            if tool.power_limits_backend().await == power_limits::Backend::Unavailable {
+                return None;
+            }
+            let mut applied = Vec::new();
+
+            if let Some((stapm, fast, slow)) = profile.power_limits_w() {
+                match tool.set_power_limits(stapm, fast, slow).await {
+                    Ok(()) => {
+                        let summary = describe_power_limits(stapm, fast, slow);
+                        tracing::info!("Re-applied {} on {}", summary, reason);
+                        applied.push(summary);
+                    }
+                    Err(e) => tracing::warn!("Could not re-apply TDP on {}: {}", reason, e),
+                }
+            }
+            if let Some(thermal) = profile.thermal_limit_c.as_ref().filter(|s| s.enabled) {
+                match tool.set_thermal_limit_c(thermal.value).await {
+                    Ok(()) => {
+                        tracing::info!("Re-applied {}°C limit on {}", thermal.value, reason);
+                        applied.push(format!("{}°C limit", thermal.value));
+                    }
+                    Err(e) => {
+                        tracing::warn!("Could not re-apply thermal limit on {}: {}", reason, e)
+                    }
+                }
             }
+            (!applied.is_empty()).then(|| applied.join(" / "))
         }
     }
 
-    mod battery {
+    mod fan_health {
         use super::*;
-        pub async fn run(ft: Arc<RwLock<Option<cli::FrameworkTool>>>, cfg: Arc<RwLock<Config>>) {
+
+        pub async fn run(state: AppState) {
             loop {
-                let limit_setting = {
-                    let c = cfg.read().await;
-                    c.battery.charge_limit_max_pct.clone()
+                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+
+                let (calibration, tolerance) = {
+                    let cfg = state.config.read().await;
+                    (
+                        cfg.fan.calibration.clone(),
+                        cfg.fan
+                            .rpm_alert_tolerance_pct
+                            .unwrap_or(crate::fan_health::DEFAULT_TOLERANCE_PCT),
+                    )
+                };
+                let Some(calibration) = calibration else {
+                    continue;
+                };
+                let history: Vec<TelemetrySample> = {
+                    let samples = state.telemetry_samples.read().await;
+                    let start = samples.len().saturating_sub(120);
+                    samples.range(start..).cloned().collect()
+                };
+                let Some(alerts) = crate::fan_health::check(&history, &calibration, tolerance)
+                else {
+                    continue;
                 };
 
-                if let Some(setting) = limit_setting {
-                    if setting.enabled {
-                        if let Some(tool) = ft.read().await.as_ref() {
-                            let _ = tool.charge_limit_set(setting.value).await;
-                        }
+                // Notify only for fans that weren't already flagged
+                let mut current = state.fan_alerts.write().await;
+                for alert in &alerts {
+                    if !current.iter().any(|a| a.fan == alert.fan) {
+                        tracing::warn!("{}", alert.message());
+                        activity::push(&state.activity, alert.message()).await;
+                        notify::push(
+                            &state.toasts,
+                            &format!("Fan {} may be failing", alert.fan + 1),
+                            &alert.message(),
+                        )
+                        .await;
                     }
                 }
-                tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+                // A fan only clears once it has been judged again and passed
+                *current = alerts;
             }
         }
     }
-}
 
-struct FrameworkControlApp {
-    state: AppState,
-    runtime: tokio::runtime::Runtime,
+    mod watchdog {
+        use super::*;
+        use std::time::{Duration, Instant};
 
-    start_on_boot: bool,
+        const POLL: Duration = Duration::from_secs(5);
 
-    // Cached data
-    thermal_data: Option<cli::ThermalParsed>,
-    power_data: Option<cli::PowerBatteryInfo>,
-    versions: Option<cli::Versions>,
+        /// If the fan task dies or every EC write fails, the last duty would stay forever.
+        /// Once no command has succeeded within the timeout, the fans go back to EC auto
+        /// control, whose own thermal protection then takes over.
+        pub async fn run(state: AppState) {
+            // Counted from startup, and from resuming after a pause or calibration
+            let mut since = Instant::now();
+            loop {
+                tokio::time::sleep(POLL).await;
+
+                if *state.control_paused.read().await
+                    || *state.fan_calibrating.read().await
+                    || ipc::service_answering()
+                {
+                    since = Instant::now();
+                    continue;
+                }
+                let timeout = {
+                    let cfg = state.config.read().await;
+                    curve::watchdog_timeout(cfg.fan.watchdog_secs, cfg.fan.longest_poll())
+                };
+                let last_ok = state
+                    .fan_heartbeat
+                    .read()
+                    .await
+                    .map_or(since, |t| t.max(since));
+                let stale = last_ok.elapsed();
+
+                let mut tripped = state.fan_watchdog_tripped.write().await;
+                match curve::watchdog_step(stale, timeout, *tripped) {
+                    curve::WatchdogStep::Healthy | curve::WatchdogStep::Tripped => continue,
+                    curve::WatchdogStep::Recovered => {
+                        tracing::info!("Fan commands are succeeding again; watchdog cleared");
+                        activity::push(&state.activity, "Fan control recovered").await;
+                        *tripped = false;
+                        continue;
+                    }
+                    curve::WatchdogStep::Trip => *tripped = true,
+                }
+                let result = cli::FrameworkTool::new()
+                    .await
+                    .set_fan_control_auto(None)
+                    .await;
+                let message = match &result {
+                    Ok(()) => format!(
+                        "No fan command succeeded for {}s; fans handed to EC auto control",
+                        stale.as_secs()
+                    ),
+                    Err(e) => format!(
+                        "No fan command succeeded for {}s, and EC auto control failed too: {}",
+                        stale.as_secs(),
+                        e
+                    ),
+                };
+                tracing::error!("Fan watchdog: {}", message);
+                activity::push(&state.activity, message.clone()).await;
+                notify::push(&state.toasts, "Fan control stopped responding", &message).await;
+            }
+        }
+    }
 
-    // Status
-    ec_status: EcStatus,
+    mod thermal_alerts {
+        use super::*;
 
-    // Fan control settings
-    fan_duty: u32,
-    fan_enabled: bool,
-    auto_fan: bool,
-    fan_curve_enabled: bool,
-    fan_curve: Vec<(f32, f32)>, // (temp_celsius, duty_percent)
+        pub async fn run(state: AppState) {
+            loop {
+                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
 
-    // Power settings
-    tdp_watts: u32,
-    thermal_limit: u32,
-    power_enabled: bool,
+                let cfg = state.config.read().await.alerts.clone();
+                let history: Vec<TelemetrySample> = {
+                    let samples = state.telemetry_samples.read().await;
+                    let start_ms = samples
+                        .back()
+                        .map_or(0, |s| s.ts_ms - cfg.duration_s as i64 * 1000);
+                    // One sample from before the window shows the history spans all of it
+                    let start = samples
+                        .iter()
+                        .rposition(|s| s.ts_ms < start_ms)
+                        .unwrap_or(0);
+                    samples.range(start..).cloned().collect()
+                };
+                let alerts = crate::thermal_alert::check(&history, &cfg);
+
+                // Notify only for sensors that weren't already hot
+                let mut current = state.thermal_alerts.write().await;
+                for alert in &alerts {
+                    if !current.iter().any(|a| a.sensor == alert.sensor) {
+                        tracing::warn!("{}", alert.message());
+                        activity::push(&state.activity, alert.message()).await;
+                        notify::push(
+                            &state.toasts,
+                            &format!("{} is overheating", alert.sensor),
+                            &alert.message(),
+                        )
+                        .await;
+                    }
+                }
+                *current = alerts;
+            }
+        }
+    }
 
-    // Battery settings
-    charge_limit: u8,
-    charge_limit_enabled: bool,
+    mod telemetry {
+        use super::*;
+        use std::collections::VecDeque;
+
+        /// Longest one EC read may take before the previous value is kept instead
+        const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+        /// Direct reads looked back over, and how many of them failing while the service
+        /// runs means it holds the EC
+        const CONTENTION_WINDOW: usize = 10;
+        const CONTENTION_FAILURES: usize = 3;
+
+        /// Outcome of recent direct EC reads: true for a poll where nothing could be read
+        #[derive(Default)]
+        struct Contention(VecDeque<bool>);
+
+        impl Contention {
+            fn record(&mut self, failed: bool) {
+                if self.0.len() >= CONTENTION_WINDOW {
+                    self.0.pop_front();
+                }
+                self.0.push_back(failed);
+            }
 
-    // Status messages
-    status_message: String,
+            fn failures(&self) -> usize {
+                self.0.iter().filter(|f| **f).count()
+            }
+        }
 
-    // Advanced / BIOS features
-    custom_command: String,
-    command_output: String,
-    keyboard_backlight_pct: u32,
-}
+        /// Versions are read on the first poll, then again only when the EC comes back
+        /// after a poll where nothing could be read
+        #[derive(Default)]
+        struct VersionRetry {
+            tried: bool,
+            answering: bool,
+        }
 
-impl FrameworkControlApp {
-    fn new(
-        cc: &eframe::CreationContext<'_>,
-        state: AppState,
-        runtime: tokio::runtime::Runtime,
-    ) -> Self {
-        // Set dark theme with Framework colors
-        let mut style = (*cc.egui_ctx.style()).clone();
+        impl VersionRetry {
+            fn due(&mut self, answering: bool) -> bool {
+                let came_back = answering && !self.answering;
+                self.answering = answering;
+                let due = !self.tried || came_back;
+                self.tried = true;
+                due
+            }
+        }
 
-        // Premium Dark Theme
-        let framework_orange = egui::Color32::from_rgb(255, 106, 0);
-        let bg_color = egui::Color32::from_rgb(10, 10, 12); // Almost black
-        let panel_color = egui::Color32::from_rgb(18, 18, 20); // Very dark gray
-        let text_color = egui::Color32::from_rgb(240, 240, 245);
+        pub async fn run(
+            ft: Arc<RwLock<Option<cli::FrameworkTool>>>,
+            cfg: Arc<RwLock<Config>>,
+            readings: tokio::sync::watch::Sender<CachedData>,
+            samples: Arc<RwLock<VecDeque<TelemetrySample>>>,
+            recording: Arc<RwLock<Option<recording::Recorder>>>,
+            activity: activity::Activity,
+            owned: Arc<RwLock<bool>>,
+        ) {
+            let mut contention = Contention::default();
+            let mut version_retry = VersionRetry::default();
+            loop {
+                let tool = ft.read().await.clone();
+                let sample = match tool {
+                    Some(tool) => {
+                        collect(&tool, &readings, &mut contention, &mut version_retry).await
+                    }
+                    None => None,
+                };
+
+                // Set once reads keep failing with the service up, cleared once they all work
+                let was_owned = *owned.read().await;
+                let now_owned = match contention.failures() {
+                    0 => false,
+                    // The service itself has nobody to contend with
+                    n if n >= CONTENTION_FAILURES && !was_owned && !ipc::is_serving() => {
+                        tokio::task::spawn_blocking(service::is_running)
+                            .await
+                            .unwrap_or(false)
+                    }
+                    _ => was_owned,
+                };
+                if now_owned != was_owned {
+                    *owned.write().await = now_owned;
+                    let message = if now_owned {
+                        "EC reads failing while the service runs; it is likely holding the EC"
+                    } else {
+                        "EC reads are working again"
+                    };
+                    tracing::warn!("{}", message);
+                    activity::push(&activity, message).await;
+                }
+                if let Some(sample) = &sample {
+                    let mut session = recording.write().await;
+                    if let Some(Err(e)) = session.as_mut().map(|r| r.record(sample)) {
+                        // A failing disk ends the session rather than retrying every second
+                        tracing::error!("Recording stopped: {}", e);
+                        *session = None;
+                        activity::push(&activity, format!("Recording stopped: {}", e)).await;
+                    }
+                }
+                if let Some(sample) = sample {
+                    let max_samples = cfg.read().await.telemetry_max_samples.max(1);
+                    let mut samples = samples.write().await;
+                    while samples.len() >= max_samples {
+                        samples.pop_front();
+                    }
+                    samples.push_back(sample);
+                }
+                let poll = cfg.read().await.poll_interval();
+                tokio::time::sleep(poll).await;
+            }
+        }
+
+        /// `op` bounded by `READ_TIMEOUT`; `None` when it failed or timed out
+        async fn read<T>(
+            op: impl std::future::Future<Output = Result<T, String>>,
+            what: &str,
+        ) -> Option<T> {
+            match tokio::time::timeout(READ_TIMEOUT, op).await {
+                Ok(result) => result.ok(),
+                Err(_) => {
+                    tracing::warn!("{} read timed out", what);
+                    None
+                }
+            }
+        }
+
+        /// Read the EC, publish the values for the GUI and build the history sample
+        async fn collect(
+            tool: &cli::FrameworkTool,
+            readings: &tokio::sync::watch::Sender<CachedData>,
+            contention: &mut Contention,
+            version_retry: &mut VersionRetry,
+        ) -> Option<TelemetrySample> {
+            // A running service owns the EC; take its readings rather than opening it too
+            let (thermal, power) = match ipc::telemetry().await {
+                Some(readings) => {
+                    contention.0.clear();
+                    readings
+                }
+                None => {
+                    let thermal = read(tool.read_thermal(), "Thermal").await;
+                    let power = read(tool.read_power_info(), "Power").await;
+                    contention.record(thermal.is_none() && power.is_none());
+                    (thermal, power)
+                }
+            };
+            // Versions don't change; read them until every string has come back, but not
+            // on every poll while the EC is missing
+            let versions_complete = readings
+                .borrow()
+                .versions
+                .as_ref()
+                .is_some_and(|v| v.complete());
+            let answering = thermal.is_some() || power.is_some();
+            let versions = if versions_complete || !version_retry.due(answering) {
+                None
+            } else {
+                read(tool.read_versions(), "Versions").await
+            };
+            let ryzenadj = match power_limits::backend() {
+                power_limits::Backend::Ryzenadj(_) => {
+                    let info = async {
+                        tokio::task::spawn_blocking(power_limits::read_info)
+                            .await
+                            .map_err(|e| e.to_string())?
+                    };
+                    read(info, "ryzenadj").await
+                }
+                power_limits::Backend::Unavailable => None,
+            };
+            readings.send_modify(|r| {
+                r.ryzenadj = ryzenadj;
+                if let Some(thermal) = &thermal {
+                    r.thermal = Some(thermal.clone());
+                }
+                if let Some(power) = &power {
+                    r.power = Some(power.clone());
+                }
+                if let Some(versions) = versions {
+                    r.versions = Some(versions);
+                }
+            });
+
+            let thermal = thermal?;
+            let clocks = tokio::task::spawn_blocking(cpu::clocks)
+                .await
+                .ok()
+                .flatten();
+            let ts_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or(0);
+            Some(TelemetrySample {
+                ts_ms,
+                temps: thermal
+                    .sensors
+                    .into_iter()
+                    .map(|s| (s.name, s.temp_c))
+                    .collect(),
+                fan_rpms: thermal.fans,
+                fan_duty_pct: match ec::fan_control_state() {
+                    Some(ec::FanControlState::Duty(duty)) => Some(duty),
+                    _ => None,
+                },
+                battery_percent: power.as_ref().map(|p| p.charge_percent),
+                battery_percents: power
+                    .as_ref()
+                    .filter(|p| p.batteries.len() > 1)
+                    .map(|p| {
+                        p.batteries
+                            .iter()
+                            .filter_map(|b| b.charge_percent)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                charging: power.as_ref().map(|p| p.status.contains("Charging")),
+                power_w: power.as_ref().and_then(|p| p.system_power_w),
+                charger_w: power.as_ref().and_then(|p| p.charger_input_w),
+                battery_w: power.as_ref().and_then(|p| p.battery_power_w),
+                cpu_mhz: clocks.map(|c| c.avg_mhz),
+                cpu_max_mhz: clocks.map(|c| c.max_mhz),
+                tdp_w: ec::tdp_watts(),
+            })
+        }
+    }
+
+    mod hotkeys {
+        use super::*;
+        use hotkey::HotkeyAction;
+
+        pub async fn run(state: AppState) {
+            let bindings = {
+                let c = state.config.read().await;
+                [
+                    (HotkeyAction::PanicFans, c.hotkeys.panic_fans.clone()),
+                    (HotkeyAction::RestoreFans, c.hotkeys.restore_fans.clone()),
+                ]
+                .into_iter()
+                .filter(|(_, spec)| !spec.trim().is_empty())
+                .collect::<Vec<_>>()
+            };
+            let (mut presses, errors) = hotkey::listen(bindings);
+            for e in errors {
+                tracing::warn!("Hotkey not registered: {}", e);
+                activity::push(&state.activity, format!("Hotkey not registered: {}", e)).await;
+            }
+
+            while let Some(action) = presses.recv().await {
+                match action {
+                    HotkeyAction::PanicFans => {
+                        // Panic holds until restored
+                        if let Err(e) = &state.hold_max_fans().await {
+                            tracing::error!("Panic fan hotkey failed: {}", e);
+                        }
+                        activity::push(&state.activity, "Panic hotkey: fans forced to 100%").await;
+                        notify::push(
+                            &state.toasts,
+                            "Fans at 100%",
+                            "Panic hotkey pressed; use the restore hotkey to resume normal control.",
+                        )
+                        .await;
+                    }
+                    HotkeyAction::RestoreFans => {
+                        *state.fan_boost_until.write().await = None;
+                        activity::push(
+                            &state.activity,
+                            "Restore hotkey: normal fan control resumed",
+                        )
+                        .await;
+                        notify::push(
+                            &state.toasts,
+                            "Fan control restored",
+                            "Fans returned to the configured mode.",
+                        )
+                        .await;
+                    }
+                }
+            }
+        }
+    }
+
+    mod battery {
+        use super::*;
+        use std::time::{Duration, SystemTime};
+
+        const POLL: Duration = Duration::from_secs(60);
+        // A wall-clock gap this much longer than the poll means the machine was asleep
+        const RESUME_GAP: Duration = Duration::from_secs(30);
+
+        /// Charge settings are written when they change, and again whenever the EC may have
+        /// forgotten them: after the EC handle is re-acquired and after a resume from sleep.
+        pub async fn run(
+            ft: Arc<RwLock<Option<cli::FrameworkTool>>>,
+            cfg: Arc<RwLock<Config>>,
+            toasts: notify::Toasts,
+            activity: activity::Activity,
+            paused: Arc<RwLock<bool>>,
+        ) {
+            let mut limit_notifier = notify::ChargeLimitNotifier::default();
+            let mut applied_window = None;
+            let mut applied_current = None;
+            let mut was_critical = false;
+            // Power config in place before a critical battery switched profiles
+            let mut before_critical: Option<PowerConfig> = None;
+            let mut had_tool = false;
+            let mut last_tick: Option<SystemTime> = None;
+            loop {
+                let (window, notify_at_limit, current_limit, rate_limit) = {
+                    let c = cfg.read().await;
+                    (
+                        c.battery.charge_window(),
+                        c.battery.notify_at_limit,
+                        c.battery
+                            .charge_current_limit_ma
+                            .as_ref()
+                            .filter(|s| s.enabled)
+                            .map(|s| s.value),
+                        c.battery
+                            .charge_rate_c
+                            .as_ref()
+                            .filter(|s| s.enabled)
+                            .map(|s| (s.value, c.battery.charge_rate_soc_threshold_pct)),
+                    )
+                };
+
+                let now = SystemTime::now();
+                let resumed = last_tick.is_some_and(|t| {
+                    now.duration_since(t)
+                        .is_ok_and(|gap| gap > POLL + RESUME_GAP)
+                });
+                last_tick = Some(now);
+                let has_tool = ft.read().await.is_some();
+                let reacquired = has_tool && !had_tool;
+                had_tool = has_tool;
+
+                // While paused the firmware's defaults stand, and while the service runs it
+                // applies them; everything is re-applied on resume
+                let control = !*paused.read().await && !ipc::service_answering();
+                if !control || resumed || reacquired {
+                    applied_window = None;
+                    applied_current = None;
+                }
+
+                // The rate limit is a current cap too; the lower of the two wins
+                let rate_cap = match (rate_limit, ft.read().await.as_ref()) {
+                    (Some((rate_c, threshold)), Some(tool)) => tool
+                        .charge_rate_limit_ma(rate_c, threshold)
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::warn!("Charge rate limit not applied: {}", e);
+                            None
+                        }),
+                    _ => None,
+                };
+                let current_limit = match (current_limit, rate_cap) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+
+                // Nothing to clear when no cap was ever sent: the EC starts uncapped
+                if control && current_limit != applied_current {
+                    if let Some(tool) = ft.read().await.as_ref() {
+                        let result = tool.set_charge_current_limit(current_limit).await;
+                        let message = match (&result, current_limit) {
+                            (Ok(()), Some(ma)) => format!("Charge current limited to {} mA", ma),
+                            (Ok(()), None) => "Charge current limit removed".to_string(),
+                            (Err(e), _) => format!("Charge current limit failed: {}", e),
+                        };
+                        activity::push(&activity, message).await;
+                        // A failure is reported once, not every poll
+                        applied_current = current_limit;
+                    }
+                }
+
+                if let Some((min, max)) = window.filter(|_| control) {
+                    if let Some(tool) = ft.read().await.as_ref() {
+                        if applied_window != Some((min, max))
+                            && tool.charge_limit_set(min, max).await.is_ok()
+                        {
+                            activity::push(
+                                &activity,
+                                format!("Charge limit set to {}% (resumes below {}%)", max, min),
+                            )
+                            .await;
+                            applied_window = Some((min, max));
+                        }
+
+                        if notify_at_limit {
+                            if let Ok(power) = tool.read_power_info().await {
+                                let on_ac = power.status.contains("Charging");
+                                if limit_notifier.update(on_ac, power.charge_percent, max) {
+                                    activity::push(
+                                        &activity,
+                                        format!("Battery reached {}% limit", max),
+                                    )
+                                    .await;
+                                    notify::push(
+                                        &toasts,
+                                        "Charge limit reached",
+                                        &format!("Battery charged to {}%; you can unplug.", max),
+                                    )
+                                    .await;
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    applied_window = None;
+                }
+
+                if let Some(tool) = ft.read().await.as_ref() {
+                    if let Ok(power) = tool.read_power_info().await {
+                        if power.critical && !was_critical {
+                            before_critical =
+                                on_critical(tool, &cfg, &toasts, &activity, power.charge_percent)
+                                    .await;
+                        } else if !power.critical {
+                            if let Some(previous) = before_critical.take() {
+                                let on_ac = power.status.contains("Charging");
+                                restore_after_critical(tool, &cfg, &activity, previous, on_ac)
+                                    .await;
+                            }
+                        }
+                        was_critical = power.critical;
+                        if power.status.starts_with("Full") {
+                            finish_charge_to_full(&cfg, &toasts, &activity).await;
+                        }
+                    }
+                }
+                tokio::time::sleep(POLL).await;
+            }
+        }
+
+        /// End a pending "charge to full once"; the next poll programs the usual limit again
+        async fn finish_charge_to_full(
+            cfg: &RwLock<Config>,
+            toasts: &notify::Toasts,
+            activity: &activity::Activity,
+        ) {
+            let mut cfg = cfg.write().await;
+            if !cfg.battery.charge_to_full_once {
+                return;
+            }
+            cfg.battery.charge_to_full_once = false;
+            config::save(&cfg);
+            let limit = cfg.battery.charge_window().map_or(100, |(_, max)| max);
+            activity::push(
+                activity,
+                format!("Battery full; {}% charge limit restored", limit),
+            )
+            .await;
+            notify::push(
+                toasts,
+                "Battery full",
+                &format!("Charged to 100% once; the {}% limit applies again.", limit),
+            )
+            .await;
+        }
+
+        /// Warn once per critical episode and optionally drop to the configured saver profile.
+        /// Returns the power config it replaced, to restore once the battery recovers.
+        async fn on_critical(
+            tool: &cli::FrameworkTool,
+            cfg: &RwLock<Config>,
+            toasts: &notify::Toasts,
+            activity: &activity::Activity,
+            percent: f32,
+        ) -> Option<PowerConfig> {
+            activity::push(
+                activity,
+                format!("Battery critically low ({:.0}%)", percent),
+            )
+            .await;
+            notify::push(
+                toasts,
+                "Battery critically low",
+                &format!("{:.0}% remaining. Plug in now.", percent),
+            )
+            .await;
+
+            let mut cfg = cfg.write().await;
+            let name = cfg.battery.critical_profile.clone()?;
+            let power = match profiles::resolve(&cfg.profiles, &name) {
+                Ok(resolved) => resolved.power?.value,
+                Err(e) => {
+                    tracing::warn!("Critical battery profile unavailable: {}", e);
+                    return None;
+                }
+            };
+            if let Some(profile) = power.battery.as_ref() {
+                super::power::apply_limits(tool, profile, "critical battery").await;
+            }
+            let previous = std::mem::replace(&mut cfg.power, power);
+            config::save(&cfg);
+            activity::push(activity, format!("Switched to '{}' power limits", name)).await;
+            Some(previous)
+        }
+
+        /// Put back the power config a critical battery replaced
+        async fn restore_after_critical(
+            tool: &cli::FrameworkTool,
+            cfg: &RwLock<Config>,
+            activity: &activity::Activity,
+            previous: PowerConfig,
+            on_ac: bool,
+        ) {
+            let profile = if on_ac {
+                &previous.ac
+            } else {
+                &previous.battery
+            };
+            if let Some(profile) = profile {
+                super::power::apply_limits(tool, profile, "battery recovery").await;
+            }
+            let mut cfg = cfg.write().await;
+            cfg.power = previous;
+            config::save(&cfg);
+            activity::push(
+                activity,
+                "Battery no longer critical; power limits restored",
+            )
+            .await;
+        }
+    }
+}
+
+/// Program a charge window now, through the service when one is running, and read back
+/// what the EC took
+async fn send_charge_window(
+    state: &AppState,
+    readback: &RwLock<Option<(u8, u8)>>,
+    min: u8,
+    max: u8,
+) {
+    let command = ipc::Request::SetChargeLimit {
+        min_pct: min,
+        max_pct: max,
+    };
+    let sent = ipc::command(command).await.is_some();
+    if let Some(ft) = state.framework_tool.read().await.as_ref() {
+        if !sent {
+            let _ = ft.charge_limit_set(min, max).await;
+        }
+        *readback.write().await = ft.charge_limit_get().await.ok();
+    }
+}
+
+/// Store the limits for the power source so the power task re-asserts them after a reboot
+/// or resume
+async fn save_power_limits(state: &AppState, on_ac: bool, limits: PowerProfile) {
+    let mut cfg = state.config.write().await;
+    if on_ac {
+        cfg.power.ac = Some(limits);
+    } else {
+        cfg.power.battery = Some(limits);
+    }
+    config::save(&cfg);
+}
+
+/// Power limits to restore if a change isn't confirmed in time. The new limits are only
+/// saved once kept, so a crash or reboot during the countdown comes back with the old ones.
+struct PowerRevert {
+    deadline: std::time::Instant,
+    on_ac: bool,
+    previous: Option<PowerProfile>,
+    applied: PowerProfile,
+}
+
+/// Framework's driver downloads, which include the EC driver
+const DRIVER_HELP_URL: &str =
+    "https://knowledgebase.frame.work/en_us/bios-and-drivers-downloads-rJ3PaCexh";
+
+#[cfg(windows)]
+const DRIVER_MISSING_HELP: &str = "None of the CrosEC device paths opened. The crosecbus driver comes with the Framework Laptop Driver Bundle; install it and reboot.";
+#[cfg(not(windows))]
+const DRIVER_MISSING_HELP: &str = "/dev/cros_ec doesn't exist. Load the cros_ec_lpcs kernel module, or use a kernel that includes it.";
+
+struct FrameworkControlApp {
+    state: AppState,
+    runtime: tokio::runtime::Runtime,
+    /// Readings the telemetry task publishes; drained without blocking each frame
+    readings: tokio::sync::watch::Receiver<CachedData>,
+
+    start_on_boot: bool,
+    minimize_to_tray: bool,
+    // None when the platform has no tray; closing the window then quits as before
+    tray: Option<tray::Tray>,
+    tray_actions: Option<tokio::sync::mpsc::UnboundedReceiver<tray::TrayAction>>,
+    // Tooltip last shown, so it's only pushed to the shell when it changes
+    tray_tooltip: String,
+    // Set by "Quit" so the close request isn't turned into hide-to-tray
+    quitting: bool,
+
+    // Cached data
+    thermal_data: Option<cli::ThermalParsed>,
+    power_data: Option<cli::PowerBatteryInfo>,
+    versions: Option<cli::Versions>,
+
+    // Status
+    ec_status: EcStatus,
+
+    // Fan control settings
+    fan_duty: u32,
+    fan_enabled: bool,
+    auto_fan: bool,
+    fan_curve_enabled: bool,
+    fan_curve: Vec<(f32, f32)>, // (temp_celsius, duty_percent)
+    /// Fan whose curve `fan_curve` is editing; None for the curve shared by all fans
+    curve_fan: Option<u32>,
+    /// Curves not currently in the editor: the shared one and any per-fan ones
+    curve_stash: std::collections::BTreeMap<Option<u32>, Vec<(f32, f32)>>,
+    curve_interpolation: Interpolation,
+    /// Show the smoothed temperature the curve runs on next to the raw reading
+    show_smoothed_temp: bool,
+    fan_calibration: Option<FanCalibration>,
+    noise_map: Vec<[u32; 2]>,
+    boost_secs: u64,
+    max_duty_pct: Option<u32>,
+    sensor_offsets: std::collections::BTreeMap<String, f32>,
+    calibrate_sensors: bool,
+    gauge_view: bool,
+    boost_until: Option<std::time::Instant>,
+
+    // Power settings
+    tdp_watts: u32,
+    /// Separate fast and slow limits, only applied through ryzenadj
+    burst_enabled: bool,
+    fast_limit_w: u32,
+    slow_limit_w: u32,
+    thermal_limit: u32,
+    power_enabled: bool,
+    confirm_power: SettingU32,
+    power_revert: Option<PowerRevert>,
+
+    // Battery settings
+    charge_limit: u8,
+    charge_limit_min: u8,
+    charge_limit_enabled: bool,
+    /// Charge window as last read back from the EC, `None` if it couldn't be read
+    ec_charge_limit: Arc<RwLock<Option<(u8, u8)>>>,
+    /// Window the last Apply asked for, checked against the read-back
+    charge_limit_requested: Option<(u8, u8)>,
+    charge_current_supported: bool,
+    charge_current_enabled: bool,
+    charge_current_ma: u32,
+    notify_at_limit: bool,
+
+    // Status messages
+    status_message: String,
+    calibration_status: Arc<RwLock<Option<calibration::Status>>>,
+    /// Fan speeds read back after the last manual or curve apply
+    fan_readback: Arc<RwLock<Option<fan_health::DutyReadback>>>,
+    /// When the read-back currently in the status message was taken
+    fan_readback_shown: Option<std::time::Instant>,
+
+    // Advanced / BIOS features
+    custom_command: String,
+    command_output: Arc<RwLock<String>>,
+    // Raw EC command (as typed, and parsed) waiting for the user to confirm it
+    raw_command_pending: Option<(Vec<String>, cli::RawCommand)>,
+    /// Brightness read at startup; `None` hides the control on boards without the command
+    keyboard_backlight: Option<u8>,
+    /// Where the TDP and thermal sliders are applied; `Unavailable` hides them
+    power_backend: power_limits::Backend,
+    /// Latest `ryzenadj --info` values, when it can be run
+    ryzenadj_info: Option<power_limits::RyzenAdjInfo>,
+    show_raw_sensors: bool,
+
+    // "What's new" notes pending dismissal
+    whats_new: Vec<(&'static str, &'static [&'static str])>,
+    /// Already running as administrator, so a denied EC isn't fixed by relaunching
+    elevated: bool,
+    /// "Not now" was chosen on the relaunch-as-administrator prompt
+    elevation_prompt_dismissed: bool,
+
+    // EC memory map diagnostic (filled in by a background read)
+    ec_report: Arc<RwLock<Option<diagnostics::EcMapReport>>>,
+    /// Shows the raw EC memory dump
+    advanced_tools: bool,
+    /// Last raw read of the EC memory map; `Some(None)` when the read failed
+    ec_memory: Arc<RwLock<Option<Option<Vec<u8>>>>>,
+    /// Per-action results of the last "Restore Firmware Defaults"
+    firmware_reset: Arc<RwLock<Option<Vec<ec::ResetStep>>>>,
+    /// Per-step results of the last macro run
+    macro_results: Arc<RwLock<Option<cli::MacroRun>>>,
+    curve_suggestion: Option<Vec<(f32, f32)>>,
+    snapshots: Vec<snapshot::Snapshot>,
+    snapshot_label: String,
+    recording_label: String,
+    snapshot_window_s: u64,
+    /// How far back the telemetry charts reach
+    chart_window_min: u32,
+    /// Indices into `snapshots` shown side by side
+    compare: (usize, usize),
+    suggest_target_c: f32,
+
+    // Named profiles
+    profiles: Vec<Profile>,
+    selected_profile: Option<String>,
+    new_profile_name: String,
+}
+
+impl FrameworkControlApp {
+    fn new(
+        cc: &eframe::CreationContext<'_>,
+        state: AppState,
+        runtime: tokio::runtime::Runtime,
+    ) -> Self {
+        // Set dark theme with Framework colors
+        let mut style = (*cc.egui_ctx.style()).clone();
+
+        // Premium Dark Theme
+        let framework_orange = egui::Color32::from_rgb(255, 106, 0);
+        let bg_color = egui::Color32::from_rgb(10, 10, 12); // Almost black
+        let panel_color = egui::Color32::from_rgb(18, 18, 20); // Very dark gray
+        let text_color = egui::Color32::from_rgb(240, 240, 245);
         let border_color = egui::Color32::from_rgb(40, 40, 45);
 
         // Apply dark theme
@@ -432,55 +1903,220 @@ impl FrameworkControlApp {
         // Check startup status
         let start_on_boot = check_start_on_boot();
 
+        // Show release notes if the app was upgraded since the last dismissal
+        let (
+            whats_new,
+            fan_calibration,
+            noise_map,
+            profiles,
+            boost_secs,
+            notify_at_limit,
+            max_duty_pct,
+            sensor_offsets,
+            charge_current,
+            confirm_power,
+            gauge_view,
+            advanced_tools,
+            fan_curves,
+            curve_interpolation,
+            saved_curve,
+            fan_mode,
+            manual_duty,
+            charge_limit,
+            charge_limit_min,
+            minimize_to_tray,
+        ) = runtime.block_on(async {
+            let cfg = state.config.read().await;
+            (
+                changelog::since(cfg.last_seen_version.as_deref(), env!("CARGO_PKG_VERSION")),
+                cfg.fan.calibration.clone(),
+                noise::resolve_map(cfg.fan.noise_map.as_ref(), cfg.fan.calibration.as_ref()),
+                cfg.profiles.clone(),
+                cfg.fan.boost_secs.unwrap_or(60),
+                cfg.battery.notify_at_limit,
+                cfg.fan.max_duty_pct,
+                cfg.sensor_offsets.clone(),
+                cfg.battery.charge_current_limit_ma.clone(),
+                cfg.ui.confirm_power_changes.clone(),
+                cfg.ui.gauge_view,
+                cfg.ui.advanced_tools,
+                cfg.fan.fan_curves.clone(),
+                cfg.fan
+                    .curve
+                    .as_ref()
+                    .map(|c| c.interpolation)
+                    .unwrap_or_default(),
+                cfg.fan.curve.as_ref().map(|c| {
+                    c.points
+                        .iter()
+                        .map(|p| (p[0] as f32, p[1] as f32))
+                        .collect::<Vec<_>>()
+                }),
+                cfg.fan.mode.clone().unwrap_or_default(),
+                cfg.fan.manual.as_ref().map_or(50, |m| m.duty_pct),
+                cfg.battery.charge_limit_max_pct.clone(),
+                cfg.battery.charge_limit_min_pct,
+                cfg.minimize_to_tray,
+            )
+        });
+        // Older ECs lack the command; the control is hidden rather than failing
+        let charge_current_supported = runtime.block_on(async {
+            cli::FrameworkTool::new()
+                .await
+                .charge_current_limit_supported()
+                .await
+        });
+        let keyboard_backlight = runtime.block_on(async {
+            cli::FrameworkTool::new()
+                .await
+                .get_keyboard_backlight()
+                .await
+                .ok()
+        });
+        let power_backend = runtime
+            .block_on(async { cli::FrameworkTool::new().await.power_limits_backend().await });
+        let ec_charge_limit = runtime.block_on(async {
+            cli::FrameworkTool::new()
+                .await
+                .charge_limit_get()
+                .await
+                .ok()
+        });
+        // With nothing saved yet, the controls start from what the EC enforces
+        let (charge_limit_enabled, charge_limit, charge_limit_min) =
+            match (charge_limit, ec_charge_limit) {
+                (Some(limit), _) => (limit.enabled, limit.value, charge_limit_min.unwrap_or(75)),
+                (None, Some((min, max))) => (max < 100, max, min),
+                (None, None) => (false, 80, 75),
+            };
+
+        let (tray, tray_actions) = match tray::create(cc.egui_ctx.clone(), charge_limit_enabled) {
+            Ok((tray, actions)) => (Some(tray), Some(actions)),
+            Err(e) => {
+                tracing::warn!("No tray icon: {}", e);
+                (None, None)
+            }
+        };
+
+        let readings = state.readings.subscribe();
         Self {
             state,
             runtime,
+            readings,
             start_on_boot,
+            minimize_to_tray,
+            tray,
+            tray_actions,
+            tray_tooltip: String::new(),
+            quitting: false,
             thermal_data: None,
             power_data: None,
             versions: None,
             ec_status: EcStatus::Unknown,
-            fan_duty: 50,
-            fan_enabled: false,
-            auto_fan: true,
-            fan_curve_enabled: false,
-            fan_curve: vec![
-                (40.0, 20.0),  // 40°C -> 20% duty
-                (50.0, 30.0),  // 50°C -> 30% duty
-                (60.0, 40.0),  // 60°C -> 40% duty
-                (70.0, 60.0),  // 70°C -> 60% duty
-                (80.0, 80.0),  // 80°C -> 80% duty
-                (90.0, 100.0), // 90°C -> 100% duty
-            ],
+            // Mirrors the mode the fan task is running
+            fan_duty: manual_duty,
+            fan_enabled: fan_mode == FanControlMode::Manual,
+            auto_fan: fan_mode == FanControlMode::Disabled,
+            fan_curve_enabled: fan_mode == FanControlMode::Curve,
+            fan_curve: saved_curve.filter(|c| c.len() >= 2).unwrap_or_else(|| {
+                vec![
+                    (40.0, 20.0),  // 40°C -> 20% duty
+                    (50.0, 30.0),  // 50°C -> 30% duty
+                    (60.0, 40.0),  // 60°C -> 40% duty
+                    (70.0, 60.0),  // 70°C -> 60% duty
+                    (80.0, 80.0),  // 80°C -> 80% duty
+                    (90.0, 100.0), // 90°C -> 100% duty
+                ]
+            }),
+            curve_fan: None,
+            curve_interpolation,
+            show_smoothed_temp: false,
+            curve_stash: fan_curves
+                .into_iter()
+                .map(|c| {
+                    let points = c
+                        .points
+                        .iter()
+                        .map(|p| (p[0] as f32, p[1] as f32))
+                        .collect();
+                    (Some(c.fan), points)
+                })
+                .collect(),
+            fan_calibration,
+            noise_map,
+            boost_secs,
+            max_duty_pct,
+            sensor_offsets,
+            calibrate_sensors: false,
+            gauge_view,
+            boost_until: None,
             tdp_watts: 15,
+            burst_enabled: false,
+            fast_limit_w: 15,
+            slow_limit_w: 15,
             thermal_limit: 80,
             power_enabled: false,
-            charge_limit: 80,
-            charge_limit_enabled: false,
+            confirm_power: confirm_power.unwrap_or(SettingU32 {
+                enabled: false,
+                value: 15,
+            }),
+            power_revert: None,
+            charge_limit,
+            charge_limit_min,
+            charge_limit_enabled,
+            ec_charge_limit: Arc::new(RwLock::new(ec_charge_limit)),
+            charge_limit_requested: None,
+            charge_current_supported,
+            charge_current_enabled: charge_current.as_ref().is_some_and(|s| s.enabled),
+            charge_current_ma: charge_current.map_or(2000, |s| s.value),
+            notify_at_limit,
             status_message: String::new(),
+            fan_readback: Arc::new(RwLock::new(None)),
+            calibration_status: Arc::new(RwLock::new(None)),
+            fan_readback_shown: None,
             custom_command: String::new(),
-            command_output: String::new(),
-            keyboard_backlight_pct: 50,
+            command_output: Arc::new(RwLock::new(String::new())),
+            raw_command_pending: None,
+            keyboard_backlight,
+            power_backend,
+            ryzenadj_info: None,
+            show_raw_sensors: false,
+            whats_new,
+            elevated: ec::is_elevated(),
+            elevation_prompt_dismissed: false,
+            ec_report: Arc::new(RwLock::new(None)),
+            advanced_tools,
+            ec_memory: Arc::new(RwLock::new(None)),
+            firmware_reset: Arc::new(RwLock::new(None)),
+            macro_results: Arc::new(RwLock::new(None)),
+            curve_suggestion: None,
+            snapshots: Vec::new(),
+            snapshot_label: String::new(),
+            recording_label: String::new(),
+            snapshot_window_s: 120,
+            chart_window_min: 5,
+            compare: (0, 0),
+            suggest_target_c: 85.0,
+            profiles,
+            selected_profile: None,
+            new_profile_name: String::new(),
         }
     }
 
     fn update_data(&mut self, ctx: &egui::Context) {
-        // Fetch data on-demand (once per second max)
-        let state = self.state.clone();
-        let runtime = &self.runtime;
-
-        // Non-blocking data fetch
-        if let Ok(cache) = self.state.cache.try_read() {
-            // Use cached data if available
-            if let Some(thermal) = &cache.thermal {
+        // Take whatever the telemetry task published since the last frame
+        if self.readings.has_changed().unwrap_or(false) {
+            let readings = self.readings.borrow_and_update();
+            if let Some(thermal) = &readings.thermal {
                 self.thermal_data = Some(thermal.clone());
             }
-            if let Some(power) = &cache.power {
+            if let Some(power) = &readings.power {
                 self.power_data = Some(power.clone());
             }
-            if let Some(versions) = &cache.versions {
+            if let Some(versions) = &readings.versions {
                 self.versions = Some(versions.clone());
             }
+            self.ryzenadj_info = readings.ryzenadj.clone();
         }
 
         // Update EC status
@@ -488,46 +2124,61 @@ impl FrameworkControlApp {
             self.ec_status = status.clone();
         }
 
-        // Spawn task to update cache if stale (non-blocking)
-        runtime.spawn(async move {
-            if let Some(ft) = state.framework_tool.read().await.as_ref() {
-                let mut cache = state.cache.write().await;
-
-                // Fetch thermal data
-                if let Ok(thermal) = ft.read_thermal().await {
-                    cache.thermal = Some(thermal);
-                }
-
-                // Fetch power data (less frequently)
-                if cache.power.is_none() {
-                    if let Ok(power) = ft.read_power_info().await {
-                        cache.power = Some(power);
-                    }
-                }
-
-                // Fetch versions once
-                if cache.versions.is_none() {
-                    if let Ok(versions) = ft.read_versions().await {
-                        cache.versions = Some(versions);
-                    }
-                }
-            }
+        // Background tasks keep collecting; only the redraw rate drops when nobody is looking
+        let focused = ctx.input(|i| {
+            let viewport = i.viewport();
+            viewport.focused.unwrap_or(true) && !viewport.minimized.unwrap_or(false)
         });
-
-        ctx.request_repaint_after(std::time::Duration::from_millis(1000));
+        let interval = match self.state.config.try_read() {
+            Ok(cfg) => cfg.ui.repaint_interval(focused, cfg.poll_interval()),
+            Err(_) => {
+                let cfg = Config::default();
+                cfg.ui.repaint_interval(focused, cfg.poll_interval())
+            }
+        };
+        if let Some(interval) = interval {
+            ctx.request_repaint_after(interval);
+        }
     }
 }
 
 impl eframe::App for FrameworkControlApp {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        let state = self.state.clone();
+        self.runtime.block_on(async move {
+            // A running service keeps applying the config; the fans are its to release
+            if ipc::request(ipc::Request::GetTelemetry).await.is_none() {
+                state.release_fans("exit").await;
+            }
+        });
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Update data from background
         self.update_data(ctx);
+        self.handle_tray(ctx);
+        self.report_fan_readback();
 
         // Top panel - title and status
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.heading("⚡ Framework Control");
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let reset = egui::Button::new(
+                        egui::RichText::new("🛑 Restore Firmware Defaults")
+                            .color(egui::Color32::WHITE),
+                    )
+                    .fill(egui::Color32::from_rgb(180, 30, 30));
+                    if ui
+                        .add(reset)
+                        .on_hover_text(
+                            "Fans to EC auto and charge limits cleared; the saved config is kept \
+                             and stops being applied until you resume",
+                        )
+                        .clicked()
+                    {
+                        self.restore_firmware_defaults();
+                    }
                     if let Some(versions) = &self.versions {
                         ui.label(format!(
                             "EC: {} | BIOS: {}",
@@ -542,8 +2193,63 @@ impl eframe::App for FrameworkControlApp {
                 ui.colored_label(egui::Color32::from_rgb(255, 165, 0), &self.status_message);
             }
 
+            if let Some(power) = self.power_data.as_ref().filter(|p| p.critical) {
+                ui.separator();
+                ui.colored_label(
+                    egui::Color32::RED,
+                    egui::RichText::new(format!(
+                        "🪫 Battery critically low ({:.0}%). Plug in now.",
+                        power.charge_percent
+                    ))
+                    .strong(),
+                );
+            }
+
+            self.show_firmware_reset(ui);
+
+            if self.state.fan_watchdog_tripped.try_read().is_ok_and(|t| *t) {
+                ui.colored_label(
+                    egui::Color32::RED,
+                    "🐕 Fan control stopped responding; the EC's automatic control has taken over",
+                );
+            }
+
+            if let Ok(alerts) = self.state.thermal_alerts.try_read() {
+                for alert in alerts.iter() {
+                    ui.colored_label(egui::Color32::RED, format!("🔥 {}", alert.message()));
+                }
+            }
+
+            if let Some(problem) = config::save_problem() {
+                ui.separator();
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    format!("💾 {}", problem),
+                );
+            }
+
+            if self.state.ec_owned_by_service.try_read().is_ok_and(|o| *o) {
+                ui.separator();
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    "🔌 The Framework Control service is running and holding the EC, so readings here drop out.",
+                )
+                .on_hover_text(
+                    "The app reads through the service when it answers on its control channel. \
+                     It didn't, so the app is opening the EC alongside it. Restart the service, \
+                     or reinstall it from this version, so the app can go through it.",
+                );
+            }
+
             // Warning banner for EC issues
             match self.ec_status {
+                EcStatus::AccessDenied if self.elevated => {
+                    ui.separator();
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        "🔒 Access Denied even as administrator: another program may hold the EC driver.",
+                    );
+                }
                 EcStatus::AccessDenied => {
                     ui.separator();
                     ui.group(|ui| {
@@ -560,15 +2266,23 @@ impl eframe::App for FrameworkControlApp {
                 }
                 EcStatus::DriverMissing => {
                     ui.separator();
-                    ui.colored_label(
-                        egui::Color32::RED,
-                        "❌ EC Driver Missing: Please install the Framework Laptop Driver Bundle.",
-                    );
+                    ui.group(|ui| {
+                        ui.colored_label(egui::Color32::RED, "❌ EC Driver Missing");
+                        ui.label(DRIVER_MISSING_HELP);
+                        ui.hyperlink_to("📖 Driver install instructions", DRIVER_HELP_URL);
+                        ui.weak("The app reconnects on its own once the driver is loaded.");
+                    });
                 }
                 _ => {}
             }
         });
 
+        self.show_whats_new(ctx);
+        self.show_elevation_prompt(ctx);
+        self.show_power_confirm(ctx);
+        self.show_raw_command_confirm(ctx);
+        self.show_toasts(ctx);
+
         // Central panel - all features in one view
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
@@ -591,6 +2305,12 @@ impl eframe::App for FrameworkControlApp {
                 // 3. Advanced / BIOS
                 self.show_advanced_panel(ui);
 
+                ui.add_space(10.0);
+                self.show_telemetry_charts(ui);
+                self.show_snapshots(ui);
+                self.show_recording(ui);
+                self.show_activity(ui);
+
                 ui.add_space(20.0);
                 ui.separator();
                 ui.add_space(10.0);
@@ -606,52 +2326,230 @@ impl FrameworkControlApp {
     fn show_temperature_panel(&mut self, ui: &mut egui::Ui) {
         ui.group(|ui| {
             ui.heading("🌡️ Temperatures");
+            let ui_config = self
+                .state
+                .config
+                .try_read()
+                .map(|c| c.ui.clone())
+                .unwrap_or_default();
+            if self.state.sensor_fallback.try_read().is_ok_and(|f| *f) {
+                ui.colored_label(
+                    egui::Color32::RED,
+                    "⚠ No usable sensor data; the fan curve is holding its fallback speed",
+                );
+            }
+            ui.checkbox(&mut self.calibrate_sensors, "Calibrate offsets");
+            let mut nudge = None;
             if let Some(thermal) = &self.thermal_data {
-                egui::Grid::new("temps")
-                    .num_columns(2)
-                    .spacing([40.0, 4.0])
-                    .show(ui, |ui| {
+                if self.gauge_view {
+                    ui.horizontal_wrapped(|ui| {
                         for sensor in &thermal.sensors {
-                            ui.label(&sensor.name);
-                            let temp = sensor.temp_c;
-                            let color = if temp > 85.0 {
-                                egui::Color32::RED
-                            } else if temp > 75.0 {
-                                egui::Color32::from_rgb(255, 165, 0)
-                            } else {
-                                egui::Color32::from_rgb(0, 200, 0)
-                            };
-                            ui.colored_label(color, format!("{:.1}°C", temp));
-                            ui.end_row();
+                            let thresholds = ui_config.thresholds_for(&sensor.name);
+                            // The dial runs to a little past "hot" so hot readings still move it
+                            gauge(
+                                ui,
+                                &sensor.name,
+                                sensor.temp_c,
+                                20.0..=thresholds.hot_c + 15.0,
+                                &[
+                                    (thresholds.warn_c, temp_color(thresholds.warn_c, thresholds)),
+                                    (
+                                        thresholds.hot_c,
+                                        temp_color(thresholds.hot_c + 0.1, thresholds),
+                                    ),
+                                    (f32::INFINITY, egui::Color32::RED),
+                                ],
+                                format!("{:.0}°C", sensor.temp_c),
+                            );
                         }
                     });
+                    if self.calibrate_sensors {
+                        egui::Grid::new("temp_offsets")
+                            .num_columns(2)
+                            .spacing([40.0, 4.0])
+                            .show(ui, |ui| {
+                                for sensor in &thermal.sensors {
+                                    ui.label(&sensor.name);
+                                    let offset = self.sensor_offsets.get(&sensor.name).copied();
+                                    offset_nudger(ui, &sensor.name, offset, &mut nudge);
+                                    ui.end_row();
+                                }
+                            });
+                    }
+                    if let Some(clock) = self.cpu_clock_text() {
+                        ui.horizontal(|ui| {
+                            ui.label("CPU Clock");
+                            ui.label(clock);
+                        });
+                    }
+                } else {
+                    egui::Grid::new("temps")
+                        .num_columns(3)
+                        .spacing([40.0, 4.0])
+                        .show(ui, |ui| {
+                            for sensor in &thermal.sensors {
+                                ui.label(&sensor.name);
+                                let temp = sensor.temp_c;
+                                let color =
+                                    temp_color(temp, ui_config.thresholds_for(&sensor.name));
+                                ui.colored_label(color, format!("{:.1}°C", temp));
+                                let offset = self.sensor_offsets.get(&sensor.name).copied();
+                                if self.calibrate_sensors {
+                                    offset_nudger(ui, &sensor.name, offset, &mut nudge);
+                                } else if let Some(offset) = offset {
+                                    ui.weak(format!("{:+.1} offset", offset));
+                                } else {
+                                    ui.label("");
+                                }
+                                ui.end_row();
+                            }
+
+                            if let Some(clock) = self.cpu_clock_text() {
+                                ui.label("CPU Clock");
+                                ui.label(clock);
+                                ui.end_row();
+                            }
+                        });
+                }
             } else {
-                ui.label("Install framework_tool");
+                ui.weak(match self.ec_status {
+                    EcStatus::DriverMissing => "No readings: the EC driver isn't installed",
+                    EcStatus::AccessDenied => "No readings: EC access denied",
+                    _ => "Waiting for the EC…",
+                });
             }
+            if let Some((name, delta)) = nudge {
+                self.nudge_sensor_offset(name, delta);
+            }
+        });
+    }
+
+    /// Latest CPU clock, which shows throttling directly
+    fn cpu_clock_text(&self) -> Option<String> {
+        let latest = self
+            .state
+            .telemetry_samples
+            .try_read()
+            .ok()
+            .and_then(|s| s.back().map(|s| (s.cpu_mhz, s.cpu_max_mhz)));
+        let (Some(mhz), max) = latest? else {
+            return None;
+        };
+        Some(match max.filter(|m| *m > 0.0) {
+            Some(max) => format!("{:.0} MHz ({:.0}% of {:.0})", mhz, mhz / max * 100.0, max),
+            None => format!("{:.0} MHz", mhz),
+        })
+    }
+
+    fn nudge_sensor_offset(&mut self, name: String, delta: f32) {
+        let offset = self.sensor_offsets.get(&name).copied().unwrap_or(0.0) + delta;
+        // Drop zeroed entries so the config only lists sensors that were corrected
+        if offset.abs() < f32::EPSILON {
+            self.sensor_offsets.remove(&name);
+        } else {
+            self.sensor_offsets.insert(name.clone(), offset);
+        }
+        cli::set_sensor_offsets(self.sensor_offsets.clone());
+        let (state, offsets) = (self.state.clone(), self.sensor_offsets.clone());
+        self.runtime.spawn(async move {
+            let mut cfg = state.config.write().await;
+            cfg.sensor_offsets = offsets;
+            config::save(&cfg);
         });
+        self.status_message = format!("✅ {} offset {:+.1}°C", name, offset);
     }
 
     fn show_fans_panel(&mut self, ui: &mut egui::Ui) {
         ui.group(|ui| {
             ui.heading("🌀 Fans");
+            match ec::fan_control_state() {
+                Some(ec::FanControlState::Auto) => {
+                    ui.label("Fan: Auto (EC)");
+                }
+                Some(ec::FanControlState::Duty(duty)) => {
+                    // The EC only sees a duty; whether a curve chose it is the app's to say
+                    let mode = if self.fan_curve_enabled && !self.auto_fan {
+                        "Curve"
+                    } else {
+                        "Manual"
+                    };
+                    ui.label(format!("Fan: {} {}%", mode, duty));
+                }
+                None => {
+                    // Nothing acknowledged by the EC yet; show what the app thinks
+                    let inferred = if self.auto_fan {
+                        "Auto".to_string()
+                    } else if self.fan_curve_enabled {
+                        "Curve".to_string()
+                    } else {
+                        format!("Manual {}%", self.fan_duty)
+                    };
+                    ui.weak(format!("Fan: {} (inferred)", inferred));
+                }
+            }
+            if let Ok(alerts) = self.state.fan_alerts.try_read() {
+                for alert in alerts.iter() {
+                    ui.colored_label(egui::Color32::RED, format!("⚠ {}", alert.message()));
+                }
+            }
+            self.show_calibration(ui);
             if let Some(thermal) = &self.thermal_data {
-                egui::Grid::new("fans")
-                    .num_columns(2)
-                    .spacing([40.0, 4.0])
-                    .show(ui, |ui| {
+                if self.gauge_view {
+                    let max_rpm = self
+                        .fan_calibration
+                        .as_ref()
+                        .and_then(|c| c.points.iter().map(|p| p[1]).max())
+                        .map_or(6000.0, |rpm| rpm as f32);
+                    ui.horizontal_wrapped(|ui| {
                         for (idx, rpm) in thermal.fans.iter().enumerate() {
-                            ui.label(format!("Fan {}", idx + 1));
-                            ui.colored_label(
-                                if *rpm > 4000.0 {
-                                    egui::Color32::from_rgb(255, 165, 0)
-                                } else {
-                                    egui::Color32::from_rgb(100, 200, 255)
-                                },
-                                format!("{} RPM", rpm),
-                            );
-                            ui.end_row();
+                            let stalled = thermal.stalled_fans.contains(&idx);
+                            ui.vertical(|ui| {
+                                gauge(
+                                    ui,
+                                    &format!("Fan {}", idx + 1),
+                                    *rpm,
+                                    0.0..=max_rpm,
+                                    &[
+                                        (4000.0, fan_color(0.0)),
+                                        (f32::INFINITY, fan_color(f32::INFINITY)),
+                                    ],
+                                    if stalled {
+                                        "⚠ Stalled".to_string()
+                                    } else {
+                                        format!("{:.0} RPM", rpm)
+                                    },
+                                );
+                                if !stalled {
+                                    let db = noise::estimate_db(*rpm, &self.noise_map);
+                                    ui.weak(format!("~{:.0} dB (est.)", db));
+                                }
+                            });
                         }
                     });
+                } else {
+                    egui::Grid::new("fans")
+                        .num_columns(3)
+                        .spacing([40.0, 4.0])
+                        .show(ui, |ui| {
+                            for (idx, rpm) in thermal.fans.iter().enumerate() {
+                                ui.label(format!("Fan {}", idx + 1));
+                                if thermal.stalled_fans.contains(&idx) {
+                                    ui.colored_label(egui::Color32::RED, "⚠ Stalled")
+                                        .on_hover_text("The EC reports this fan as stalled");
+                                    ui.end_row();
+                                    continue;
+                                }
+                                ui.colored_label(fan_color(*rpm), format!("{} RPM", rpm));
+                                let db = noise::estimate_db(*rpm, &self.noise_map);
+                                ui.add(
+                                    egui::ProgressBar::new(db / noise::MAX_DB)
+                                        .desired_width(100.0)
+                                        .text(format!("~{:.0} dB (est.)", db)),
+                                );
+                                ui.end_row();
+                            }
+                        });
+                }
             }
         });
     }
@@ -660,6 +2558,20 @@ impl FrameworkControlApp {
         ui.group(|ui| {
             ui.heading("🔋 Power");
             if let Some(power) = &self.power_data {
+                if self.gauge_view {
+                    gauge(
+                        ui,
+                        "Level",
+                        power.charge_percent,
+                        0.0..=100.0,
+                        &[
+                            (20.0, battery_color(0.0)),
+                            (50.0, battery_color(20.0)),
+                            (f32::INFINITY, battery_color(100.0)),
+                        ],
+                        format!("{:.0}%", power.charge_percent),
+                    );
+                }
                 egui::Grid::new("power")
                     .num_columns(2)
                     .spacing([40.0, 4.0])
@@ -675,21 +2587,74 @@ impl FrameworkControlApp {
                         );
                         ui.end_row();
 
-                        {
+                        if !self.gauge_view {
                             let pct = power.charge_percent;
                             ui.label("Level");
-                            ui.colored_label(
-                                if pct < 20.0 {
-                                    egui::Color32::RED
-                                } else if pct < 50.0 {
-                                    egui::Color32::from_rgb(255, 165, 0)
-                                } else {
-                                    egui::Color32::from_rgb(0, 200, 0)
-                                },
-                                format!("{:.1}%", pct),
+                            ui.colored_label(battery_color(pct), format!("{:.1}%", pct));
+                            ui.end_row();
+                        }
+
+                        if let Some(input) = power.charger_input_w {
+                            ui.label("Charger In");
+                            ui.label(format!("≤{:.1} W (est.)", input)).on_hover_text(
+                                "Negotiated voltage times the charger's current limit; \
+                                 the real draw is usually lower",
                             );
                             ui.end_row();
                         }
+
+                        ui.label("Battery");
+                        match power.battery_power_w {
+                            Some(watts) => ui.label(format!("{:+.1} W", watts)),
+                            None => ui.weak("unknown"),
+                        };
+                        ui.end_row();
+
+                        ui.label("Health");
+                        match (power.health_pct(), power.wear_pct()) {
+                            (Some(health), Some(wear)) => {
+                                let cycles = power
+                                    .cycle_count()
+                                    .map_or(String::new(), |c| format!(" · {} cycles", c));
+                                ui.colored_label(
+                                    wear_color(wear),
+                                    format!("{:.0}% · {:.0}% wear{}", health, wear, cycles),
+                                )
+                            }
+                            _ => ui.weak("unknown"),
+                        };
+                        ui.end_row();
+
+                        if let Some(system) = power.system_power_w {
+                            ui.label("System");
+                            // Derived from the charger estimate while plugged in
+                            if power.charger_input_w.is_some() {
+                                ui.label(format!("≤{:.1} W (est.)", system));
+                            } else {
+                                ui.label(format!("{:.1} W", system));
+                            }
+                            ui.end_row();
+                        }
+
+                        // Totals above; per-pack detail only when there is more than one
+                        if power.batteries.len() > 1 {
+                            for (i, pack) in power.batteries.iter().enumerate() {
+                                ui.label(format!("Pack {}", i + 1));
+                                let charge = pack
+                                    .charge_percent
+                                    .map_or("?".to_string(), |p| format!("{:.0}%", p));
+                                let health = pack
+                                    .health_pct()
+                                    .map_or("unknown".to_string(), |h| format!("{:.0}%", h));
+                                ui.label(format!(
+                                    "{} · {:+.1} W · health {}",
+                                    charge,
+                                    pack.voltage * pack.current,
+                                    health
+                                ));
+                                ui.end_row();
+                            }
+                        }
                     });
             }
         });
@@ -700,6 +2665,10 @@ impl FrameworkControlApp {
         ui.heading("🌀 Fan Control");
         ui.add_space(5.0);
 
+        self.show_fan_boost(ui);
+        self.show_max_duty(ui);
+        ui.add_space(5.0);
+
         ui.horizontal(|ui| {
             if ui
                 .radio(self.auto_fan && !self.fan_curve_enabled, "Auto")
@@ -744,17 +2713,34 @@ impl FrameworkControlApp {
             if ui.button("⚡ Apply").clicked() {
                 self.apply_fan_speed();
             }
+            self.show_fan_readback(ui);
         } else {
             ui.label("Grid-based Fan Curve:");
+            self.show_fan_readback(ui);
+            let fan_count = self.thermal_data.as_ref().map_or(0, |t| t.fans.len());
+            if fan_count > 1 {
+                self.show_curve_fan_selector(ui, fan_count);
+            }
+            self.show_curve_interpolation(ui);
+            if self
+                .state
+                .config
+                .try_read()
+                .is_ok_and(|c| c.fan.has_power_source_curves())
+            {
+                ui.weak("The config's AC or battery curve replaces this one on that power source");
+            }
             ui.add_space(5.0);
 
+            let calibration = self.fan_calibration.as_ref();
             egui::Grid::new("curve")
-                .num_columns(3)
+                .num_columns(4)
                 .spacing([10.0, 5.0])
                 .striped(true)
                 .show(ui, |ui| {
                     ui.label("Temp (°C)");
                     ui.label("Fan (%)");
+                    ui.label("Estimate");
                     ui.label("");
                     ui.end_row();
 
@@ -763,6 +2749,14 @@ impl FrameworkControlApp {
                     for (idx, (temp, duty)) in self.fan_curve.iter_mut().enumerate() {
                         ui.add(egui::DragValue::new(temp).speed(1.0).range(20.0..=100.0));
                         ui.add(egui::DragValue::new(duty).speed(1.0).range(0.0..=100.0));
+                        match calibration.and_then(|c| noise::rpm_for_duty(*duty, c)) {
+                            Some(rpm) => ui.label(format!(
+                                "~{:.0} RPM · ~{:.0} dB",
+                                rpm,
+                                noise::estimate_db(rpm, &self.noise_map)
+                            )),
+                            None => ui.weak("—"),
+                        };
                         if ui.small_button("✖").clicked() && curve_len > 2 {
                             to_remove = Some(idx);
                         }
@@ -785,7 +2779,43 @@ impl FrameworkControlApp {
                 if ui.button("⚡ Apply Curve").clicked() {
                     self.apply_fan_curve();
                 }
+                if ui.button("📂 Import fw-fanctrl").clicked() {
+                    self.import_fw_fanctrl();
+                }
+                if ui.button("💡 Suggest from history").clicked() {
+                    self.suggest_curve();
+                }
             });
+            self.show_curve_suggestion(ui);
+
+            // Current operating point, from the live readings
+            if let Some(thermal) = &self.thermal_data {
+                let max_temp = thermal
+                    .sensors
+                    .iter()
+                    .map(|s| s.temp_c)
+                    .fold(f32::NEG_INFINITY, f32::max);
+                let max_rpm = thermal.fans.iter().copied().fold(0.0, f32::max);
+                if max_temp.is_finite() {
+                    ui.weak(format!(
+                        "Now: {:.0}°C, {:.0} RPM, ~{:.0} dB (estimate)",
+                        max_temp,
+                        max_rpm,
+                        noise::estimate_db(max_rpm, &self.noise_map)
+                    ));
+                }
+            }
+            ui.checkbox(&mut self.show_smoothed_temp, "Show smoothed temperature")
+                .on_hover_text("Set smoothing_alpha in the curve config to average out jitter");
+            if self.show_smoothed_temp {
+                match self.state.curve_temps.try_read().ok().and_then(|t| *t) {
+                    Some((raw, smoothed)) => ui.weak(format!(
+                        "Curve input: {:.1}°C raw, {:.1}°C smoothed",
+                        raw, smoothed
+                    )),
+                    None => ui.weak("Curve input: waiting for the fan curve to run"),
+                };
+            }
         }
 
         ui.add_space(5.0);
@@ -794,14 +2824,45 @@ impl FrameworkControlApp {
         }
     }
 
-    fn show_power_battery_control(&mut self, ui: &mut egui::Ui) {
-        ui.heading("⚡ Power");
-        ui.checkbox(&mut self.power_enabled, "Custom Limits");
+    /// TDP and thermal sliders, programmed through ryzenadj
+    fn show_power_limits(&mut self, ui: &mut egui::Ui) {
+        let backend = ui.weak(format!("Applied through {}", self.power_backend.label()));
+        if let power_limits::Backend::Ryzenadj(path) = &self.power_backend {
+            backend.on_hover_text(format!(
+                "The EC has no power limit command, so {} sets them; they reset on sleep or reboot until re-applied",
+                path.display()
+            ));
+        }
+        self.show_power_readings(ui);
+        if ui
+            .checkbox(&mut self.power_enabled, "Custom Limits")
+            .changed()
+            && !self.power_enabled
+        {
+            self.clear_power_limits();
+        }
         ui.add_enabled_ui(self.power_enabled, |ui| {
             ui.horizontal(|ui| {
                 ui.label("TDP:");
                 ui.add(egui::Slider::new(&mut self.tdp_watts, 5..=28).suffix("W"));
             });
+            ui.checkbox(&mut self.burst_enabled, "Separate burst limits")
+                .on_hover_text(
+                    "Let short bursts draw more than the sustained TDP; otherwise all three \
+                     limits follow it",
+                );
+            ui.add_enabled_ui(self.burst_enabled, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Fast:");
+                    ui.add(egui::Slider::new(&mut self.fast_limit_w, 5..=28).suffix("W"))
+                        .on_hover_text("Limit for a few seconds of load");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Slow:");
+                    ui.add(egui::Slider::new(&mut self.slow_limit_w, 5..=28).suffix("W"))
+                        .on_hover_text("Limit over tens of seconds, before the sustained TDP");
+                });
+            });
             ui.horizontal(|ui| {
                 ui.label("Thermal:");
                 ui.add(egui::Slider::new(&mut self.thermal_limit, 60..=100).suffix("°C"));
@@ -809,238 +2870,2574 @@ impl FrameworkControlApp {
             if ui.button("⚡ Apply").clicked() {
                 self.apply_power_settings();
             }
+            ui.horizontal(|ui| {
+                let mut changed = ui
+                    .checkbox(&mut self.confirm_power.enabled, "Confirm, reverting after")
+                    .on_hover_text(
+                        "Restores the previous limits unless you keep the new ones in time",
+                    )
+                    .changed();
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut self.confirm_power.value)
+                            .range(5..=120)
+                            .suffix("s"),
+                    )
+                    .changed();
+                if changed {
+                    let (state, setting) = (self.state.clone(), self.confirm_power.clone());
+                    self.runtime.spawn(async move {
+                        let mut cfg = state.config.write().await;
+                        cfg.ui.confirm_power_changes = Some(setting);
+                        config::save(&cfg);
+                    });
+                }
+            });
+        });
+    }
+
+    /// What the CPU draws against the limits in force, rows ryzenadj didn't report left out
+    fn show_power_readings(&self, ui: &mut egui::Ui) {
+        let info = self.ryzenadj_info.clone().unwrap_or_default();
+        let watts = |value: Option<f32>, limit: Option<f32>| match (value, limit) {
+            (Some(v), Some(l)) => Some(format!("{:.1} / {:.0} W", v, l)),
+            (Some(v), None) => Some(format!("{:.1} W", v)),
+            (None, Some(l)) => Some(format!("limit {:.0} W", l)),
+            (None, None) => None,
+        };
+        let core = match (info.core_temp_c, info.thermal_limit_c) {
+            (Some(t), Some(l)) => Some(format!("{:.0} / {:.0}°C", t, l)),
+            (Some(t), None) => Some(format!("{:.0}°C", t)),
+            (None, _) => None,
+        };
+        let rows = [
+            ("Sustained", watts(info.stapm_w, info.stapm_limit_w)),
+            ("Fast", watts(info.fast_w, info.fast_limit_w)),
+            ("Slow", watts(info.slow_w, info.slow_limit_w)),
+            ("Core", core),
+            ("Clock", self.cpu_clock_text()),
+        ];
+        if rows.iter().all(|(_, text)| text.is_none()) {
+            return;
+        }
+        egui::Grid::new("power_readings").show(ui, |ui| {
+            for (label, text) in rows {
+                if let Some(text) = text {
+                    ui.label(label);
+                    ui.label(text);
+                    ui.end_row();
+                }
+            }
         });
+    }
+
+    fn show_power_battery_control(&mut self, ui: &mut egui::Ui) {
+        ui.heading("⚡ Power");
+        if self.power_backend != power_limits::Backend::Unavailable {
+            self.show_power_limits(ui);
+        } else if self.state.cpu_vendor == cpu::Vendor::Amd {
+            ui.colored_label(
+                egui::Color32::GRAY,
+                "Power limits unavailable: ryzenadj wasn't found next to the app or on PATH",
+            );
+        } else {
+            ui.colored_label(
+                egui::Color32::GRAY,
+                format!(
+                    "Power limits unavailable: the EC has no TDP or thermal limit command, and ryzenadj only supports AMD ({} detected)",
+                    self.state.cpu_vendor.label()
+                ),
+            );
+        }
         ui.separator();
         ui.heading("🔋 Battery");
-        ui.checkbox(&mut self.charge_limit_enabled, "Charge Limit");
+        self.show_ec_charge_limit(ui);
+        if ui
+            .checkbox(&mut self.charge_limit_enabled, "Charge Limit")
+            .changed()
+            && !self.charge_limit_enabled
+        {
+            self.clear_charge_limit();
+        }
         ui.add_enabled_ui(self.charge_limit_enabled, |ui| {
             ui.horizontal(|ui| {
                 ui.label("Max:");
                 ui.add(egui::Slider::new(&mut self.charge_limit, 50..=100).suffix("%"));
             });
+            ui.horizontal(|ui| {
+                ui.label("Resume below:");
+                ui.add(egui::Slider::new(&mut self.charge_limit_min, 20..=100).suffix("%"));
+            });
+            let (min, max) = charge_window(self.charge_limit, Some(self.charge_limit_min));
+            if min != self.charge_limit_min {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    format!(
+                        "⚠ Resume point limited to {}% (at least {}% below max)",
+                        min, MIN_CHARGE_DEADBAND_PCT
+                    ),
+                );
+            } else if max - min < RECOMMENDED_CHARGE_DEADBAND_PCT {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    format!(
+                        "⚠ A window under {}% cycles charging on and off often",
+                        RECOMMENDED_CHARGE_DEADBAND_PCT
+                    ),
+                );
+            }
+            if ui
+                .checkbox(&mut self.notify_at_limit, "Notify when limit is reached")
+                .changed()
+            {
+                let state = self.state.clone();
+                let notify_at_limit = self.notify_at_limit;
+                self.runtime.spawn(async move {
+                    let mut cfg = state.config.write().await;
+                    cfg.battery.notify_at_limit = notify_at_limit;
+                    config::save(&cfg);
+                });
+            }
             if ui.button("🔋 Apply").clicked() {
                 self.apply_charge_limit();
             }
+            self.show_charge_to_full(ui);
+            self.show_critical_profile(ui);
         });
+        if self.charge_current_supported {
+            self.show_charge_current_limit(ui);
+        }
     }
 
-    // Action methods
-    fn apply_fan_speed(&mut self) {
-        let duty = self.fan_duty;
-        let state = self.state.clone();
+    fn show_ec_charge_limit(&mut self, ui: &mut egui::Ui) {
+        let Ok(readback) = self.ec_charge_limit.try_read() else {
+            return;
+        };
+        match *readback {
+            Some((_, 100)) => ui.label("EC: no charge limit"),
+            Some((min, max)) => ui.label(format!("EC: stops at {}%, resumes below {}%", max, min)),
+            None => ui.weak("EC: charge limit unknown"),
+        };
+        // Only the stop point matters once charging is unlimited
+        let accepted = match (self.charge_limit_requested, *readback) {
+            (Some((_, 100)), Some((_, 100))) => true,
+            (Some(requested), Some(actual)) => requested == actual,
+            _ => true,
+        };
+        if !accepted {
+            ui.colored_label(
+                egui::Color32::from_rgb(255, 165, 0),
+                "⚠ The EC did not take the requested charge limit",
+            );
+        }
+    }
 
-        self.runtime.spawn(async move {
-            if let Some(ft) = state.framework_tool.read().await.as_ref() {
-                let _ = ft.set_fan_duty(duty, None).await;
+    fn show_charge_current_limit(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(&mut self.charge_current_enabled, "Limit charge current")
+                .on_hover_text(
+                    "Caps charging current in mA, e.g. for a weak charger. \
+                     With a charge rate (C) limit also set, whichever allows less current wins.",
+                )
+                .changed()
+                && !self.charge_current_enabled
+            {
+                self.apply_charge_current_limit();
             }
+            ui.add_enabled_ui(self.charge_current_enabled, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut self.charge_current_ma, 500..=5000)
+                        .step_by(100.0)
+                        .suffix(" mA"),
+                );
+                if ui.button("Apply").clicked() {
+                    self.apply_charge_current_limit();
+                }
+            });
         });
-
-        self.fan_enabled = true;
-        self.status_message = format!("✓ Fan: {}%", duty);
     }
 
-    fn reset_fan_to_auto(&mut self) {
+    fn apply_charge_current_limit(&mut self) {
+        let setting = SettingU32 {
+            enabled: self.charge_current_enabled,
+            value: self.charge_current_ma,
+        };
+        let limit = setting.enabled.then_some(setting.value);
         let state = self.state.clone();
-
-        // Apply via framework_tool in hidden CMD
+        // The battery task sends it, combined with the charge rate cap
         self.runtime.spawn(async move {
-            if let Some(ft) = state.framework_tool.read().await.as_ref() {
-                let _ = ft.set_fan_control_auto(None).await;
-            }
+            let mut cfg = state.config.write().await;
+            cfg.battery.charge_current_limit_ma = Some(setting);
+            config::save(&cfg);
         });
-
-        self.fan_enabled = false;
-        self.auto_fan = true;
-        self.status_message = "✓ Fan: Auto".to_string();
+        self.status_message = match limit {
+            Some(ma) => format!("✓ Charge current limited to {} mA", ma),
+            None => "✓ Charge current limit removed".to_string(),
+        };
     }
 
-    fn apply_fan_curve(&mut self) {
-        self.fan_curve
-            .sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-        let curve = self.fan_curve.clone();
-        let state = self.state.clone();
-
-        // Spawn background task that continuously applies the curve
-        self.runtime.spawn(async move {
-            loop {
-                if let Some(ft) = state.framework_tool.read().await.as_ref() {
-                    if let Ok(thermal) = ft.read_thermal().await {
-                        let max_temp = thermal
-                            .sensors
-                            .iter()
-                            .map(|s| s.temp_c)
-                            .fold(f32::NEG_INFINITY, f32::max);
-
-                        // Calculate duty from curve
-                        let mut duty = 50.0;
-                        for i in 0..curve.len() {
-                            if i == 0 && max_temp <= curve[i].0 {
-                                duty = curve[i].1;
-                                break;
-                            }
-                            if i == curve.len() - 1 && max_temp >= curve[i].0 {
-                                duty = curve[i].1;
-                                break;
-                            }
-                            if i < curve.len() - 1
-                                && max_temp >= curve[i].0
-                                && max_temp <= curve[i + 1].0
-                            {
-                                let t1 = curve[i].0;
-                                let t2 = curve[i + 1].0;
-                                let d1 = curve[i].1;
-                                let d2 = curve[i + 1].1;
-                                let ratio = (max_temp - t1) / (t2 - t1);
-                                duty = d1 + (d2 - d1) * ratio;
-                                break;
-                            }
-                        }
-
-                        // Apply via framework_tool (which uses hidden CMD)
-                        let _ = ft.set_fan_duty(duty as u32, None).await;
+    fn show_fan_boost(&mut self, ui: &mut egui::Ui) {
+        let held = self.boost_until.is_none()
+            && self
+                .state
+                .fan_boost_until
+                .try_read()
+                .is_ok_and(|b| b.is_some_and(|t| std::time::Instant::now() < t));
+        if !held {
+            let max = egui::Button::new(
+                egui::RichText::new("🌀 Max Fan (100%)")
+                    .strong()
+                    .color(egui::Color32::WHITE),
+            )
+            .fill(egui::Color32::from_rgb(200, 90, 20));
+            let hotkey = self
+                .state
+                .config
+                .try_read()
+                .map(|c| c.hotkeys.panic_fans.clone())
+                .unwrap_or_default();
+            let hint = if hotkey.trim().is_empty() {
+                "All fans to 100% until you return to the current mode".to_string()
+            } else {
+                format!(
+                    "All fans to 100% until you return to the current mode (hotkey {})",
+                    hotkey
+                )
+            };
+            if ui.add(max).on_hover_text(hint).clicked() {
+                self.hold_max_fans();
+            }
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .button(format!("🚀 Boost ({}s)", self.boost_secs))
+                .on_hover_text("All fans to 100%, then back to the current mode")
+                .clicked()
+            {
+                self.start_fan_boost();
+            }
+            if let Some(until) = self.boost_until {
+                let left = until.saturating_duration_since(std::time::Instant::now());
+                if left.is_zero() {
+                    self.end_fan_boost();
+                } else {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 165, 0),
+                        format!("Boost: {}s left", left.as_secs() + 1),
+                    );
+                    if ui.small_button("✖").clicked() {
+                        self.end_fan_boost();
                     }
+                    ui.ctx().request_repaint_after(std::time::Duration::from_millis(250));
+                }
+            } else if held {
+                // Held by the max-fan button or panic hotkey rather than the timed boost
+                ui.colored_label(egui::Color32::RED, "🌀 Cooling boost active: fans at 100%");
+                let back = if self.auto_fan {
+                    "Back to auto".to_string()
+                } else if self.fan_curve_enabled {
+                    "Back to curve".to_string()
+                } else {
+                    format!("Back to {}%", self.fan_duty)
+                };
+                if ui.button(format!("↩ {}", back)).clicked() {
+                    self.end_fan_boost();
                 }
-                tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
             }
         });
+    }
+
+    fn show_max_duty(&mut self, ui: &mut egui::Ui) {
+        let mut capped = self.max_duty_pct.is_some();
+        let mut cap = self.max_duty_pct.unwrap_or(70);
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui.checkbox(&mut capped, "Max fan speed").changed();
+            ui.add_enabled_ui(capped, |ui| {
+                changed |= ui
+                    .add(egui::Slider::new(&mut cap, 30..=100).suffix("%"))
+                    .changed();
+            });
+        });
+        if capped {
+            ui.colored_label(
+                egui::Color32::from_rgb(255, 165, 0),
+                format!(
+                    "⚠ Capping fan speed raises temperatures; the cap is lifted at {:.0}°C",
+                    curve::EMERGENCY_TEMP_C
+                ),
+            );
+        }
+        if !changed {
+            return;
+        }
 
-        self.status_message = "✓ Curve active".to_string();
+        self.max_duty_pct = capped.then_some(cap);
+        let (state, max_duty_pct) = (self.state.clone(), self.max_duty_pct);
+        self.runtime.spawn(async move {
+            let mut cfg = state.config.write().await;
+            cfg.fan.max_duty_pct = max_duty_pct;
+            config::save(&cfg);
+        });
+        if !self.auto_fan && !self.fan_curve_enabled {
+            self.apply_fan_speed();
+        }
     }
 
-    fn apply_power_settings(&mut self) {
-        let (tdp, thermal, state) = (self.tdp_watts, self.thermal_limit, self.state.clone());
+    fn start_fan_boost(&mut self) {
+        // Pressing again restarts the timer instead of stacking
+        let until = std::time::Instant::now() + std::time::Duration::from_secs(self.boost_secs);
+        self.boost_until = Some(until);
+        let state = self.state.clone();
         self.runtime.spawn(async move {
+            // Commanded under the lock, like a held boost, so no curve duty lands after it
+            let mut boost = state.fan_boost_until.write().await;
+            *boost = Some(until);
             if let Some(ft) = state.framework_tool.read().await.as_ref() {
-                if let Err(e) = ft.set_tdp_watts(tdp).await {
-                    eprintln!("Failed to set TDP watts: {}", e);
-                }
-                if let Err(e) = ft.set_thermal_limit_c(thermal).await {
-                    eprintln!("Failed to set thermal limit: {}", e);
-                }
+                let _ = ft.set_fan_duty(100, None).await;
             }
         });
-        self.status_message = format!("✓ Power: {}W/{}°C", tdp, thermal);
+        self.status_message = "🚀 Fan boost active".to_string();
+        self.log_activity(format!("Fan boost for {}s", self.boost_secs));
     }
 
-    fn show_dashboard(&mut self, ui: &mut egui::Ui) {
-        ui.heading("📊 Dashboard");
-        ui.add_space(5.0);
-
-        egui::Grid::new("dashboard_grid")
-            .num_columns(2)
-            .spacing([20.0, 20.0])
-            .show(ui, |ui| {
-                // Top Left: Temperatures
-                ui.vertical(|ui| {
-                    self.show_temperature_panel(ui);
-                });
-
-                // Top Right: Power Status
-                ui.vertical(|ui| {
-                    self.show_power_panel(ui);
-                });
-                ui.end_row();
-
-                // Bottom: Fans (spanning 2 columns if possible, or just left)
-                ui.vertical(|ui| {
-                    self.show_fans_panel(ui);
-                });
-                ui.end_row();
-            });
+    fn hold_max_fans(&mut self) {
+        self.boost_until = None;
+        let state = self.state.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = state.hold_max_fans().await {
+                tracing::error!("Max fan failed: {}", e);
+            }
+        });
+        self.status_message = "🌀 Fans held at 100%".to_string();
+        self.log_activity("Max fan: fans forced to 100%".to_string());
     }
 
-    fn show_control_center(&mut self, ui: &mut egui::Ui) {
-        ui.heading("🎛️ Control Center");
-        ui.add_space(5.0);
-
-        ui.columns(2, |columns| {
-            columns[0].group(|ui| {
-                self.show_fan_control_enhanced(ui);
-            });
-            columns[1].group(|ui| {
-                self.show_power_battery_control(ui);
-            });
+    fn end_fan_boost(&mut self) {
+        self.boost_until = None;
+        let state = self.state.clone();
+        self.runtime.spawn(async move {
+            *state.fan_boost_until.write().await = None;
         });
+
+        // Hand control back to whatever mode was selected
+        if self.auto_fan {
+            self.reset_fan_to_auto();
+        } else if !self.fan_curve_enabled {
+            self.apply_fan_speed();
+        } else {
+            self.status_message = "✓ Curve active".to_string();
+        }
     }
 
-    fn show_advanced_panel(&mut self, ui: &mut egui::Ui) {
-        ui.heading("🛠️ Advanced / BIOS");
-        ui.add_space(5.0);
+    // Action methods
+    fn apply_fan_speed(&mut self) {
+        let duty = self.fan_duty.min(self.max_duty_pct.unwrap_or(100));
+        let state = self.state.clone();
 
-        ui.group(|ui| {
-            ui.label("Experimental Features");
-            ui.separator();
+        let manual_duty = self.fan_duty;
 
-            // Keyboard Backlight (Placeholder)
-            ui.horizontal(|ui| {
-                ui.label("Keyboard Backlight:");
-                ui.add(egui::Slider::new(&mut self.keyboard_backlight_pct, 0..=100).suffix("%"));
-                if ui.button("Set").clicked() {
-                    // TODO: Implement actual call
-                    self.status_message = format!(
-                        "Backlight set to {}% (Simulated)",
-                        self.keyboard_backlight_pct
-                    );
+        // Set it now for a responsive slider; the fan task holds it from the config
+        self.runtime.spawn(async move {
+            let command = ipc::Request::SetFanDuty { duty_pct: duty };
+            if ipc::command(command).await.is_none() {
+                if let Some(ft) = state.framework_tool.read().await.as_ref() {
+                    let _ = ft.set_fan_duty(duty, None).await;
                 }
+            }
+            let mut cfg = state.config.write().await;
+            cfg.fan.mode = Some(FanControlMode::Manual);
+            cfg.fan.manual = Some(ManualConfig {
+                duty_pct: manual_duty,
             });
+            config::save(&cfg);
+        });
 
-            ui.add_space(10.0);
-            ui.separator();
+        self.fan_enabled = true;
+        self.status_message = format!("✓ Fan: {}%", duty);
+        self.spawn_fan_readback();
+    }
 
-            // Custom Command Runner
-            ui.label("Custom framework_tool Command:");
-            ui.horizontal(|ui| {
-                ui.text_edit_singleline(&mut self.custom_command);
-                if ui.button("Run").clicked() {
-                    self.run_custom_command();
-                }
+    /// Read the fans once they have had time to react to the duty the EC now holds. The
+    /// curve's duty is only known after the fan task has applied it, so both modes read the
+    /// duty back from the EC state rather than taking it as an argument.
+    fn spawn_fan_readback(&self) {
+        let readback = self.fan_readback.clone();
+        self.runtime.spawn(async move {
+            tokio::time::sleep(fan_health::READBACK_SETTLE).await;
+            let Some(ec::FanControlState::Duty(duty_pct)) = ec::fan_control_state() else {
+                return;
+            };
+            let Ok(thermal) = cli::FrameworkTool::new().await.read_thermal().await else {
+                return;
+            };
+            *readback.write().await = Some(fan_health::DutyReadback {
+                duty_pct,
+                rpms: thermal.fans,
+                at: std::time::Instant::now(),
             });
-
-            if !self.command_output.is_empty() {
-                ui.add_space(5.0);
-                ui.label("Output:");
-                egui::ScrollArea::vertical()
-                    .max_height(100.0)
-                    .show(ui, |ui| {
-                        ui.monospace(&self.command_output);
-                    });
-            }
         });
     }
 
-    fn run_custom_command(&mut self) {
-        let cmd = self.custom_command.clone();
-        if cmd.trim().is_empty() {
+    /// Put a fresh read-back into the status line, flagging fans that didn't start
+    fn report_fan_readback(&mut self) {
+        let Ok(readback) = self.fan_readback.try_read() else {
+            return;
+        };
+        let Some(readback) = readback.as_ref() else {
+            return;
+        };
+        if self.fan_readback_shown == Some(readback.at) {
             return;
         }
+        self.fan_readback_shown = Some(readback.at);
+        let stalled = readback.stalled();
+        self.status_message = if stalled.is_empty() {
+            format!("✓ Fan: {}", readback.summary())
+        } else {
+            let fans: Vec<String> = stalled.iter().map(|f| (f + 1).to_string()).collect();
+            format!(
+                "⚠ Fan: {}; fan {} not spinning",
+                readback.summary(),
+                fans.join(", ")
+            )
+        };
+    }
 
-        let state = self.state.clone();
-        self.command_output =
-            "Command sent (async). Output logging not yet linked to UI.".to_string();
-
-        self.runtime.spawn(async move {
-            let args: Vec<String> = cmd.split_whitespace().map(String::from).collect();
-            if let Some(ft) = state.framework_tool.read().await.as_ref() {
-                match ft.run_raw_command(args).await {
-                    Ok(o) => tracing::info!("Custom Command Output:\n{}", o),
-                    Err(e) => tracing::error!("Custom Command Error: {}", e),
+    fn show_calibration(&mut self, ui: &mut egui::Ui) {
+        let status = match self.calibration_status.try_read() {
+            Ok(status) => status.clone(),
+            Err(_) => return,
+        };
+        // Pick up a finished run's table for the estimates
+        if let Some(calibration::Status::Finished(Ok(table))) = &status {
+            let newer = self
+                .fan_calibration
+                .as_ref()
+                .is_none_or(|c| c.updated_at != table.updated_at);
+            if newer {
+                if let Ok(cfg) = self.state.config.try_read() {
+                    self.noise_map = noise::resolve_map(cfg.fan.noise_map.as_ref(), Some(table));
+                    self.fan_calibration = Some(table.clone());
                 }
             }
-        });
-    }
+        }
 
-    fn apply_charge_limit(&mut self) {
-        let limit = self.charge_limit;
-        let state = self.state.clone();
+        let running = matches!(status, Some(calibration::Status::Running { .. }));
+        // The curve sets the duty from temperatures; stepping it down under load isn't safe
+        let curve_active = !self.auto_fan && self.fan_curve_enabled;
+        ui.horizontal(|ui| {
+            let clicked = ui
+                .add_enabled(
+                    !running && !curve_active,
+                    egui::Button::new("🔧 Recalibrate"),
+                )
+                .on_hover_text(format!(
+                    "Steps the fans from 0 to 100% and records their speed (about a minute). \
+                     Stops above {:.0}°C.",
+                    calibration::ABORT_TEMP_C
+                ))
+                .on_disabled_hover_text("Switch the fans to Auto or Manual to calibrate")
+                .clicked();
+            if clicked {
+                self.start_calibration();
+            }
+            match &status {
+                Some(calibration::Status::Running { duty_pct }) => {
+                    ui.spinner();
+                    ui.label(format!("Calibrating at {}%…", duty_pct))
+                }
+                Some(calibration::Status::Finished(Err(e))) => {
+                    ui.colored_label(egui::Color32::RED, e)
+                }
+                _ => match &self.fan_calibration {
+                    Some(c) => ui.weak(format!(
+                        "Calibrated {}",
+                        csv_export::iso8601(c.updated_at).get(..10).unwrap_or("")
+                    )),
+                    None => ui.weak("Not calibrated"),
+                },
+            };
+        });
+    }
+
+    fn start_calibration(&mut self) {
+        let state = self.state.clone();
+        let status = self.calibration_status.clone();
+        self.runtime.spawn(async move {
+            *state.fan_calibrating.write().await = true;
+            let tool = cli::FrameworkTool::new().await;
+            let result = calibration::run(&tool, &status).await;
+            // The fan task re-applies the configured mode once it resumes
+            *state.fan_calibrating.write().await = false;
+            match &result {
+                Ok(table) => {
+                    let mut cfg = state.config.write().await;
+                    cfg.fan.calibration = Some(table.clone());
+                    config::save(&cfg);
+                    drop(cfg);
+                    activity::push(&state.activity, "Fan calibration saved").await;
+                }
+                Err(e) => {
+                    tracing::warn!("Fan calibration failed: {}", e);
+                    activity::push(&state.activity, format!("Fan calibration failed: {}", e)).await;
+                }
+            }
+            *status.write().await = Some(calibration::Status::Finished(result));
+        });
+        self.status_message = "🔧 Calibrating fans…".to_string();
+    }
+
+    fn show_fan_readback(&self, ui: &mut egui::Ui) {
+        let Ok(readback) = self.fan_readback.try_read() else {
+            return;
+        };
+        if let Some(readback) = readback.as_ref() {
+            let text = format!(
+                "Measured: {} ({})",
+                readback.summary(),
+                activity::age(readback.at)
+            );
+            if readback.stalled().is_empty() {
+                ui.weak(text);
+            } else {
+                ui.colored_label(egui::Color32::RED, format!("⚠ {}", text));
+            }
+        }
+    }
+
+    fn reset_fan_to_auto(&mut self) {
+        let state = self.state.clone();
+
+        // Apply via framework_tool in hidden CMD
+        self.runtime.spawn(async move {
+            if ipc::command(ipc::Request::SetFanAuto).await.is_none() {
+                if let Some(ft) = state.framework_tool.read().await.as_ref() {
+                    let _ = ft.set_fan_control_auto(None).await;
+                }
+            }
+            let mut cfg = state.config.write().await;
+            cfg.fan.mode = Some(FanControlMode::Disabled);
+            config::save(&cfg);
+        });
+
+        self.fan_enabled = false;
+        self.auto_fan = true;
+        self.status_message = "✓ Fan: Auto".to_string();
+    }
+
+    fn show_curve_fan_selector(&mut self, ui: &mut egui::Ui, fan_count: usize) {
+        let name = |fan: Option<u32>| match fan {
+            None => "All fans".to_string(),
+            Some(fan) => format!("Fan {}", fan + 1),
+        };
+        ui.horizontal(|ui| {
+            ui.label("Curve for:");
+            let mut selected = self.curve_fan;
+            egui::ComboBox::from_id_salt("curve_fan")
+                .selected_text(name(selected))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut selected, None, name(None));
+                    for fan in 0..fan_count as u32 {
+                        let own = self.curve_stash.contains_key(&Some(fan))
+                            || self.curve_fan == Some(fan);
+                        let label = if own {
+                            format!("{} (own curve)", name(Some(fan)))
+                        } else {
+                            name(Some(fan))
+                        };
+                        ui.selectable_value(&mut selected, Some(fan), label);
+                    }
+                });
+            if selected != self.curve_fan {
+                self.select_curve_fan(selected);
+            }
+            if self.curve_fan.is_some()
+                && ui
+                    .button("Use shared curve")
+                    .on_hover_text("Drop this fan's own curve; it follows the curve for all fans")
+                    .clicked()
+            {
+                self.fan_curve = self.shared_curve();
+            }
+        });
+    }
+
+    fn show_curve_interpolation(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Between points:");
+            let mut selected = self.curve_interpolation;
+            egui::ComboBox::from_id_salt("curve_interpolation")
+                .selected_text(selected.label())
+                .show_ui(ui, |ui| {
+                    for mode in Interpolation::ALL {
+                        ui.selectable_value(&mut selected, mode, mode.label());
+                    }
+                });
+            if selected == self.curve_interpolation {
+                return;
+            }
+            self.curve_interpolation = selected;
+            let state = self.state.clone();
+            let points: Vec<[u32; 2]> = self
+                .shared_curve()
+                .iter()
+                .map(|(t, d)| [*t as u32, *d as u32])
+                .collect();
+            self.runtime.spawn(async move {
+                let mut cfg = state.config.write().await;
+                cfg.fan
+                    .curve
+                    .get_or_insert_with(|| CurveConfig {
+                        points,
+                        ..Default::default()
+                    })
+                    .interpolation = selected;
+                config::save(&cfg);
+            });
+        });
+    }
+
+    /// The curve for fans without their own
+    fn shared_curve(&self) -> Vec<(f32, f32)> {
+        match self.curve_fan {
+            None => self.fan_curve.clone(),
+            Some(_) => self
+                .curve_stash
+                .get(&None)
+                .cloned()
+                .unwrap_or_else(|| self.fan_curve.clone()),
+        }
+    }
+
+    /// Put the editor's curve back in the stash; a fan curve equal to the shared one is dropped
+    fn stash_curve(&mut self) {
+        let shared = self.shared_curve();
+        match self.curve_fan {
+            Some(fan) if self.fan_curve == shared => {
+                self.curve_stash.remove(&Some(fan));
+            }
+            selected => {
+                self.curve_stash.insert(selected, self.fan_curve.clone());
+            }
+        }
+    }
+
+    fn select_curve_fan(&mut self, fan: Option<u32>) {
+        let shared = self.shared_curve();
+        self.stash_curve();
+        self.curve_fan = fan;
+        self.fan_curve = self.curve_stash.get(&fan).cloned().unwrap_or(shared);
+    }
+
+    fn apply_fan_curve(&mut self) {
+        // Points at the same temperature would divide by zero between them
+        let mut points: Vec<[u32; 2]> = self
+            .fan_curve
+            .iter()
+            .map(|(t, d)| [*t as u32, *d as u32])
+            .collect();
+        let fixes = normalize_points(&mut points);
+        self.fan_curve = points.iter().map(|p| (p[0] as f32, p[1] as f32)).collect();
+        self.stash_curve();
+        let to_points = |curve: &[(f32, f32)]| -> Vec<[u32; 2]> {
+            curve.iter().map(|(t, d)| [*t as u32, *d as u32]).collect()
+        };
+        let curve = to_points(&self.shared_curve());
+        let fan_curves: Vec<FanCurve> = self
+            .curve_stash
+            .iter()
+            .filter_map(|(fan, points)| {
+                fan.map(|fan| FanCurve {
+                    fan,
+                    points: to_points(points),
+                })
+            })
+            .collect();
+        let interpolation = self.curve_interpolation;
+        let state = self.state.clone();
+
+        // The fan task applies the curve from the config, so there is only ever one loop
+        self.runtime.spawn(async move {
+            let mut cfg = state.config.write().await;
+            let saved = cfg.fan.curve.get_or_insert_with(CurveConfig::default);
+            saved.points = curve;
+            saved.interpolation = interpolation;
+            cfg.fan.fan_curves = fan_curves;
+            for fix in cfg.fan.validate_and_normalize_curves() {
+                tracing::warn!("{}", fix);
+            }
+            cfg.fan.mode = Some(FanControlMode::Curve);
+            config::save(&cfg);
+        });
+
+        self.status_message = if fixes.is_empty() {
+            "✓ Curve active".to_string()
+        } else {
+            format!("⚠ Curve active, {}", fixes.join("; "))
+        };
+        self.spawn_fan_readback();
+    }
+
+    fn suggest_curve(&mut self) {
+        let Ok(samples) = self.state.telemetry_samples.try_read() else {
+            return;
+        };
+        let samples: Vec<TelemetrySample> = samples.iter().cloned().collect();
+        match curve::suggest_curve(&samples, self.suggest_target_c) {
+            Ok(points) => {
+                self.curve_suggestion =
+                    Some(points.iter().map(|p| (p[0] as f32, p[1] as f32)).collect());
+            }
+            Err(e) => {
+                self.curve_suggestion = None;
+                self.status_message = format!("❌ {}", e);
+            }
+        }
+    }
+
+    fn show_curve_suggestion(&mut self, ui: &mut egui::Ui) {
+        let Some(points) = self.curve_suggestion.clone() else {
+            return;
+        };
+        ui.group(|ui| {
+            ui.label("💡 Suggested curve");
+            ui.horizontal(|ui| {
+                ui.label("Keep below:");
+                let target =
+                    ui.add(egui::Slider::new(&mut self.suggest_target_c, 60.0..=95.0).suffix("°C"));
+                if target.drag_stopped() || target.lost_focus() {
+                    self.suggest_curve();
+                }
+            });
+            ui.label(
+                points
+                    .iter()
+                    .map(|(t, d)| format!("{}°C→{}%", *t as u32, *d as u32))
+                    .collect::<Vec<_>>()
+                    .join("  "),
+            );
+            ui.weak(
+                "Based on the quietest fan speed that held each temperature in the recent history.",
+            );
+            ui.horizontal(|ui| {
+                if ui.button("✓ Use this curve").clicked() {
+                    self.fan_curve = points.clone();
+                    self.curve_suggestion = None;
+                    self.status_message =
+                        "✓ Loaded suggested curve (tweak it, then click Apply Curve)".to_string();
+                }
+                if ui.button("Dismiss").clicked() {
+                    self.curve_suggestion = None;
+                }
+            });
+        });
+    }
+
+    fn import_fw_fanctrl(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Import fw-fanctrl config")
+            .add_filter("JSON", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        let imported = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+            .and_then(|json| fw_fanctrl::import(&json));
+        match imported {
+            Ok(fw_fanctrl::Imported {
+                curve,
+                curve_battery,
+                warnings,
+            }) => {
+                self.fan_curve = curve
+                    .points
+                    .iter()
+                    .map(|p| (p[0] as f32, p[1] as f32))
+                    .collect();
+                let what = if curve_battery.is_some() {
+                    "fw-fanctrl curve and battery curve"
+                } else {
+                    "fw-fanctrl curve"
+                };
+                let state = self.state.clone();
+                self.runtime.spawn(async move {
+                    let mut cfg = state.config.write().await;
+                    cfg.fan.curve = Some(curve);
+                    if curve_battery.is_some() {
+                        cfg.fan.curve_battery = curve_battery;
+                    }
+                    config::save(&cfg);
+                });
+                self.log_activity(format!("Imported {}", what));
+                self.status_message = if warnings.is_empty() {
+                    format!("✓ Imported {} (click Apply Curve to use it)", what)
+                } else {
+                    format!("⚠ Imported with notes: {}", warnings.join("; "))
+                };
+            }
+            Err(e) => self.status_message = format!("❌ Import failed: {}", e),
+        }
+    }
+
+    /// (STAPM, fast, slow) watts from the sliders; fast and slow follow the TDP unless
+    /// separate burst limits are on
+    fn power_limits_w(&self) -> (u32, u32, u32) {
+        if self.burst_enabled {
+            (self.tdp_watts, self.fast_limit_w, self.slow_limit_w)
+        } else {
+            (self.tdp_watts, self.tdp_watts, self.tdp_watts)
+        }
+    }
+
+    fn apply_power_settings(&mut self) {
+        let range = cpu::tdp_range_watts();
+        let (tdp, clamped) = cpu::clamp_tdp(self.tdp_watts, &range);
+        let (_, fast, slow) = self.power_limits_w();
+        let burst = [fast, slow].map(|watts| SettingU32 {
+            enabled: self.burst_enabled,
+            value: watts,
+        });
+        let (thermal, state) = (self.thermal_limit, self.state.clone());
+        let on_ac = self
+            .power_data
+            .as_ref()
+            .is_some_and(|p| p.status.contains("Charging"));
+        let [fast_limit_w, slow_limit_w] = burst;
+        let applied = PowerProfile {
+            tdp_watts: Some(SettingU32 {
+                enabled: true,
+                value: tdp,
+            }),
+            fast_limit_w: Some(fast_limit_w),
+            slow_limit_w: Some(slow_limit_w),
+            thermal_limit_c: Some(SettingU32 {
+                enabled: true,
+                value: thermal,
+            }),
+        };
+        if self.confirm_power.enabled {
+            if let Ok(cfg) = state.config.try_read() {
+                let previous = if on_ac {
+                    cfg.power.ac.clone()
+                } else {
+                    cfg.power.battery.clone()
+                };
+                // Re-applying while a countdown runs keeps the original limits to fall back to
+                let previous = match self.power_revert.take() {
+                    Some(pending) if pending.on_ac == on_ac => pending.previous,
+                    _ => previous,
+                };
+                self.power_revert = Some(PowerRevert {
+                    deadline: std::time::Instant::now()
+                        + std::time::Duration::from_secs(self.confirm_power.value as u64),
+                    on_ac,
+                    previous,
+                    applied: applied.clone(),
+                });
+            }
+        }
+        // Awaiting confirmation, the limits are saved once kept
+        let persist = self.power_revert.is_none();
+        self.runtime.spawn(async move {
+            if let Some(ft) = state.framework_tool.read().await.as_ref() {
+                if let Err(e) = ft.set_power_limits(tdp, fast, slow).await {
+                    eprintln!("Failed to set TDP watts: {}", e);
+                }
+                if let Err(e) = ft.set_thermal_limit_c(thermal).await {
+                    eprintln!("Failed to set thermal limit: {}", e);
+                }
+            }
+            if persist {
+                save_power_limits(&state, on_ac, applied).await;
+            }
+        });
+        self.log_activity(format!(
+            "Power limits set to {} / {}°C",
+            describe_power_limits(tdp, fast, slow),
+            thermal
+        ));
+        self.status_message = if clamped {
+            format!(
+                "⚠ TDP clamped to {}W (CPU supports {}–{}W) / {}°C",
+                tdp,
+                range.start(),
+                range.end(),
+                thermal
+            )
+        } else {
+            format!("✓ Power: {}W/{}°C", tdp, thermal)
+        };
+    }
+
+    fn show_power_confirm(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.power_revert else {
+            return;
+        };
+        let left = pending
+            .deadline
+            .saturating_duration_since(std::time::Instant::now());
+        if left.is_zero() {
+            self.revert_power_settings("not confirmed in time");
+            return;
+        }
+
+        let (mut keep, mut revert) = (false, false);
+        egui::Window::new("⚡ Keep these power settings?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "The previous limits come back in {}s unless you keep these.",
+                    left.as_secs() + 1
+                ));
+                ui.horizontal(|ui| {
+                    keep = ui.button("✓ Keep").clicked();
+                    revert = ui.button("↩ Revert now").clicked();
+                });
+            });
+        if keep {
+            if let Some(PowerRevert { on_ac, applied, .. }) = self.power_revert.take() {
+                let state = self.state.clone();
+                self.runtime.spawn(async move {
+                    save_power_limits(&state, on_ac, applied).await;
+                });
+            }
+            self.status_message = "✓ Power limits kept".to_string();
+        } else if revert {
+            self.revert_power_settings("reverted by user");
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+    }
+
+    fn revert_power_settings(&mut self, reason: &str) {
+        // The config still holds the previous limits; only the EC needs restoring
+        let Some(PowerRevert { previous, .. }) = self.power_revert.take() else {
+            return;
+        };
+        let enabled = |s: &Option<SettingU32>| s.as_ref().filter(|s| s.enabled).map(|s| s.value);
+        let limits = previous.as_ref().and_then(|p| p.power_limits_w());
+        let thermal = previous.as_ref().and_then(|p| enabled(&p.thermal_limit_c));
+        if let Some((tdp, fast, slow)) = limits {
+            self.tdp_watts = tdp;
+            self.burst_enabled = (fast, slow) != (tdp, tdp);
+            (self.fast_limit_w, self.slow_limit_w) = (fast, slow);
+        }
+        if let Some(thermal) = thermal {
+            self.thermal_limit = thermal;
+        }
+        self.power_enabled = limits.is_some() || thermal.is_some();
+
+        let state = self.state.clone();
+        self.runtime.spawn(async move {
+            if let Some(ft) = state.framework_tool.read().await.as_ref() {
+                if let Some((tdp, fast, slow)) = limits {
+                    if let Err(e) = ft.set_power_limits(tdp, fast, slow).await {
+                        eprintln!("Failed to restore TDP watts: {}", e);
+                    }
+                }
+                if let Some(thermal) = thermal {
+                    if let Err(e) = ft.set_thermal_limit_c(thermal).await {
+                        eprintln!("Failed to restore thermal limit: {}", e);
+                    }
+                }
+            }
+        });
+
+        self.log_activity(format!("Power limits reverted ({})", reason));
+        self.status_message = if self.power_enabled {
+            format!("↩ Power limits reverted ({})", reason)
+        } else {
+            // Nothing custom to re-apply; firmware defaults return on the next reboot
+            format!(
+                "↩ Custom power limits off ({}); firmware defaults return after a reboot",
+                reason
+            )
+        };
+    }
+
+    fn show_dashboard(&mut self, ui: &mut egui::Ui) {
+        ui.heading("📊 Dashboard");
+        ui.add_space(5.0);
+
+        egui::Grid::new("dashboard_grid")
+            .num_columns(2)
+            .spacing([20.0, 20.0])
+            .show(ui, |ui| {
+                // Top Left: Temperatures
+                ui.vertical(|ui| {
+                    self.show_temperature_panel(ui);
+                });
+
+                // Top Right: Power Status
+                ui.vertical(|ui| {
+                    self.show_power_panel(ui);
+                });
+                ui.end_row();
+
+                // Bottom: Fans (spanning 2 columns if possible, or just left)
+                ui.vertical(|ui| {
+                    self.show_fans_panel(ui);
+                });
+                ui.end_row();
+            });
+    }
+
+    fn show_control_center(&mut self, ui: &mut egui::Ui) {
+        ui.heading("🎛️ Control Center");
+        ui.add_space(5.0);
+
+        ui.group(|ui| {
+            self.show_profiles(ui);
+        });
+        ui.add_space(5.0);
+
+        ui.columns(2, |columns| {
+            columns[0].group(|ui| {
+                self.show_fan_control_enhanced(ui);
+            });
+            columns[1].group(|ui| {
+                self.show_power_battery_control(ui);
+            });
+        });
+    }
+
+    fn show_profiles(&mut self, ui: &mut egui::Ui) {
+        ui.heading("📁 Profiles");
+
+        let names: Vec<String> = profiles::all(&self.profiles)
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        let selected_builtin = self
+            .selected_profile
+            .as_deref()
+            .is_some_and(|name| profiles::is_builtin(&self.profiles, name));
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("profile_select")
+                .selected_text(self.selected_profile.as_deref().unwrap_or("Select…"))
+                .show_ui(ui, |ui| {
+                    for name in &names {
+                        let label = if profiles::is_builtin(&self.profiles, name) {
+                            format!("{} (built-in)", name)
+                        } else {
+                            name.clone()
+                        };
+                        ui.selectable_value(&mut self.selected_profile, Some(name.clone()), label);
+                    }
+                });
+
+            if let Some(name) = self.selected_profile.clone() {
+                if ui.button("⚡ Apply").clicked() {
+                    self.apply_profile(&name);
+                }
+                if !selected_builtin && ui.button("🗑 Delete").clicked() {
+                    self.profiles.retain(|p| p.name != name);
+                    self.selected_profile = None;
+                    self.save_profiles();
+                }
+            }
+        });
+
+        if let Some(name) = self.selected_profile.clone() {
+            // Base profile selection
+            let mut inherits = self
+                .profiles
+                .iter()
+                .find(|p| p.name == name)
+                .and_then(|p| p.inherits.clone());
+            let before = inherits.clone();
+            // Built-ins stand alone; save one under a new name to build on it
+            if !selected_builtin {
+                ui.horizontal(|ui| {
+                    ui.label("Inherits:");
+                    egui::ComboBox::from_id_salt("profile_inherits")
+                        .selected_text(inherits.as_deref().unwrap_or("(none)"))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut inherits, None, "(none)");
+                            for base in names.iter().filter(|n| **n != name) {
+                                ui.selectable_value(&mut inherits, Some(base.clone()), base);
+                            }
+                        });
+                });
+            }
+            if inherits != before {
+                if let Some(p) = self.profiles.iter_mut().find(|p| p.name == name) {
+                    p.inherits = inherits;
+                }
+                self.save_profiles();
+            }
+
+            // Effective values, marking anything taken from a base profile
+            match profiles::resolve(&self.profiles, &name) {
+                Ok(resolved) => {
+                    let origin = |from: &str| {
+                        if from == name {
+                            String::new()
+                        } else {
+                            format!("  (from {})", from)
+                        }
+                    };
+                    egui::Grid::new("profile_resolved")
+                        .num_columns(2)
+                        .spacing([20.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.label("Fan");
+                            match &resolved.fan {
+                                Some(f) => ui.label(format!(
+                                    "{}{}",
+                                    describe_fan(&f.value),
+                                    origin(&f.from)
+                                )),
+                                None => ui.weak("unchanged"),
+                            };
+                            ui.end_row();
+
+                            ui.label("Power");
+                            match &resolved.power {
+                                Some(p) => ui.label(format!(
+                                    "{}{}",
+                                    describe_power(&p.value),
+                                    origin(&p.from)
+                                )),
+                                None => ui.weak("unchanged"),
+                            };
+                            ui.end_row();
+
+                            ui.label("Battery");
+                            match &resolved.battery {
+                                Some(b) => ui.label(format!(
+                                    "{}{}",
+                                    describe_battery(&b.value),
+                                    origin(&b.from)
+                                )),
+                                None => ui.weak("unchanged"),
+                            };
+                            ui.end_row();
+                        });
+                }
+                Err(e) => {
+                    ui.colored_label(egui::Color32::RED, e);
+                }
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Save current as:");
+            ui.text_edit_singleline(&mut self.new_profile_name);
+            let name = self.new_profile_name.trim().to_string();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("💾 Save"))
+                .clicked()
+            {
+                let profile = self.profile_from_ui(&name);
+                match self.profiles.iter_mut().find(|p| p.name == name) {
+                    Some(existing) => {
+                        // Keep the existing base link when overwriting
+                        let inherits = existing.inherits.take();
+                        *existing = Profile { inherits, ..profile };
+                    }
+                    None => self.profiles.push(profile),
+                }
+                self.selected_profile = Some(name);
+                self.new_profile_name.clear();
+                self.save_profiles();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .button("📤 Export Preset")
+                .on_hover_text("Save the fan curve and your profiles to a file to share")
+                .clicked()
+            {
+                self.export_preset();
+            }
+            if ui
+                .button("📥 Import Preset")
+                .on_hover_text("Load a shared fan curve and profiles")
+                .clicked()
+            {
+                self.import_preset();
+            }
+        });
+    }
+
+    fn export_preset(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Export preset")
+            .add_filter("JSON", &["json"])
+            .set_file_name("framework-control-preset.json")
+            .save_file()
+        else {
+            return;
+        };
+        // The editor's points with the applied curve's timing settings
+        let mut curve = self
+            .state
+            .config
+            .try_read()
+            .ok()
+            .and_then(|c| c.fan.curve.clone())
+            .unwrap_or_default();
+        curve.points = self
+            .shared_curve()
+            .iter()
+            .map(|(t, d)| [*t as u32, *d as u32])
+            .collect();
+        curve.interpolation = self.curve_interpolation;
+        let json = preset::export(Some(curve), self.profiles.clone());
+        self.status_message = match std::fs::write(&path, json) {
+            Ok(()) => format!(
+                "✓ Exported the fan curve and {} profile(s) to {}",
+                self.profiles.len(),
+                path.display()
+            ),
+            Err(e) => format!("❌ Failed to write {}: {}", path.display(), e),
+        };
+    }
+
+    fn import_preset(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Import preset")
+            .add_filter("JSON", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        let imported = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+            .and_then(|json| preset::import(&json));
+        let preset = match imported {
+            Ok(preset) => preset,
+            Err(e) => {
+                self.status_message = format!("❌ Import failed: {}", e);
+                return;
+            }
+        };
+
+        let mut parts = Vec::new();
+        if let Some(curve) = preset.curve {
+            self.fan_curve = curve
+                .points
+                .iter()
+                .map(|p| (p[0] as f32, p[1] as f32))
+                .collect();
+            self.curve_interpolation = curve.interpolation;
+            let state = self.state.clone();
+            self.runtime.spawn(async move {
+                let mut cfg = state.config.write().await;
+                cfg.fan.curve = Some(curve);
+                config::save(&cfg);
+            });
+            parts.push("the fan curve".to_string());
+        }
+        if !preset.profiles.is_empty() {
+            let count = preset.profiles.len();
+            // A profile with the same name is replaced
+            for profile in preset.profiles {
+                self.profiles.retain(|p| p.name != profile.name);
+                self.profiles.push(profile);
+            }
+            self.save_profiles();
+            parts.push(format!("{} profile(s)", count));
+        }
+        let message = format!("Imported {} from {}", parts.join(" and "), path.display());
+        self.log_activity(message.clone());
+        self.status_message = format!("✓ {}", message);
+    }
+
+    fn profile_from_ui(&self, name: &str) -> Profile {
+        let mode = if self.auto_fan {
+            FanControlMode::Disabled
+        } else if self.fan_curve_enabled {
+            FanControlMode::Curve
+        } else {
+            FanControlMode::Manual
+        };
+        let power_profile = PowerProfile {
+            tdp_watts: Some(SettingU32 {
+                enabled: self.power_enabled,
+                value: self.tdp_watts,
+            }),
+            fast_limit_w: Some(SettingU32 {
+                enabled: self.burst_enabled,
+                value: self.fast_limit_w,
+            }),
+            slow_limit_w: Some(SettingU32 {
+                enabled: self.burst_enabled,
+                value: self.slow_limit_w,
+            }),
+            thermal_limit_c: Some(SettingU32 {
+                enabled: self.power_enabled,
+                value: self.thermal_limit,
+            }),
+        };
+        Profile {
+            name: name.to_string(),
+            inherits: None,
+            fan: Some(FanControlConfig {
+                mode: Some(mode),
+                manual: Some(ManualConfig {
+                    duty_pct: self.fan_duty,
+                }),
+                curve: Some(CurveConfig {
+                    points: self
+                        .shared_curve()
+                        .iter()
+                        .map(|(t, d)| [*t as u32, *d as u32])
+                        .collect(),
+                    interpolation: self.curve_interpolation,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            // Limits that aren't switched on are left out, so they come from the base profile
+            power: (self.power_enabled || self.burst_enabled).then(|| PowerConfig {
+                ac: Some(power_profile.clone()),
+                battery: Some(power_profile),
+            }),
+            battery: self.charge_limit_enabled.then(|| BatteryConfig {
+                charge_limit_max_pct: Some(SettingU8 {
+                    enabled: true,
+                    value: self.charge_limit,
+                }),
+                charge_limit_min_pct: Some(self.charge_limit_min),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn save_profiles(&mut self) {
+        let profiles = self.profiles.clone();
+        let state = self.state.clone();
+        self.runtime.spawn(async move {
+            let mut cfg = state.config.write().await;
+            cfg.profiles = profiles;
+            config::save(&cfg);
+        });
+    }
+
+    fn apply_profile(&mut self, name: &str) {
+        let resolved = match profiles::resolve(&self.profiles, name) {
+            Ok(r) => r,
+            Err(e) => {
+                self.status_message = format!("❌ {}", e);
+                return;
+            }
+        };
+
+        // Mirror the effective values into the controls, then apply them
+        if let Some(fan) = &resolved.fan {
+            let fan = &fan.value;
+            if let Some(manual) = &fan.manual {
+                self.fan_duty = manual.duty_pct;
+            }
+            if let Some(curve) = &fan.curve {
+                self.fan_curve = curve
+                    .points
+                    .iter()
+                    .map(|p| (p[0] as f32, p[1] as f32))
+                    .collect();
+                self.curve_interpolation = curve.interpolation;
+            }
+            match fan.mode.clone().unwrap_or_default() {
+                FanControlMode::Disabled => self.reset_fan_to_auto(),
+                FanControlMode::Manual => {
+                    self.auto_fan = false;
+                    self.fan_curve_enabled = false;
+                    self.apply_fan_speed();
+                }
+                FanControlMode::Curve => {
+                    self.auto_fan = false;
+                    self.fan_curve_enabled = true;
+                    self.apply_fan_curve();
+                }
+            }
+        }
+        if let Some(power) = &resolved.power {
+            let on_ac = self
+                .power_data
+                .as_ref()
+                .is_some_and(|p| p.status.contains("Charging"));
+            let profile = if on_ac {
+                power.value.ac.as_ref()
+            } else {
+                power.value.battery.as_ref()
+            };
+            if let Some(profile) = profile {
+                let tdp = profile.tdp_watts.clone().unwrap_or_default();
+                let thermal = profile.thermal_limit_c.clone().unwrap_or_default();
+                self.power_enabled = tdp.enabled || thermal.enabled;
+                if let Some((tdp, fast, slow)) = profile.power_limits_w() {
+                    self.tdp_watts = tdp;
+                    self.burst_enabled = (fast, slow) != (tdp, tdp);
+                    (self.fast_limit_w, self.slow_limit_w) = (fast, slow);
+                }
+                if thermal.enabled {
+                    self.thermal_limit = thermal.value;
+                }
+                if self.power_enabled {
+                    self.apply_power_settings();
+                }
+            }
+        }
+        if let Some(battery) = &resolved.battery {
+            if let Some(limit) = &battery.value.charge_limit_max_pct {
+                self.charge_limit_enabled = limit.enabled;
+                self.charge_limit = limit.value;
+                self.charge_limit_min =
+                    charge_window(limit.value, battery.value.charge_limit_min_pct).0;
+                if limit.enabled {
+                    self.apply_charge_limit();
+                }
+            }
+        }
+
+        let state = self.state.clone();
+        self.runtime.spawn(async move {
+            let mut cfg = state.config.write().await;
+            profiles::apply_to_config(&resolved, &mut cfg);
+            config::save(&cfg);
+        });
+        self.log_activity(format!("Switched to profile '{}'", name));
+        self.status_message = format!("✓ Profile: {}", name);
+    }
+
+    fn show_advanced_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("🛠️ Advanced / BIOS");
+        ui.add_space(5.0);
+
+        ui.group(|ui| {
+            ui.label("Experimental Features");
+            ui.separator();
+
+            if let Some(mut level) = self.keyboard_backlight {
+                ui.horizontal(|ui| {
+                    ui.label("Keyboard Backlight:");
+                    let response = ui.add(egui::Slider::new(&mut level, 0..=100).suffix("%"));
+                    // Sent once the drag ends rather than on every step
+                    if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                        self.set_keyboard_backlight(level);
+                    }
+                });
+                self.keyboard_backlight = Some(level);
+
+                ui.add_space(10.0);
+                ui.separator();
+            }
+
+            // Raw EC command runner, sent only after confirmation
+            ui.label("Raw EC Command:");
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.custom_command)
+                        .hint_text("0x0002 [version] [hex payload]"),
+                );
+                if ui.button("Run").clicked() {
+                    self.run_custom_command();
+                }
+            });
+
+            let output = self
+                .command_output
+                .try_read()
+                .map(|o| o.clone())
+                .unwrap_or_default();
+            if !output.is_empty() {
+                ui.add_space(5.0);
+                ui.label("Output:");
+                egui::ScrollArea::vertical()
+                    .max_height(100.0)
+                    .show(ui, |ui| {
+                        ui.monospace(output);
+                    });
+            }
+
+            self.show_ec_macros(ui);
+
+            ui.add_space(10.0);
+            ui.separator();
+
+            // Every temperature slot, whether or not it has a friendly name
+            ui.checkbox(&mut self.show_raw_sensors, "Show all raw sensors");
+            if self.show_raw_sensors {
+                match self.thermal_data.as_ref().map(|t| &t.raw_sensors) {
+                    Some(raw) if !raw.is_empty() => {
+                        egui::Grid::new("raw_sensors")
+                            .num_columns(4)
+                            .spacing([20.0, 2.0])
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Slot");
+                                ui.strong("Raw");
+                                ui.strong("Status");
+                                ui.strong("Temp");
+                                ui.end_row();
+                                for (slot, byte) in raw.iter().enumerate() {
+                                    let (status, temp) = diagnostics::decode_sensor(*byte);
+                                    ui.monospace(format!("0x{:02X}", slot));
+                                    ui.monospace(format!("0x{:02X}", byte));
+                                    ui.label(status);
+                                    match temp {
+                                        Some(t) => ui.colored_label(
+                                            temp_color(
+                                                t as f32,
+                                                SensorCategory::Other.default_thresholds(),
+                                            ),
+                                            format!("{}°C", t),
+                                        ),
+                                        None => ui.weak("—"),
+                                    };
+                                    ui.end_row();
+                                }
+                            });
+                    }
+                    _ => {
+                        ui.weak("No sensor data");
+                    }
+                }
+            }
+
+            ui.add_space(10.0);
+            ui.separator();
+
+            // EC memory map diagnostic for bug reports
+            ui.horizontal(|ui| {
+                if ui
+                    .button("📦 Export Diagnostics")
+                    .on_hover_text(
+                        "Versions, CPU, mainboard, EC status, config, recent telemetry, \
+                         EC memory map, activity and log tails, zipped for a bug report",
+                    )
+                    .clicked()
+                {
+                    self.export_diagnostics();
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("EC Diagnostic:");
+                if ui.button("🩺 Run").clicked() {
+                    let slot = self.ec_report.clone();
+                    self.runtime.spawn(async move {
+                        let report = tokio::task::spawn_blocking(diagnostics::ec_map_report)
+                            .await
+                            .ok()
+                            .flatten();
+                        if report.is_none() {
+                            tracing::warn!("EC diagnostic: memory map unreadable");
+                        }
+                        *slot.write().await = report;
+                    });
+                }
+            });
+            if let Ok(report) = self.ec_report.try_read() {
+                if let Some(report) = report.as_ref() {
+                    let text = report.to_text();
+                    ui.horizontal(|ui| {
+                        if ui.button("📋 Copy Text").clicked() {
+                            ui.ctx().copy_text(text.clone());
+                        }
+                        if ui.button("📋 Copy JSON").clicked() {
+                            ui.ctx().copy_text(report.to_json());
+                        }
+                    });
+                    egui::ScrollArea::vertical()
+                        .id_salt("ec_report")
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            ui.monospace(text);
+                        });
+                }
+            }
+            if self.advanced_tools {
+                self.show_ec_memory(ui);
+            }
+        });
+    }
+
+    fn refresh_ec_memory(&self) {
+        let slot = self.ec_memory.clone();
+        self.runtime.spawn(async move {
+            let mem = tokio::task::spawn_blocking(|| ec::read_ec_memory(0x00, 0xFF))
+                .await
+                .ok()
+                .flatten();
+            *slot.write().await = Some(mem);
+        });
+    }
+
+    /// Read-only hex/ASCII view of the EC memory map, for finding offsets on new boards
+    fn show_ec_memory(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("🔍 EC Memory")
+            .default_open(false)
+            .show(ui, |ui| {
+                let dump = match self.ec_memory.try_read().as_deref() {
+                    Ok(Some(Some(mem))) => Some(diagnostics::hex_dump(mem)),
+                    Ok(Some(None)) => {
+                        ui.colored_label(egui::Color32::RED, "❌ EC memory map unreadable");
+                        None
+                    }
+                    _ => None,
+                };
+                ui.horizontal(|ui| {
+                    if ui.button("🔄 Refresh").clicked() {
+                        self.refresh_ec_memory();
+                    }
+                    if let Some(dump) = &dump {
+                        if ui.button("📋 Copy").clicked() {
+                            ui.ctx().copy_text(dump.clone());
+                        }
+                    }
+                });
+                match dump {
+                    Some(dump) => {
+                        egui::ScrollArea::vertical()
+                            .id_salt("ec_memory")
+                            .max_height(260.0)
+                            .show(ui, |ui| {
+                                ui.monospace(dump);
+                            });
+                    }
+                    None => {
+                        ui.weak("Refresh to read 0x00–0xFE from the EC");
+                    }
+                }
+            });
+    }
+
+    fn show_ec_macros(&mut self, ui: &mut egui::Ui) {
+        let macros = match self.state.config.try_read() {
+            Ok(cfg) => cfg.ec_macros.clone(),
+            Err(_) => return,
+        };
+        ui.add_space(5.0);
+        ui.horizontal_wrapped(|ui| {
+            ui.label("EC Macros:");
+            if macros.is_empty() {
+                ui.weak("none defined (add \"ec_macros\" to config.json)");
+            }
+            for ec_macro in macros {
+                let label = format!("▶ {} ({} steps)", ec_macro.name, ec_macro.steps.len());
+                if ui.button(label).clicked() {
+                    self.run_ec_macro(ec_macro);
+                }
+            }
+        });
+
+        let Ok(results) = self.macro_results.try_read() else {
+            return;
+        };
+        let Some(run) = results.as_ref() else {
+            return;
+        };
+        ui.label(format!("Last run: {}", run.name));
+        egui::Grid::new("macro_results")
+            .num_columns(3)
+            .spacing([10.0, 2.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for (i, step) in run.steps.iter().enumerate() {
+                    ui.monospace(format!(
+                        "{}. 0x{:04X} v{}",
+                        i + 1,
+                        step.command,
+                        step.version
+                    ));
+                    match &step.result {
+                        Ok(resp) => {
+                            ui.colored_label(egui::Color32::from_rgb(0, 200, 0), "✅");
+                            ui.monospace(cli::hex_bytes(resp));
+                        }
+                        Err(e) => {
+                            ui.colored_label(egui::Color32::RED, "❌");
+                            ui.label(e);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn set_keyboard_backlight(&mut self, level: u8) {
+        let state = self.state.clone();
+        self.runtime.spawn(async move {
+            let result = cli::FrameworkTool::new()
+                .await
+                .set_keyboard_backlight(level)
+                .await;
+            if let Err(e) = result {
+                tracing::error!("Keyboard backlight: {}", e);
+                activity::push(&state.activity, format!("Keyboard backlight: {}", e)).await;
+            }
+        });
+        self.status_message = format!("✓ Keyboard backlight: {}%", level);
+    }
+
+    fn restore_firmware_defaults(&mut self) {
+        let (state, slot) = (self.state.clone(), self.firmware_reset.clone());
+        self.boost_until = None;
+        self.runtime.spawn(async move {
+            // Stop the background tasks first so they don't immediately re-apply the config
+            *state.control_paused.write().await = true;
+            *state.fan_boost_until.write().await = None;
+            let steps = match cli::FrameworkTool::new()
+                .await
+                .restore_firmware_defaults()
+                .await
+            {
+                Ok(steps) => steps,
+                Err(e) => vec![ec::ResetStep {
+                    action: "Restore firmware defaults",
+                    result: Err(e),
+                }],
+            };
+            for step in &steps {
+                match &step.result {
+                    Ok(()) => tracing::info!("Firmware defaults: {} ok", step.action),
+                    Err(e) => tracing::warn!("Firmware defaults: {} failed: {}", step.action, e),
+                }
+            }
+            let ok = steps.iter().filter(|s| s.result.is_ok()).count();
+            activity::push(
+                &state.activity,
+                format!(
+                    "Restored firmware defaults ({}/{} actions ok)",
+                    ok,
+                    steps.len()
+                ),
+            )
+            .await;
+            *slot.write().await = Some(steps);
+        });
+        self.status_message = "🛑 Restoring firmware defaults".to_string();
+    }
+
+    fn show_firmware_reset(&mut self, ui: &mut egui::Ui) {
+        if !self.state.control_paused.try_read().is_ok_and(|p| *p) {
+            return;
+        }
+        ui.separator();
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    "🛑 Firmware defaults active; saved settings are not being applied",
+                );
+                if ui.button("▶ Resume app control").clicked() {
+                    let (state, slot) = (self.state.clone(), self.firmware_reset.clone());
+                    self.runtime.spawn(async move {
+                        *state.control_paused.write().await = false;
+                        *slot.write().await = None;
+                        activity::push(&state.activity, "Resumed app control").await;
+                    });
+                }
+            });
+            if let Ok(slot) = self.firmware_reset.try_read() {
+                for step in slot.iter().flatten() {
+                    match &step.result {
+                        Ok(()) => ui.label(format!("✓ {}", step.action)),
+                        Err(e) => ui
+                            .colored_label(egui::Color32::RED, format!("✗ {}: {}", step.action, e)),
+                    };
+                }
+            }
+        });
+    }
+
+    fn run_ec_macro(&mut self, ec_macro: EcMacro) {
+        let (state, slot) = (self.state.clone(), self.macro_results.clone());
+        self.status_message = format!("Running macro '{}'", ec_macro.name);
+        self.runtime.spawn(async move {
+            let Some(tool) = state.framework_tool.read().await.clone() else {
+                return;
+            };
+            let run = tool.run_macro(&ec_macro).await;
+            let ok = run.steps.iter().filter(|r| r.result.is_ok()).count();
+            activity::push(
+                &state.activity,
+                format!(
+                    "Ran EC macro '{}' ({}/{} steps ok)",
+                    ec_macro.name,
+                    ok,
+                    ec_macro.steps.len()
+                ),
+            )
+            .await;
+            *slot.write().await = Some(run);
+        });
+    }
+
+    fn run_custom_command(&mut self) {
+        let args: Vec<String> = self
+            .custom_command
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+        if args.is_empty() {
+            return;
+        }
+        match cli::RawCommand::parse(&args) {
+            Ok(raw) => self.raw_command_pending = Some((args, raw)),
+            Err(e) => {
+                if let Ok(mut output) = self.command_output.try_write() {
+                    *output = format!("❌ {}", e);
+                }
+            }
+        }
+    }
+
+    fn show_raw_command_confirm(&mut self, ctx: &egui::Context) {
+        let Some((_, raw)) = &self.raw_command_pending else {
+            return;
+        };
+        let (mut send, mut cancel) = (false, false);
+        egui::Window::new("⚠ Send raw EC command?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.monospace(format!(
+                    "Command 0x{:04X}, version {}",
+                    raw.command, raw.version
+                ));
+                if raw.data.is_empty() {
+                    ui.monospace("No payload");
+                } else {
+                    ui.monospace(format!("Payload: {}", cli::hex_bytes(&raw.data)));
+                }
+                ui.label("The EC acts on this as-is, bypassing every limit this app enforces.");
+                ui.horizontal(|ui| {
+                    send = ui.button("📤 Send").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+        if cancel {
+            self.raw_command_pending = None;
+        }
+        if !send {
+            return;
+        }
+        let Some((args, raw)) = self.raw_command_pending.take() else {
+            return;
+        };
+        let (state, output) = (self.state.clone(), self.command_output.clone());
+        self.runtime.spawn(async move {
+            let Some(tool) = state.framework_tool.read().await.clone() else {
+                *output.write().await = "❌ Framework tool not available".to_string();
+                return;
+            };
+            let result = tool.run_raw_command(args).await;
+            let text = match &result {
+                Ok(resp) => format!("✅ {}", resp),
+                Err(e) => format!("❌ {}", e),
+            };
+            activity::push(
+                &state.activity,
+                format!(
+                    "Sent raw EC command 0x{:04X} v{} ({})",
+                    raw.command,
+                    raw.version,
+                    if result.is_ok() { "ok" } else { "failed" }
+                ),
+            )
+            .await;
+            *output.write().await = text;
+        });
+    }
+
+    fn apply_charge_limit(&mut self) {
+        let (min, limit) = charge_window(self.charge_limit, Some(self.charge_limit_min));
+        self.charge_limit_min = min;
+        self.charge_limit_requested = Some((min, limit));
+        let state = self.state.clone();
+        let readback = self.ec_charge_limit.clone();
+
+        // Apply to hardware and keep the battery task in sync
+        self.runtime.spawn(async move {
+            send_charge_window(&state, &readback, min, limit).await;
+            let mut cfg = state.config.write().await;
+            cfg.battery.charge_limit_max_pct = Some(SettingU8 {
+                enabled: true,
+                value: limit,
+            });
+            cfg.battery.charge_limit_min_pct = Some(min);
+            // A limit chosen now replaces a pending top-up
+            cfg.battery.charge_to_full_once = false;
+            config::save(&cfg);
+        });
+
+        self.status_message = format!("✓ Charge Limit: {}–{}%", min, limit);
+    }
+
+    /// One-shot top-up to 100%, e.g. before a trip; the battery task restores the limit
+    /// once the battery reports full
+    fn show_charge_to_full(&mut self, ui: &mut egui::Ui) {
+        let Ok(pending) = self
+            .state
+            .config
+            .try_read()
+            .map(|cfg| cfg.battery.charge_to_full_once)
+        else {
+            return;
+        };
+        ui.horizontal(|ui| {
+            if pending {
+                ui.label(format!(
+                    "🔌 Charging to 100% once; {}% applies again when full",
+                    self.charge_limit
+                ));
+                if ui.button("Cancel").clicked() {
+                    self.set_charge_to_full(false);
+                }
+            } else if ui
+                .button("🔌 Charge to full once")
+                .on_hover_text("Charge to 100% this time, then go back to the limit")
+                .clicked()
+            {
+                self.set_charge_to_full(true);
+            }
+        });
+    }
+
+    fn set_charge_to_full(&mut self, once: bool) {
+        let (min, max) = if once {
+            charge_window(100, None)
+        } else {
+            charge_window(self.charge_limit, Some(self.charge_limit_min))
+        };
+        self.charge_limit_requested = Some((min, max));
+        let state = self.state.clone();
+        let readback = self.ec_charge_limit.clone();
+        self.runtime.spawn(async move {
+            {
+                let mut cfg = state.config.write().await;
+                cfg.battery.charge_to_full_once = once;
+                config::save(&cfg);
+            }
+            send_charge_window(&state, &readback, min, max).await;
+        });
+        let message = if once {
+            "Charging to 100% once".to_string()
+        } else {
+            format!("Top-up cancelled; {}% charge limit restored", max)
+        };
+        self.log_activity(message.clone());
+        self.status_message = format!("✓ {}", message);
+    }
+
+    fn handle_tray(&mut self, ctx: &egui::Context) {
+        while let Some(action) = self.tray_actions.as_mut().and_then(|rx| rx.try_recv().ok()) {
+            match action {
+                tray::TrayAction::Show => {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                }
+                tray::TrayAction::AutoFan => self.reset_fan_to_auto(),
+                tray::TrayAction::MaxFan => {
+                    self.fan_duty = 100;
+                    self.apply_fan_speed();
+                }
+                tray::TrayAction::ToggleChargeLimit => {
+                    self.charge_limit_enabled = !self.charge_limit_enabled;
+                    if self.charge_limit_enabled {
+                        self.apply_charge_limit();
+                    } else {
+                        self.clear_charge_limit();
+                    }
+                }
+                tray::TrayAction::Quit => {
+                    self.quitting = true;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            }
+        }
+
+        let Some(tray) = &self.tray else {
+            return;
+        };
+        tray.set_charge_limit(self.charge_limit_enabled);
+        let hottest = self
+            .thermal_data
+            .as_ref()
+            .and_then(|t| t.sensors.iter().map(|s| s.temp_c).reduce(f32::max));
+        let tooltip = match hottest {
+            Some(temp) => format!("Framework Control · {:.0}°C max", temp),
+            None => "Framework Control".to_string(),
+        };
+        if tooltip != self.tray_tooltip {
+            tray.set_tooltip(&tooltip);
+            self.tray_tooltip = tooltip;
+        }
+
+        // Closing hides the window; the app keeps running from the tray
+        if self.minimize_to_tray && !self.quitting && ctx.input(|i| i.viewport().close_requested())
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        }
+    }
+
+    fn show_toasts(&mut self, ctx: &egui::Context) {
+        let Ok(mut toasts) = self.state.toasts.try_write() else {
+            return;
+        };
+        toasts.retain(|t| t.at.elapsed() < notify::TOAST_TTL);
+        if toasts.is_empty() {
+            return;
+        }
+
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+            .show(ctx, |ui| {
+                for toast in toasts.iter() {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.strong(&toast.title);
+                        ui.label(&toast.body);
+                    });
+                }
+            });
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+    }
+
+    fn show_whats_new(&mut self, ctx: &egui::Context) {
+        if self.whats_new.is_empty() {
+            return;
+        }
+
+        let mut dismissed = false;
+        egui::Window::new("✨ What's New")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                for (version, notes) in &self.whats_new {
+                    ui.strong(format!("v{}", version));
+                    for note in notes.iter() {
+                        ui.label(format!("• {}", note));
+                    }
+                    ui.add_space(5.0);
+                }
+                ui.separator();
+                if ui.button("Got it").clicked() {
+                    dismissed = true;
+                }
+            });
 
-        // Apply to hardware
+        if dismissed {
+            self.whats_new.clear();
+            let state = self.state.clone();
+            self.runtime.spawn(async move {
+                let mut cfg = state.config.write().await;
+                cfg.last_seen_version = Some(env!("CARGO_PKG_VERSION").to_string());
+                config::save(&cfg);
+            });
+        }
+    }
+
+    /// Offer the relaunch up front when the EC refuses a non-elevated process, rather than
+    /// leaving every panel empty
+    fn show_elevation_prompt(&mut self, ctx: &egui::Context) {
+        if self.ec_status != EcStatus::AccessDenied
+            || self.elevated
+            || self.elevation_prompt_dismissed
+        {
+            return;
+        }
+
+        egui::Window::new("🔒 Administrator Rights Needed")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("The EC driver refused access, so temperatures, fans and limits can't be read or changed.");
+                ui.label("Relaunch Framework Control as administrator to fix this.");
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("🛡️ Restart as Administrator").clicked() {
+                        ec::restart_as_admin();
+                    }
+                    if ui.button("Not now").clicked() {
+                        self.elevation_prompt_dismissed = true;
+                    }
+                });
+            });
+    }
+
+    /// Profile whose power limits take over while the battery is critical
+    fn show_critical_profile(&mut self, ui: &mut egui::Ui) {
+        let current = match self.state.config.try_read() {
+            Ok(cfg) => cfg.battery.critical_profile.clone(),
+            Err(_) => return,
+        };
+        let mut selected = current.clone();
+        ui.horizontal(|ui| {
+            ui.label("When critical:");
+            egui::ComboBox::from_id_salt("critical_profile")
+                .selected_text(selected.as_deref().unwrap_or("Just warn"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut selected, None, "Just warn");
+                    for profile in profiles::all(&self.profiles) {
+                        ui.selectable_value(
+                            &mut selected,
+                            Some(profile.name.clone()),
+                            format!("Switch to '{}'", profile.name),
+                        );
+                    }
+                });
+        });
+        if selected != current {
+            let state = self.state.clone();
+            self.runtime.spawn(async move {
+                let mut cfg = state.config.write().await;
+                cfg.battery.critical_profile = selected;
+                config::save(&cfg);
+            });
+        }
+    }
+
+    /// Stop re-asserting custom limits; the firmware defaults return on the next reboot
+    fn clear_power_limits(&mut self) {
+        let state = self.state.clone();
+        self.runtime.spawn(async move {
+            let mut cfg = state.config.write().await;
+            let power = &mut cfg.power;
+            for profile in [power.ac.as_mut(), power.battery.as_mut()]
+                .into_iter()
+                .flatten()
+            {
+                for setting in [profile.tdp_watts.as_mut(), profile.thermal_limit_c.as_mut()]
+                    .into_iter()
+                    .flatten()
+                {
+                    setting.enabled = false;
+                }
+            }
+            config::save(&cfg);
+        });
+        self.log_activity("Custom power limits off".to_string());
+        self.status_message = "✓ Custom power limits off".to_string();
+    }
+
+    fn clear_charge_limit(&mut self) {
+        let (min, max) = charge_window(100, None);
+        self.charge_limit_requested = Some((min, max));
+        let state = self.state.clone();
+        let readback = self.ec_charge_limit.clone();
         self.runtime.spawn(async move {
             if let Some(ft) = state.framework_tool.read().await.as_ref() {
-                let _ = ft.charge_limit_set(limit).await;
+                let _ = ft.charge_limit_set(min, max).await;
+                *readback.write().await = ft.charge_limit_get().await.ok();
+            }
+            let mut cfg = state.config.write().await;
+            if let Some(limit) = cfg.battery.charge_limit_max_pct.as_mut() {
+                limit.enabled = false;
+            }
+            config::save(&cfg);
+        });
+        self.log_activity("Charge limit off".to_string());
+        self.status_message = "✓ Charge Limit: off".to_string();
+    }
+
+    fn log_activity(&self, message: String) {
+        let activity = self.state.activity.clone();
+        self.runtime.spawn(async move {
+            activity::push(&activity, message).await;
+        });
+    }
+
+    fn show_recording(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("⏺ Recording")
+            .default_open(false)
+            .show(ui, |ui| {
+                let active = self
+                    .state
+                    .recording
+                    .try_read()
+                    .ok()
+                    .and_then(|r| r.as_ref().map(|r| (r.label.clone(), r.started, r.samples)));
+                match active {
+                    Some((label, started, samples)) => {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(
+                                egui::Color32::RED,
+                                format!(
+                                    "⏺ {} · {}s · {} samples",
+                                    label,
+                                    started.elapsed().as_secs(),
+                                    samples
+                                ),
+                            );
+                            if ui.button("⏹ Stop").clicked() {
+                                self.stop_recording();
+                            }
+                        });
+                    }
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.label("Label:");
+                            ui.text_edit_singleline(&mut self.recording_label);
+                            if ui.button("⏺ Start recording").clicked() {
+                                self.start_recording();
+                            }
+                        });
+                        ui.weak(format!(
+                            "Samples are written to {} until stopped.",
+                            recording::recordings_dir().display()
+                        ));
+                    }
+                }
+            });
+    }
+
+    fn start_recording(&mut self) {
+        let Ok(cfg) = self.state.config.try_read() else {
+            return;
+        };
+        let label = match self.recording_label.trim() {
+            "" => "Recording".to_string(),
+            label => label.to_string(),
+        };
+        let header = recording::RecordingHeader {
+            label: label.clone(),
+            started_ms: 0,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            cpu: cpu::brand_string().to_string(),
+            ec_version: self
+                .versions
+                .as_ref()
+                .map(|v| v.ec_version.clone())
+                .unwrap_or_default(),
+            bios_version: self
+                .versions
+                .as_ref()
+                .map(|v| v.bios_version.clone())
+                .unwrap_or_default(),
+            settings: format!(
+                "Fan: {} | Power: {}",
+                describe_fan(&cfg.fan),
+                describe_power(&cfg.power)
+            ),
+        };
+        drop(cfg);
+        match recording::Recorder::start(header) {
+            Ok(recorder) => {
+                self.status_message = format!("⏺ Recording to {}", recorder.path.display());
+                self.log_activity(format!("Started recording '{}'", label));
+                let state = self.state.clone();
+                self.runtime.spawn(async move {
+                    *state.recording.write().await = Some(recorder);
+                });
+                self.recording_label.clear();
             }
+            Err(e) => self.status_message = format!("❌ {}", e),
+        }
+    }
+
+    fn stop_recording(&mut self) {
+        let state = self.state.clone();
+        self.runtime.spawn(async move {
+            let Some(recorder) = state.recording.write().await.take() else {
+                return;
+            };
+            let label = recorder.label.clone();
+            let message = match recorder.stop() {
+                Ok((path, samples)) => format!(
+                    "Saved recording '{}' ({} samples) to {}",
+                    label,
+                    samples,
+                    path.display()
+                ),
+                Err(e) => format!("Recording '{}' failed to close: {}", label, e),
+            };
+            activity::push(&state.activity, message).await;
+        });
+        self.status_message = "⏹ Recording stopped".to_string();
+    }
+
+    fn capture_snapshot(&mut self) {
+        let (Ok(samples), Ok(cfg)) = (
+            self.state.telemetry_samples.try_read(),
+            self.state.config.try_read(),
+        ) else {
+            return;
+        };
+        let samples: Vec<TelemetrySample> = samples.iter().cloned().collect();
+        let settings = format!(
+            "Fan: {} | Power: {}",
+            describe_fan(&cfg.fan),
+            describe_power(&cfg.power)
+        );
+        let label = match self.snapshot_label.trim() {
+            "" => format!("Snapshot {}", self.snapshots.len() + 1),
+            label => label.to_string(),
+        };
+        match snapshot::capture(
+            &label,
+            settings,
+            &samples,
+            self.snapshot_window_s,
+            &self.noise_map,
+        ) {
+            Some(snap) => {
+                self.snapshots.push(snap);
+                // Compare the newest against the one before it
+                let last = self.snapshots.len() - 1;
+                self.compare = (last.saturating_sub(1), last);
+                self.snapshot_label.clear();
+                self.status_message = format!("✓ Captured '{}'", label);
+            }
+            None => self.status_message = "❌ No telemetry recorded yet".to_string(),
+        }
+    }
+
+    fn show_telemetry_charts(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("📈 History")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Window:");
+                    for mins in [1, 5, 15] {
+                        ui.selectable_value(
+                            &mut self.chart_window_min,
+                            mins,
+                            format!("{} min", mins),
+                        );
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("💾 Export CSV").clicked() {
+                            self.export_telemetry_csv();
+                        }
+                    });
+                });
+                let Ok(samples) = self.state.telemetry_samples.try_read() else {
+                    return;
+                };
+                let Some(newest) = samples.back().map(|s| s.ts_ms) else {
+                    ui.weak("No telemetry recorded yet");
+                    return;
+                };
+                let window_s = self.chart_window_min as f32 * 60.0;
+                // (seconds before the newest sample, sample) for everything in the window
+                let recent: Vec<(f32, &TelemetrySample)> = samples
+                    .iter()
+                    .map(|s| ((newest - s.ts_ms) as f32 / 1000.0, s))
+                    .filter(|(ago, _)| *ago <= window_s)
+                    .collect();
+
+                let mut temps: Vec<ChartSeries> = Vec::new();
+                let mut fans: Vec<ChartSeries> = Vec::new();
+                let mut battery: ChartSeries = ("Charge".to_string(), Vec::new());
+                let mut power: [ChartSeries; 2] = [
+                    ("Charger (est.)".to_string(), Vec::new()),
+                    ("Battery".to_string(), Vec::new()),
+                ];
+                for (ago, sample) in &recent {
+                    for (name, temp) in &sample.temps {
+                        match temps.iter_mut().find(|(n, _)| n == name) {
+                            Some((_, points)) => points.push((*ago, *temp)),
+                            None => temps.push((name.clone(), vec![(*ago, *temp)])),
+                        }
+                    }
+                    for (i, rpm) in sample.fan_rpms.iter().enumerate() {
+                        if fans.len() <= i {
+                            fans.push((format!("Fan {}", i + 1), Vec::new()));
+                        }
+                        fans[i].1.push((*ago, *rpm));
+                    }
+                    if let Some(pct) = sample.battery_percent {
+                        battery.1.push((*ago, pct));
+                    }
+                    for ((_, points), watts) in
+                        power.iter_mut().zip([sample.charger_w, sample.battery_w])
+                    {
+                        if let Some(watts) = watts {
+                            points.push((*ago, watts));
+                        }
+                    }
+                }
+
+                ui.label("Temperatures");
+                line_chart(ui, &temps, window_s, "°C");
+                ui.label("Fans");
+                line_chart(ui, &fans, window_s, " RPM");
+                ui.label("Battery");
+                line_chart(ui, &[battery], window_s, "%");
+                ui.label("Power");
+                line_chart(ui, &power, window_s, " W");
+            });
+    }
+
+    fn export_telemetry_csv(&mut self) {
+        let Ok(samples) = self.state.telemetry_samples.try_read() else {
+            return;
+        };
+        if samples.is_empty() {
+            self.status_message = "❌ No telemetry recorded yet".to_string();
+            return;
+        }
+        let samples: Vec<TelemetrySample> = samples.iter().cloned().collect();
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Export telemetry")
+            .add_filter("CSV", &["csv"])
+            .set_file_name("telemetry.csv")
+            .save_file()
+        else {
+            return;
+        };
+        self.status_message = match std::fs::write(&path, csv_export::telemetry_csv(&samples)) {
+            Ok(()) => format!("✓ Exported {} samples to {}", samples.len(), path.display()),
+            Err(e) => format!("❌ Failed to write {}: {}", path.display(), e),
+        };
+    }
+
+    fn export_diagnostics(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Export diagnostics")
+            .add_filter("Zip", &["zip"])
+            .set_file_name("framework-control-diagnostics.zip")
+            .save_file()
+        else {
+            return;
+        };
+        let state = self.state.clone();
+        self.runtime.spawn(async move {
+            let files = bundle::collect(&state).await;
+            let message = match std::fs::write(&path, bundle::zip(&files)) {
+                Ok(()) => format!("Diagnostics saved to {}", path.display()),
+                Err(e) => format!("Failed to write {}: {}", path.display(), e),
+            };
+            tracing::info!("{}", message);
+            activity::push(&state.activity, message).await;
         });
+        self.status_message = "📦 Collecting diagnostics".to_string();
+    }
+
+    fn show_snapshots(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("📸 Snapshots")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Label:");
+                    ui.text_edit_singleline(&mut self.snapshot_label);
+                    egui::ComboBox::from_id_salt("snapshot_window")
+                        .selected_text(format!("last {} min", self.snapshot_window_s / 60))
+                        .show_ui(ui, |ui| {
+                            for mins in [1, 2, 5, 15] {
+                                ui.selectable_value(
+                                    &mut self.snapshot_window_s,
+                                    mins * 60,
+                                    format!("last {} min", mins),
+                                );
+                            }
+                        });
+                    if ui.button("📸 Capture").clicked() {
+                        self.capture_snapshot();
+                    }
+                    if !self.snapshots.is_empty() && ui.small_button("Clear").clicked() {
+                        self.snapshots.clear();
+                        self.compare = (0, 0);
+                    }
+                });
+                if self.snapshots.is_empty() {
+                    ui.weak("Capture one snapshot, change a setting, let it settle, then capture another.");
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    for (id, index) in [("compare_a", &mut self.compare.0), ("compare_b", &mut self.compare.1)] {
+                        egui::ComboBox::from_id_salt(id)
+                            .selected_text(&self.snapshots[*index].label)
+                            .show_ui(ui, |ui| {
+                                for (i, snap) in self.snapshots.iter().enumerate() {
+                                    ui.selectable_value(index, i, &snap.label);
+                                }
+                            });
+                    }
+                });
+
+                let (a, b) = (&self.snapshots[self.compare.0], &self.snapshots[self.compare.1]);
+                let rows: [(&str, f32, f32, &str); 4] = [
+                    ("Max temp", a.max_temp_c, b.max_temp_c, "°C"),
+                    ("Avg temp", a.avg_temp_c, b.avg_temp_c, "°C"),
+                    ("Avg fan", a.avg_fan_rpm, b.avg_fan_rpm, " RPM"),
+                    ("Avg noise", a.avg_noise_db, b.avg_noise_db, " dB"),
+                ];
+                egui::Grid::new("snapshot_compare")
+                    .num_columns(4)
+                    .spacing([20.0, 2.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        ui.strong(&a.label);
+                        ui.strong(&b.label);
+                        ui.strong("Δ");
+                        ui.end_row();
+                        for (name, va, vb, unit) in rows {
+                            ui.label(name);
+                            ui.label(format!("{:.1}{}", va, unit));
+                            ui.label(format!("{:.1}{}", vb, unit));
+                            ui.label(format!("{:+.1}{}", vb - va, unit));
+                            ui.end_row();
+                        }
+                        ui.label("Avg power");
+                        let watts = |w: Option<f32>| w.map_or("—".to_string(), |w| format!("{:.1} W", w));
+                        ui.label(watts(a.avg_power_w));
+                        ui.label(watts(b.avg_power_w));
+                        match (a.avg_power_w, b.avg_power_w) {
+                            (Some(pa), Some(pb)) => ui.label(format!("{:+.1} W", pb - pa)),
+                            _ => ui.weak("—"),
+                        };
+                        ui.end_row();
+                        ui.label("Samples");
+                        ui.label(format!("{} over {:.0}s", a.samples, a.duration_s));
+                        ui.label(format!("{} over {:.0}s", b.samples, b.duration_s));
+                        ui.end_row();
+                    });
+                ui.weak(format!("{}: {}", a.label, a.settings));
+                ui.weak(format!("{}: {}", b.label, b.settings));
+            });
+    }
 
-        self.status_message = format!("✓ Charge Limit: {}%", limit);
+    fn show_activity(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("📜 Activity")
+            .default_open(false)
+            .show(ui, |ui| {
+                let Ok(events) = self.state.activity.try_read() else {
+                    return;
+                };
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} events", events.len()));
+                    if !events.is_empty() && ui.small_button("Clear").clicked() {
+                        let activity = self.state.activity.clone();
+                        self.runtime.spawn(async move {
+                            activity.write().await.clear();
+                        });
+                    }
+                });
+                egui::ScrollArea::vertical()
+                    .id_salt("activity_scroll")
+                    .max_height(150.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("activity_grid")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                // Newest first
+                                for event in events.iter().rev() {
+                                    ui.weak(activity::age(event.at));
+                                    ui.label(&event.message);
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
     }
 
     fn show_system(&mut self, ui: &mut egui::Ui) {
@@ -1057,6 +5454,10 @@ impl FrameworkControlApp {
                     ui.label(format!("UEFI: {}", v.bios_version));
                     ui.separator();
                     ui.label(format!("EC: {}", v.ec_version));
+                    if let Some(board) = &v.mainboard {
+                        ui.separator();
+                        ui.label(format!("Board: {}", board));
+                    }
                 });
             }
 
@@ -1065,60 +5466,571 @@ impl FrameworkControlApp {
                 .checkbox(&mut self.start_on_boot, "Start on Startup")
                 .changed()
             {
-                set_start_on_boot(self.start_on_boot);
-                // Save config
-                let state = self.state.clone();
-                let start_on_boot = self.start_on_boot;
+                match set_start_on_boot(self.start_on_boot) {
+                    Ok(()) => {
+                        // Save config
+                        let state = self.state.clone();
+                        let start_on_boot = self.start_on_boot;
+                        self.runtime.spawn(async move {
+                            let mut cfg = state.config.write().await;
+                            cfg.start_on_boot = start_on_boot;
+                            config::save(&*cfg);
+                        });
+                    }
+                    Err(e) => {
+                        self.start_on_boot = check_start_on_boot();
+                        self.status_message = format!("❌ {}", e);
+                    }
+                }
+            }
+
+            if self.tray.is_some()
+                && ui
+                    .checkbox(&mut self.minimize_to_tray, "Close to tray")
+                    .changed()
+            {
+                let (state, minimize_to_tray) = (self.state.clone(), self.minimize_to_tray);
+                self.runtime.spawn(async move {
+                    let mut cfg = state.config.write().await;
+                    cfg.minimize_to_tray = minimize_to_tray;
+                    config::save(&cfg);
+                });
+            }
+
+            if ui
+                .checkbox(&mut self.advanced_tools, "Advanced tools")
+                .on_hover_text("Adds a read-only EC memory viewer to Advanced / BIOS")
+                .changed()
+            {
+                let (state, advanced_tools) = (self.state.clone(), self.advanced_tools);
+                self.runtime.spawn(async move {
+                    let mut cfg = state.config.write().await;
+                    cfg.ui.advanced_tools = advanced_tools;
+                    config::save(&cfg);
+                });
+            }
+            if ui.checkbox(&mut self.gauge_view, "Gauge view").changed() {
+                let (state, gauge_view) = (self.state.clone(), self.gauge_view);
                 self.runtime.spawn(async move {
                     let mut cfg = state.config.write().await;
-                    cfg.start_on_boot = start_on_boot;
-                    config::save(&*cfg);
+                    cfg.ui.gauge_view = gauge_view;
+                    config::save(&cfg);
+                });
+            }
+            let restore = self
+                .state
+                .config
+                .try_read()
+                .ok()
+                .map(|cfg| cfg.restore_auto_on_exit);
+            if let Some(mut restore) = restore {
+                let changed = ui
+                    .checkbox(&mut restore, "Return fans to auto on exit")
+                    .on_hover_text("Off keeps the last manual duty or curve point after closing")
+                    .changed();
+                if changed {
+                    let state = self.state.clone();
+                    self.runtime.spawn(async move {
+                        let mut cfg = state.config.write().await;
+                        cfg.restore_auto_on_exit = restore;
+                        config::save(&cfg);
+                    });
+                }
+            }
+            let poll_ms = self
+                .state
+                .config
+                .try_read()
+                .ok()
+                .map(|cfg| cfg.poll_interval().as_millis() as u64);
+            if let Some(mut poll_ms) = poll_ms {
+                ui.horizontal(|ui| {
+                    ui.label("Refresh every");
+                    let response = ui
+                        .add(
+                            egui::DragValue::new(&mut poll_ms)
+                                .range(MIN_POLL_INTERVAL_MS..=10_000)
+                                .speed(50)
+                                .suffix("ms"),
+                        )
+                        .on_hover_text(
+                            "How often temperatures, fans and battery are read; slower saves power",
+                        );
+                    // Written to disk once the drag or edit ends rather than on every step
+                    let finished = response.drag_stopped()
+                        || response.lost_focus()
+                        || (response.changed() && !response.dragged() && !response.has_focus());
+                    if response.changed() || finished {
+                        let state = self.state.clone();
+                        self.runtime.spawn(async move {
+                            let mut cfg = state.config.write().await;
+                            cfg.poll_interval_ms = Some(poll_ms);
+                            if finished {
+                                config::save(&cfg);
+                            }
+                        });
+                    }
+                });
+            }
+            let alerts = self
+                .state
+                .config
+                .try_read()
+                .ok()
+                .map(|cfg| cfg.alerts.clone());
+            if let Some(mut alerts) = alerts {
+                ui.horizontal(|ui| {
+                    let mut changed = ui
+                        .checkbox(&mut alerts.enabled, "Overheat alert above")
+                        .changed();
+                    ui.add_enabled_ui(alerts.enabled, |ui| {
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut alerts.temp_c)
+                                    .range(60.0..=110.0)
+                                    .suffix("°C"),
+                            )
+                            .changed();
+                        ui.label("for");
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut alerts.duration_s)
+                                    .range(5..=600)
+                                    .suffix("s"),
+                            )
+                            .changed();
+                    });
+                    if changed {
+                        let state = self.state.clone();
+                        self.runtime.spawn(async move {
+                            let mut cfg = state.config.write().await;
+                            cfg.alerts = alerts;
+                            config::save(&cfg);
+                        });
+                    }
                 });
             }
+            if let Ok(cfg) = self.state.config.try_read() {
+                let describe = |spec: &str| {
+                    if spec.trim().is_empty() {
+                        "off".to_string()
+                    } else {
+                        spec.to_string()
+                    }
+                };
+                ui.weak(format!(
+                    "Hotkeys: fans 100% {} · restore {}",
+                    describe(&cfg.hotkeys.panic_fans),
+                    describe(&cfg.hotkeys.restore_fans)
+                ));
+            }
+            let logs = logging::current_dir();
+            if ui
+                .button("📂 Open Logs")
+                .on_hover_text(logs.display().to_string())
+                .clicked()
+            {
+                let opener = if cfg!(windows) {
+                    "explorer"
+                } else {
+                    "xdg-open"
+                };
+                if let Err(e) = hidden_command(opener).arg(&logs).spawn() {
+                    self.status_message = format!("❌ Can't open {}: {}", logs.display(), e);
+                }
+            }
         });
     }
 }
 
+fn temp_color(temp: f32, thresholds: TempThresholds) -> egui::Color32 {
+    if temp > thresholds.hot_c {
+        egui::Color32::RED
+    } else if temp > thresholds.warn_c {
+        egui::Color32::from_rgb(255, 165, 0)
+    } else {
+        egui::Color32::from_rgb(0, 200, 0)
+    }
+}
+
+fn fan_color(rpm: f32) -> egui::Color32 {
+    if rpm > 4000.0 {
+        egui::Color32::from_rgb(255, 165, 0)
+    } else {
+        egui::Color32::from_rgb(100, 200, 255)
+    }
+}
+
+/// Capacity lost: up to 20% is normal ageing, past 40% the pack is due for replacement
+fn wear_color(wear_pct: f32) -> egui::Color32 {
+    if wear_pct > 40.0 {
+        egui::Color32::RED
+    } else if wear_pct > 20.0 {
+        egui::Color32::from_rgb(255, 165, 0)
+    } else {
+        egui::Color32::from_rgb(0, 200, 0)
+    }
+}
+
+fn battery_color(pct: f32) -> egui::Color32 {
+    if pct < 20.0 {
+        egui::Color32::RED
+    } else if pct < 50.0 {
+        egui::Color32::from_rgb(255, 165, 0)
+    } else {
+        egui::Color32::from_rgb(0, 200, 0)
+    }
+}
+
+/// − / + buttons around a sensor's offset; a click is returned through `nudge`
+fn offset_nudger(
+    ui: &mut egui::Ui,
+    name: &str,
+    offset: Option<f32>,
+    nudge: &mut Option<(String, f32)>,
+) {
+    ui.horizontal(|ui| {
+        if ui.small_button("−").clicked() {
+            *nudge = Some((name.to_string(), -0.5));
+        }
+        ui.label(format!("{:+.1}", offset.unwrap_or(0.0)));
+        if ui.small_button("+").clicked() {
+            *nudge = Some((name.to_string(), 0.5));
+        }
+    });
+}
+
+/// Semi-circular dial. `zones` are (upper bound, color) bands in ascending order: the track
+/// shows each band faintly and the filled arc takes the color of the band holding `value`.
+fn gauge(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: f32,
+    range: std::ops::RangeInclusive<f32>,
+    zones: &[(f32, egui::Color32)],
+    text: String,
+) {
+    const SIZE: egui::Vec2 = egui::vec2(96.0, 72.0);
+    const WIDTH: f32 = 8.0;
+    let (rect, _) = ui.allocate_exact_size(SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let center = egui::pos2(rect.center().x, rect.top() + 44.0);
+    let radius = 36.0;
+
+    let (min, max) = (*range.start(), *range.end());
+    let frac = |v: f32| ((v - min) / (max - min)).clamp(0.0, 1.0);
+    // Fraction 0 is the left end, 1 the right; screen y grows downwards so the arc bulges up
+    let arc = |from: f32, to: f32| -> Vec<egui::Pos2> {
+        let steps = ((to - from) * 32.0).ceil().max(1.0) as usize;
+        (0..=steps)
+            .map(|i| {
+                let t = from + (to - from) * i as f32 / steps as f32;
+                let angle = std::f32::consts::PI * (1.0 + t);
+                center + radius * egui::vec2(angle.cos(), angle.sin())
+            })
+            .collect()
+    };
+
+    let mut lower = min;
+    for &(upper, color) in zones {
+        let (a, b) = (frac(lower), frac(upper));
+        if b > a {
+            painter.add(egui::Shape::line(
+                arc(a, b),
+                egui::Stroke::new(WIDTH, color.gamma_multiply(0.25)),
+            ));
+        }
+        lower = upper;
+    }
+    let color = zones
+        .iter()
+        .find(|(upper, _)| value <= *upper)
+        .map_or(egui::Color32::GRAY, |(_, c)| *c);
+    if frac(value) > 0.0 {
+        painter.add(egui::Shape::line(
+            arc(0.0, frac(value)),
+            egui::Stroke::new(WIDTH, color),
+        ));
+    }
+
+    painter.text(
+        center - egui::vec2(0.0, 8.0),
+        egui::Align2::CENTER_CENTER,
+        text,
+        egui::FontId::proportional(14.0),
+        color,
+    );
+    painter.text(
+        egui::pos2(center.x, rect.bottom() - 10.0),
+        egui::Align2::CENTER_CENTER,
+        label,
+        egui::FontId::proportional(12.0),
+        ui.visuals().text_color(),
+    );
+}
+
+/// A named line of (seconds ago, value) points
+type ChartSeries = (String, Vec<(f32, f32)>);
+
+const CHART_COLORS: [egui::Color32; 6] = [
+    egui::Color32::from_rgb(255, 106, 0),
+    egui::Color32::from_rgb(79, 195, 247),
+    egui::Color32::from_rgb(129, 199, 132),
+    egui::Color32::from_rgb(229, 115, 115),
+    egui::Color32::from_rgb(186, 104, 200),
+    egui::Color32::from_rgb(255, 213, 79),
+];
+
+/// Time-series chart with "now" on the right and `window_s` ago on the left.
+/// The Y axis fits whatever is visible, with a little headroom.
+fn line_chart(ui: &mut egui::Ui, series: &[ChartSeries], window_s: f32, unit: &str) {
+    const HEIGHT: f32 = 110.0;
+    const GRID_LINES: usize = 4;
+    let values = series
+        .iter()
+        .flat_map(|(_, points)| points.iter().map(|p| p.1));
+    let (lo, hi) = values.fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    if lo > hi {
+        ui.weak("No data in this window");
+        return;
+    }
+    // Keep a flat line off the edges
+    let pad = ((hi - lo) * 0.1).max(1.0);
+    let (lo, hi) = (lo - pad, hi + pad);
+
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), HEIGHT),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect_filled(rect, 4.0, visuals.extreme_bg_color);
+    let to_screen = |ago: f32, v: f32| {
+        egui::pos2(
+            rect.right() - ago / window_s * rect.width(),
+            rect.bottom() - (v - lo) / (hi - lo) * rect.height(),
+        )
+    };
+
+    let font = egui::FontId::proportional(10.0);
+    // Inner lines only, leaving the bottom corners for the time labels
+    for i in 1..GRID_LINES {
+        let v = lo + (hi - lo) * i as f32 / GRID_LINES as f32;
+        let y = to_screen(0.0, v).y;
+        painter.hline(
+            rect.x_range(),
+            y,
+            egui::Stroke::new(1.0, visuals.faint_bg_color),
+        );
+        painter.text(
+            egui::pos2(rect.left() + 2.0, y),
+            egui::Align2::LEFT_BOTTOM,
+            format!("{:.0}{}", v, unit),
+            font.clone(),
+            visuals.weak_text_color(),
+        );
+    }
+    painter.text(
+        rect.right_bottom() - egui::vec2(2.0, 0.0),
+        egui::Align2::RIGHT_BOTTOM,
+        "now",
+        font.clone(),
+        visuals.weak_text_color(),
+    );
+    painter.text(
+        egui::pos2(rect.left() + 2.0, rect.bottom()),
+        egui::Align2::LEFT_BOTTOM,
+        format!("-{:.0} min", window_s / 60.0),
+        font,
+        visuals.weak_text_color(),
+    );
+
+    for (i, (_, points)) in series.iter().enumerate() {
+        let line: Vec<egui::Pos2> = points.iter().map(|&(ago, v)| to_screen(ago, v)).collect();
+        painter.add(egui::Shape::line(
+            line,
+            egui::Stroke::new(1.5, CHART_COLORS[i % CHART_COLORS.len()]),
+        ));
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        for (i, (name, points)) in series.iter().enumerate() {
+            let latest = points.last().map(|p| p.1).unwrap_or_default();
+            ui.colored_label(
+                CHART_COLORS[i % CHART_COLORS.len()],
+                format!("● {} {:.0}{}", name, latest, unit),
+            );
+        }
+    });
+}
+
+fn describe_fan(fan: &FanControlConfig) -> String {
+    match fan.mode.clone().unwrap_or_default() {
+        FanControlMode::Disabled => "Auto".to_string(),
+        FanControlMode::Manual => format!(
+            "Manual {}%",
+            fan.manual.as_ref().map(|m| m.duty_pct).unwrap_or(50)
+        ),
+        FanControlMode::Curve => format!(
+            "Curve ({} points)",
+            fan.curve.as_ref().map(|c| c.points.len()).unwrap_or(0)
+        ),
+    }
+}
+
+/// "15W TDP", or "15W TDP (fast 25W, slow 20W)" with separate burst limits
+fn describe_power_limits(stapm: u32, fast: u32, slow: u32) -> String {
+    if (fast, slow) == (stapm, stapm) {
+        format!("{}W TDP", stapm)
+    } else {
+        format!("{}W TDP (fast {}W, slow {}W)", stapm, fast, slow)
+    }
+}
+
+fn describe_power(power: &PowerConfig) -> String {
+    let describe = |p: Option<&PowerProfile>| {
+        let tdp = p
+            .and_then(|p| p.power_limits_w())
+            .map(|(stapm, fast, slow)| {
+                if (fast, slow) == (stapm, stapm) {
+                    format!("{}W", stapm)
+                } else {
+                    format!("{}/{}/{}W", stapm, fast, slow)
+                }
+            });
+        let thermal = p
+            .and_then(|p| p.thermal_limit_c.as_ref())
+            .filter(|s| s.enabled)
+            .map(|s| format!("{}°C", s.value));
+        match (tdp, thermal) {
+            (None, None) => "default".to_string(),
+            (tdp, thermal) => [tdp, thermal].into_iter().flatten().collect::<Vec<_>>().join("/"),
+        }
+    };
+    format!(
+        "AC {} · Battery {}",
+        describe(power.ac.as_ref()),
+        describe(power.battery.as_ref())
+    )
+}
+
+fn describe_battery(battery: &BatteryConfig) -> String {
+    match battery.charge_window() {
+        Some((min, max)) => format!("Charge limit {}–{}%", min, max),
+        None => "No charge limit".to_string(),
+    }
+}
+
+// Name of the logon task, and of the Run-key value older versions registered
+const STARTUP_TASK: &str = "FrameworkControl";
+const RUN_KEY: &str = "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+
+/// A console tool run without flashing a console window
+fn hidden_command(program: impl AsRef<std::ffi::OsStr>) -> std::process::Command {
+    #[allow(unused_mut)]
+    let mut command = std::process::Command::new(program);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
 fn check_start_on_boot() -> bool {
-    std::process::Command::new("reg")
-        .args(&[
-            "query",
-            "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Run",
-            "/v",
-            "FrameworkControl",
-        ])
+    hidden_command("schtasks")
+        .args(["/Query", "/TN", STARTUP_TASK])
         .output()
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
 
-fn set_start_on_boot(enable: bool) {
-    let exe_path = std::env::current_exe().unwrap_or_default();
-    let exe_str = exe_path.to_string_lossy();
-
-    if enable {
-        let _ = std::process::Command::new("reg")
-            .args(&[
-                "add",
-                "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Run",
-                "/v",
-                "FrameworkControl",
-                "/t",
-                "REG_SZ",
-                "/d",
-                &format!("\"{}\" --minimized", exe_str),
-                "/f",
-            ])
+/// Start at logon via a scheduled task. The app needs administrator rights and Windows
+/// skips elevated programs listed in the Run key, so the task runs with highest privileges;
+/// it also drops the task's default battery conditions and time limit, which would stop a
+/// laptop utility on battery or after three days.
+fn set_start_on_boot(enable: bool) -> Result<(), String> {
+    // Entries from older versions never started (elevation) but still show in Task Manager
+    let _ = hidden_command("reg")
+        .args(["delete", RUN_KEY, "/v", STARTUP_TASK, "/f"])
+        .output();
+
+    let output = if enable {
+        let exe = std::env::current_exe().map_err(|e| format!("Can't locate the app: {}", e))?;
+        let user = format!(
+            "{}\\{}",
+            std::env::var("USERDOMAIN").unwrap_or_default(),
+            std::env::var("USERNAME").unwrap_or_default()
+        );
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        };
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-16"?>
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <Triggers>
+    <LogonTrigger><Enabled>true</Enabled><UserId>{user}</UserId></LogonTrigger>
+  </Triggers>
+  <Principals>
+    <Principal id="Author">
+      <UserId>{user}</UserId>
+      <LogonType>InteractiveToken</LogonType>
+      <RunLevel>HighestAvailable</RunLevel>
+    </Principal>
+  </Principals>
+  <Settings>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>
+    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
+  </Settings>
+  <Actions Context="Author">
+    <Exec><Command>{exe}</Command><Arguments>--minimized</Arguments></Exec>
+  </Actions>
+</Task>
+"#,
+            user = escape(&user),
+            exe = escape(&exe.to_string_lossy()),
+        );
+        // schtasks reads task XML as UTF-16 with a byte order mark
+        let bytes: Vec<u8> = std::iter::once(0xFEFF)
+            .chain(xml.encode_utf16())
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let path = std::env::temp_dir().join("framework-control-startup.xml");
+        std::fs::write(&path, bytes)
+            .map_err(|e| format!("Can't write {}: {}", path.display(), e))?;
+        let output = hidden_command("schtasks")
+            .args(["/Create", "/TN", STARTUP_TASK, "/F", "/XML"])
+            .arg(&path)
             .output();
+        let _ = std::fs::remove_file(&path);
+        output
+    } else if check_start_on_boot() {
+        hidden_command("schtasks")
+            .args(["/Delete", "/TN", STARTUP_TASK, "/F"])
+            .output()
     } else {
-        let _ = std::process::Command::new("reg")
-            .args(&[
-                "delete",
-                "HKCU\\Software\\Microsoft\\Windows\\CurrentVersion\\Run",
-                "/v",
-                "FrameworkControl",
-                "/f",
-            ])
-            .output();
+        return Ok(());
+    };
+
+    let output = output.map_err(|e| format!("Can't run schtasks: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Startup task not {}: {}",
+            if enable { "created" } else { "removed" },
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    // Only report success once the task is really there (or really gone)
+    if check_start_on_boot() != enable {
+        return Err(format!(
+            "Startup task still {} after schtasks reported success",
+            if enable { "missing" } else { "present" }
+        ));
     }
+    Ok(())
 }
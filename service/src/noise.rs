@@ -0,0 +1,58 @@
+// Rough fan loudness estimate derived from RPM (approximation, not a measurement)
+
+use crate::types::FanCalibration;
+
+/// Default RPM -> dB(A)-ish mapping for the Framework blower fans
+pub fn default_map() -> Vec<[u32; 2]> {
+    vec![
+        [0, 0],
+        [1500, 22],
+        [3000, 32],
+        [4500, 41],
+        [6000, 48],
+        [7500, 54],
+    ]
+}
+
+/// Loudness shown as "full scale" by the gauges
+pub const MAX_DB: f32 = 60.0;
+
+/// Linear lookup in an `[x, y]` table; `None` when it's empty
+fn lerp_points(points: &[[u32; 2]], x: f32) -> Option<f32> {
+    if points.is_empty() {
+        return None;
+    }
+    let points: Vec<(f32, f32)> = points.iter().map(|p| (p[0] as f32, p[1] as f32)).collect();
+    crate::curve::lerp_table(&points, x)
+}
+
+/// Resolve the mapping to use: an explicit config map wins, otherwise the default map is
+/// rescaled so its loudest point sits at the calibrated full-speed RPM.
+pub fn resolve_map(
+    configured: Option<&Vec<[u32; 2]>>,
+    calibration: Option<&FanCalibration>,
+) -> Vec<[u32; 2]> {
+    if let Some(map) = configured.filter(|m| !m.is_empty()) {
+        let mut map = map.clone();
+        map.sort_by_key(|p| p[0]);
+        return map;
+    }
+    let mut map = default_map();
+    let max_rpm = calibration.and_then(|c| c.points.iter().map(|p| p[1]).max());
+    if let Some(max_rpm) = max_rpm.filter(|r| *r > 0) {
+        let default_max = map.last().map(|p| p[0]).unwrap_or(1).max(1);
+        for p in &mut map {
+            p[0] = (p[0] as u64 * max_rpm as u64 / default_max as u64) as u32;
+        }
+    }
+    map
+}
+
+pub fn estimate_db(rpm: f32, map: &[[u32; 2]]) -> f32 {
+    lerp_points(map, rpm.max(0.0)).unwrap_or(0.0)
+}
+
+/// Expected RPM for a commanded duty, from the calibration table
+pub fn rpm_for_duty(duty_pct: f32, calibration: &FanCalibration) -> Option<f32> {
+    lerp_points(&calibration.points, duty_pct)
+}
@@ -0,0 +1,66 @@
+// User-facing notifications: an in-app toast plus a desktop notification
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// How long a toast stays on screen
+pub const TOAST_TTL: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub title: String,
+    pub body: String,
+    pub at: Instant,
+}
+
+pub type Toasts = Arc<RwLock<Vec<Toast>>>;
+
+/// Queue a toast for the GUI and raise an OS notification (best effort)
+pub async fn push(toasts: &Toasts, title: &str, body: &str) {
+    tracing::info!("Notification: {} - {}", title, body);
+    toasts.write().await.push(Toast {
+        title: title.to_string(),
+        body: body.to_string(),
+        at: Instant::now(),
+    });
+
+    let (title, body) = (title.to_string(), body.to_string());
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = notify_rust::Notification::new()
+            .appname("Framework Control")
+            .summary(&title)
+            .body(&body)
+            .show()
+        {
+            tracing::warn!("Desktop notification failed: {}", e);
+        }
+    });
+}
+
+/// Fires once when the battery reaches the charge limit on AC, then re-arms only after
+/// the level has dropped below the limit again.
+#[derive(Debug, Clone)]
+pub struct ChargeLimitNotifier {
+    armed: bool,
+}
+
+impl Default for ChargeLimitNotifier {
+    fn default() -> Self {
+        Self { armed: true }
+    }
+}
+
+impl ChargeLimitNotifier {
+    pub fn update(&mut self, on_ac: bool, percent: f32, limit_pct: u8) -> bool {
+        let limit = limit_pct as f32;
+        if percent < limit - 1.0 {
+            self.armed = true;
+            return false;
+        }
+        if on_ac && self.armed && percent >= limit {
+            self.armed = false;
+            return true;
+        }
+        false
+    }
+}
@@ -0,0 +1,196 @@
+// Where TDP and thermal limits are sent, and the live values read back. No Framework EC
+// host command sets the TDP or Tctl, so on AMD boards ryzenadj programs the SMU directly;
+// elsewhere there is no way in.
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Backend {
+    Ryzenadj(PathBuf),
+    Unavailable,
+}
+
+impl Backend {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ryzenadj(_) => "ryzenadj",
+            Self::Unavailable => "none",
+        }
+    }
+}
+
+/// Look for ryzenadj on AMD. Not cached, so a ryzenadj dropped in next to the app is
+/// picked up without a restart.
+pub fn backend() -> Backend {
+    match ryzenadj_path() {
+        Some(path) if crate::cpu::vendor() == crate::cpu::Vendor::Amd => Backend::Ryzenadj(path),
+        _ => Backend::Unavailable,
+    }
+}
+
+/// ryzenadj next to this executable, else the first one on `PATH`
+fn ryzenadj_path() -> Option<PathBuf> {
+    let name = if cfg!(windows) {
+        "ryzenadj.exe"
+    } else {
+        "ryzenadj"
+    };
+    let beside_exe = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(name)));
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let on_path = std::env::split_paths(&path).map(|dir| dir.join(name));
+    beside_exe
+        .into_iter()
+        .chain(on_path)
+        .find(|path| path.is_file())
+}
+
+/// Sustained (STAPM), short-burst (fast) and medium-term (slow) power limits in watts
+pub fn set_power_limits(stapm: u32, fast: u32, slow: u32) -> Result<(), String> {
+    match backend() {
+        Backend::Ryzenadj(path) => {
+            run_ryzenadj(
+                &path,
+                &[
+                    format!("--stapm-limit={}", stapm * 1000),
+                    format!("--fast-limit={}", fast * 1000),
+                    format!("--slow-limit={}", slow * 1000),
+                ],
+            )?;
+            crate::ec::remember_tdp_watts(stapm);
+            Ok(())
+        }
+        Backend::Unavailable => Err(unavailable()),
+    }
+}
+
+pub fn set_thermal_limit_c(limit: u32) -> Result<(), String> {
+    match backend() {
+        Backend::Ryzenadj(path) => {
+            run_ryzenadj(&path, &[format!("--tctl-temp={}", limit)])?;
+            Ok(())
+        }
+        Backend::Unavailable => Err(unavailable()),
+    }
+}
+
+/// Live values next to the limits they run against, from `ryzenadj --info`. Each is
+/// `None` when the row is missing or reads "nan" on this processor.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RyzenAdjInfo {
+    pub stapm_limit_w: Option<f32>,
+    pub stapm_w: Option<f32>,
+    pub fast_limit_w: Option<f32>,
+    pub fast_w: Option<f32>,
+    pub slow_limit_w: Option<f32>,
+    pub slow_w: Option<f32>,
+    pub thermal_limit_c: Option<f32>,
+    pub core_temp_c: Option<f32>,
+}
+
+pub fn read_info() -> Result<RyzenAdjInfo, String> {
+    match backend() {
+        Backend::Ryzenadj(path) => Ok(parse_info(&run_ryzenadj(&path, &["--info".to_string()])?)),
+        Backend::Unavailable => Err(unavailable()),
+    }
+}
+
+/// Pick the known rows out of the `| Name | Value | Parameter |` table
+pub fn parse_info(text: &str) -> RyzenAdjInfo {
+    let mut info = RyzenAdjInfo::default();
+    for line in text.lines() {
+        let cells: Vec<&str> = line.split('|').map(str::trim).collect();
+        let [_, name, value, ..] = cells.as_slice() else {
+            continue;
+        };
+        let Some(value) = value.parse::<f32>().ok().filter(|v| v.is_finite()) else {
+            continue;
+        };
+        let field = match name.to_ascii_uppercase().as_str() {
+            "STAPM LIMIT" => &mut info.stapm_limit_w,
+            "STAPM VALUE" => &mut info.stapm_w,
+            "PPT LIMIT FAST" => &mut info.fast_limit_w,
+            "PPT VALUE FAST" => &mut info.fast_w,
+            "PPT LIMIT SLOW" => &mut info.slow_limit_w,
+            "PPT VALUE SLOW" => &mut info.slow_w,
+            "THM LIMIT CORE" => &mut info.thermal_limit_c,
+            "THM VALUE CORE" => &mut info.core_temp_c,
+            _ => continue,
+        };
+        *field = Some(value);
+    }
+    info
+}
+
+fn unavailable() -> String {
+    match crate::cpu::vendor() {
+        crate::cpu::Vendor::Amd => "ryzenadj wasn't found next to the app or on PATH",
+        _ => "Power limits need ryzenadj, which only supports AMD processors",
+    }
+    .to_string()
+}
+
+/// Run ryzenadj, returning what it printed
+fn run_ryzenadj(path: &std::path::Path, args: &[String]) -> Result<String, String> {
+    let output = crate::hidden_command(path)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Can't run {}: {}", path.display(), e))?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+    // ryzenadj reports what it couldn't set on either stream
+    let text = [output.stderr, output.stdout]
+        .iter()
+        .map(|bytes| String::from_utf8_lossy(bytes).trim().to_string())
+        .find(|text| !text.is_empty())
+        .unwrap_or_else(|| output.status.to_string());
+    Err(format!("ryzenadj failed: {}", text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INFO: &str = "\
+CPU Family: Rembrandt
+SMU BIOS Interface Version: 18
+Version: v0.13.0
+PM Table Version: 450005
+|        Name         |   Value   |     Parameter      |
+|---------------------|-----------|--------------------|
+| STAPM LIMIT         |    28.000 | stapm-limit        |
+| STAPM VALUE         |     5.561 |                    |
+| PPT LIMIT FAST      |    35.000 | fast-limit         |
+| PPT VALUE FAST      |     8.276 |                    |
+| PPT LIMIT SLOW      |    30.000 | slow-limit         |
+| PPT VALUE SLOW      |     5.772 |                    |
+| StapmTimeConst      |       nan | stapm-time         |
+| THM LIMIT CORE      |   100.000 | tctl-temp          |
+| THM VALUE CORE      |    52.180 |                    |
+";
+
+    #[test]
+    fn info_table_is_parsed() {
+        let info = parse_info(INFO);
+        assert_eq!(info.stapm_limit_w, Some(28.0));
+        assert_eq!(info.stapm_w, Some(5.561));
+        assert_eq!(info.fast_limit_w, Some(35.0));
+        assert_eq!(info.fast_w, Some(8.276));
+        assert_eq!(info.slow_limit_w, Some(30.0));
+        assert_eq!(info.slow_w, Some(5.772));
+        assert_eq!(info.thermal_limit_c, Some(100.0));
+        assert_eq!(info.core_temp_c, Some(52.18));
+    }
+
+    #[test]
+    fn missing_and_nan_rows_stay_unknown() {
+        let info = parse_info(
+            "| STAPM LIMIT | 15.000 | stapm-limit |\n| STAPM VALUE | nan | |\nError: no table\n",
+        );
+        assert_eq!(info.stapm_limit_w, Some(15.0));
+        assert_eq!(info.stapm_w, None);
+        assert_eq!(info.core_temp_c, None);
+        assert_eq!(parse_info(""), RyzenAdjInfo::default());
+    }
+}
@@ -0,0 +1,246 @@
+// Shareable presets: a fan curve and profiles in a standalone JSON file, so tuned settings
+// can be passed around without the rest of the config
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    normalize_points, BatteryConfig, CurveConfig, FanControlConfig, PowerProfile, Profile,
+    SettingU32, MAX_CURVE_TEMP_C,
+};
+
+/// Bumped whenever a change would make older builds misread a preset
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Thermal limits (°C) the power panel offers
+const THERMAL_LIMIT_C: std::ops::RangeInclusive<u32> = 60..=100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    /// Absent in files that aren't presets at all
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve: Option<CurveConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<Profile>,
+}
+
+pub fn export(curve: Option<CurveConfig>, profiles: Vec<Profile>) -> String {
+    let preset = Preset {
+        schema_version: SCHEMA_VERSION,
+        curve,
+        profiles,
+    };
+    serde_json::to_string_pretty(&preset).unwrap_or_default()
+}
+
+/// Parse and check a preset. Out-of-range points and percentages are rejected rather than
+/// clamped, since a silently altered curve isn't the one that was shared; valid points are
+/// sorted. Power limits are fitted to this CPU, and the fan watchdog is never imported.
+pub fn import(json: &str) -> Result<Preset, String> {
+    let mut preset: Preset =
+        serde_json::from_str(json).map_err(|e| format!("Invalid preset: {}", e))?;
+    match preset.schema_version {
+        0 => return Err("Not a Framework Control preset (no schema_version)".to_string()),
+        v if v > SCHEMA_VERSION => {
+            return Err(format!(
+                "Preset schema {} is newer than this app supports ({}); update the app",
+                v, SCHEMA_VERSION
+            ))
+        }
+        _ => {}
+    }
+    if preset.curve.is_none() && preset.profiles.is_empty() {
+        return Err("Preset holds no curve and no profiles".to_string());
+    }
+
+    if let Some(curve) = &mut preset.curve {
+        check_curve("Fan curve", curve)?;
+    }
+    for profile in &mut preset.profiles {
+        if profile.name.trim().is_empty() {
+            return Err("A profile has no name".to_string());
+        }
+        let what = format!("Profile '{}'", profile.name);
+        if let Some(fan) = &mut profile.fan {
+            check_fan(&what, fan)?;
+        }
+        if let Some(power) = &mut profile.power {
+            for limits in [&mut power.ac, &mut power.battery].into_iter().flatten() {
+                check_power(&what, limits)?;
+            }
+        }
+        if let Some(battery) = &profile.battery {
+            check_battery(&what, battery)?;
+        }
+    }
+    Ok(preset)
+}
+
+fn check_curve(what: &str, curve: &mut CurveConfig) -> Result<(), String> {
+    check_points(what, &mut curve.points)?;
+    // Timing and smoothing are only brought into range
+    curve.validate_and_normalize();
+    Ok(())
+}
+
+fn check_fan(what: &str, fan: &mut FanControlConfig) -> Result<(), String> {
+    for (name, curve) in [
+        ("curve", &mut fan.curve),
+        ("AC curve", &mut fan.curve_ac),
+        ("battery curve", &mut fan.curve_battery),
+    ] {
+        if let Some(curve) = curve {
+            check_curve(&format!("{} {}", what, name), curve)?;
+        }
+    }
+    for fc in &mut fan.fan_curves {
+        check_points(&format!("{} fan {} curve", what, fc.fan), &mut fc.points)?;
+    }
+    for (name, pct) in [
+        ("manual duty", fan.manual.as_ref().map(|m| m.duty_pct)),
+        ("duty cap", fan.max_duty_pct),
+        ("fallback duty", fan.fallback_duty_pct),
+        (
+            "spin-up kick duty",
+            fan.spin_up_kick.as_ref().map(|k| k.duty_pct),
+        ),
+        ("RPM alert tolerance", fan.rpm_alert_tolerance_pct),
+    ] {
+        if let Some(pct) = pct.filter(|p| *p > 100) {
+            return Err(format!("{} {} {}% is above 100%", what, name, pct));
+        }
+    }
+    // A shared file must not be able to switch off or stretch the fan safety net
+    fan.watchdog_secs = None;
+    Ok(())
+}
+
+fn check_power(what: &str, limits: &mut PowerProfile) -> Result<(), String> {
+    // Made on another CPU, the watts may not fit this one
+    let range = crate::cpu::tdp_range_watts();
+    for setting in [
+        &mut limits.tdp_watts,
+        &mut limits.fast_limit_w,
+        &mut limits.slow_limit_w,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        setting.value = crate::cpu::clamp_tdp(setting.value, &range).0;
+    }
+    if let Some(SettingU32 { value, .. }) = limits.thermal_limit_c {
+        if !THERMAL_LIMIT_C.contains(&value) {
+            return Err(format!(
+                "{} thermal limit {}°C is outside {}-{}°C",
+                what,
+                value,
+                THERMAL_LIMIT_C.start(),
+                THERMAL_LIMIT_C.end()
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_battery(what: &str, battery: &BatteryConfig) -> Result<(), String> {
+    if let Some(max) = &battery.charge_limit_max_pct {
+        if !(50..=100).contains(&max.value) {
+            return Err(format!(
+                "{} charge limit {}% is outside 50-100%",
+                what, max.value
+            ));
+        }
+    }
+    if let Some(min) = battery.charge_limit_min_pct.filter(|m| *m > 100) {
+        return Err(format!(
+            "{} charge resume point {}% is above 100%",
+            what, min
+        ));
+    }
+    if let Some(rate) = &battery.charge_rate_c {
+        if !(0.0..=1.0).contains(&rate.value) {
+            return Err(format!(
+                "{} charge rate {}C is outside 0-1C",
+                what, rate.value
+            ));
+        }
+    }
+    if let Some(threshold) = battery.charge_rate_soc_threshold_pct.filter(|t| *t > 100) {
+        return Err(format!(
+            "{} charge rate threshold {}% is above 100%",
+            what, threshold
+        ));
+    }
+    Ok(())
+}
+
+fn check_points(what: &str, points: &mut Vec<[u32; 2]>) -> Result<(), String> {
+    if let Some(p) = points
+        .iter()
+        .find(|p| p[0] > MAX_CURVE_TEMP_C || p[1] > 100)
+    {
+        return Err(format!(
+            "{}: point {}°C → {}% is out of range (0–{}°C, 0–100%)",
+            what, p[0], p[1], MAX_CURVE_TEMP_C
+        ));
+    }
+    let mut temps: Vec<u32> = points.iter().map(|p| p[0]).collect();
+    temps.sort_unstable();
+    temps.dedup();
+    if temps.len() < 2 {
+        return Err(format!(
+            "{}: needs at least two points at different temperatures",
+            what
+        ));
+    }
+    normalize_points(points);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exported_preset_imports_unchanged() {
+        let curve = CurveConfig {
+            points: vec![[40, 20], [60, 50], [85, 100]],
+            ..Default::default()
+        };
+        let profile = Profile {
+            name: "Quiet".to_string(),
+            ..Default::default()
+        };
+        let preset = import(&export(Some(curve), vec![profile])).unwrap();
+        assert_eq!(preset.schema_version, SCHEMA_VERSION);
+        assert_eq!(
+            preset.curve.unwrap().points,
+            vec![[40, 20], [60, 50], [85, 100]]
+        );
+        assert_eq!(preset.profiles[0].name, "Quiet");
+    }
+
+    #[test]
+    fn rejects_bad_presets() {
+        let out_of_range = r#"{"schema_version":1,"curve":{"points":[[40,20],[60,120]]}}"#;
+        assert!(import(out_of_range).unwrap_err().contains("60°C → 120%"));
+        let too_hot = r#"{"schema_version":1,"curve":{"points":[[40,20],[150,100]]}}"#;
+        assert!(import(too_hot).is_err());
+        let newer = r#"{"schema_version":99,"curve":{"points":[[40,20],[60,50]]}}"#;
+        assert!(import(newer).unwrap_err().contains("newer"));
+        assert!(import(r#"{"curve":{"points":[[40,20],[60,50]]}}"#).is_err());
+        assert!(import(r#"{"schema_version":1,"curve":{"points":[[40,20]]}}"#).is_err());
+        let loud = r#"{"schema_version":1,"profiles":[{"name":"Loud","fan":{"manual":{"duty_pct":150}}}]}"#;
+        assert!(import(loud).unwrap_err().contains("150%"));
+        let overcharge = r#"{"schema_version":1,"profiles":[{"name":"Full","battery":{"charge_limit_max_pct":{"enabled":true,"value":120}}}]}"#;
+        assert!(import(overcharge).is_err());
+    }
+
+    #[test]
+    fn watchdog_is_never_imported() {
+        let json =
+            r#"{"schema_version":1,"profiles":[{"name":"Risky","fan":{"watchdog_secs":0}}]}"#;
+        let preset = import(json).unwrap();
+        assert_eq!(preset.profiles[0].fan.as_ref().unwrap().watchdog_secs, None);
+    }
+}
@@ -0,0 +1,205 @@
+// Profile inheritance resolution
+
+use crate::types::{
+    BatteryConfig, Config, CurveConfig, FanControlConfig, FanControlMode, PowerConfig,
+    PowerProfile, Profile, SettingU32, SettingU8,
+};
+
+/// A subsystem value together with the name of the profile it came from
+#[derive(Debug, Clone)]
+pub struct Sourced<T> {
+    pub value: T,
+    pub from: String,
+}
+
+/// Effective profile after walking the inheritance chain
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedProfile {
+    pub fan: Option<Sourced<FanControlConfig>>,
+    pub power: Option<Sourced<PowerConfig>>,
+    pub battery: Option<Sourced<BatteryConfig>>,
+}
+
+fn take<T: Clone>(slot: &mut Option<Sourced<T>>, value: &Option<T>, from: &str) {
+    if slot.is_none() {
+        if let Some(v) = value {
+            *slot = Some(Sourced {
+                value: v.clone(),
+                from: from.to_string(),
+            });
+        }
+    }
+}
+
+/// One shipped profile: a curve, TDP and thermal limit on AC and battery, and a charge limit
+fn builtin_profile(
+    name: &str,
+    curve: &[[u32; 2]],
+    tdp_watts: (u32, u32),
+    thermal_limit_c: u32,
+    charge_limit: Option<u8>,
+) -> Profile {
+    let power = |tdp| PowerProfile {
+        tdp_watts: Some(SettingU32 {
+            enabled: true,
+            value: tdp,
+        }),
+        thermal_limit_c: Some(SettingU32 {
+            enabled: true,
+            value: thermal_limit_c,
+        }),
+        ..Default::default()
+    };
+    Profile {
+        name: name.to_string(),
+        inherits: None,
+        fan: Some(FanControlConfig {
+            mode: Some(FanControlMode::Curve),
+            curve: Some(CurveConfig {
+                points: curve.to_vec(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        power: Some(PowerConfig {
+            ac: Some(power(tdp_watts.0)),
+            battery: Some(power(tdp_watts.1)),
+        }),
+        battery: Some(BatteryConfig {
+            charge_limit_max_pct: Some(SettingU8 {
+                enabled: charge_limit.is_some(),
+                value: charge_limit.unwrap_or(100),
+            }),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Profiles shipped with the app. They are not stored in the config; saving a profile under
+/// the same name replaces one. Their TDPs are shares of this CPU's supported range, so
+/// they never ask for more or less than it takes.
+pub fn builtin() -> Vec<Profile> {
+    let range = crate::cpu::tdp_range_watts();
+    let (min, max) = (*range.start(), *range.end());
+    let watts = |share: f32| min + ((max - min) as f32 * share).round() as u32;
+    vec![
+        builtin_profile(
+            "Balanced",
+            &[[40, 20], [50, 30], [60, 40], [70, 60], [80, 80], [90, 100]],
+            (watts(0.5), watts(0.25)),
+            90,
+            Some(80),
+        ),
+        builtin_profile(
+            "Performance",
+            &[[40, 30], [50, 45], [60, 60], [70, 80], [80, 100]],
+            (max, watts(0.5)),
+            95,
+            None,
+        ),
+        builtin_profile(
+            "Power saver",
+            &[[45, 15], [55, 25], [65, 40], [75, 60], [85, 100]],
+            (watts(0.25), min),
+            80,
+            Some(80),
+        ),
+    ]
+}
+
+/// Saved profiles followed by the built-ins none of them replace
+pub fn all(saved: &[Profile]) -> Vec<Profile> {
+    let mut profiles = saved.to_vec();
+    profiles.extend(
+        builtin()
+            .into_iter()
+            .filter(|b| saved.iter().all(|p| p.name != b.name)),
+    );
+    profiles
+}
+
+/// Whether `name` is a built-in that no saved profile replaces
+pub fn is_builtin(saved: &[Profile], name: &str) -> bool {
+    saved.iter().all(|p| p.name != name) && builtin().iter().any(|b| b.name == name)
+}
+
+/// Merge a profile over its bases (nearest wins), looking names up among the saved profiles
+/// and then the built-ins. Fails on unknown names and inheritance cycles.
+pub fn resolve(saved: &[Profile], name: &str) -> Result<ResolvedProfile, String> {
+    let profiles = all(saved);
+    let mut resolved = ResolvedProfile::default();
+    let mut chain: Vec<&str> = Vec::new();
+    let mut current = Some(name);
+
+    while let Some(n) = current {
+        if chain.contains(&n) {
+            chain.push(n);
+            return Err(format!("Profile inheritance cycle: {}", chain.join(" → ")));
+        }
+        chain.push(n);
+        let profile = profiles
+            .iter()
+            .find(|p| p.name == n)
+            .ok_or_else(|| format!("Unknown profile '{}'", n))?;
+        take(&mut resolved.fan, &profile.fan, n);
+        take(&mut resolved.power, &profile.power, n);
+        take(&mut resolved.battery, &profile.battery, n);
+        current = profile.inherits.as_deref();
+    }
+    Ok(resolved)
+}
+
+/// Write the resolved settings into the live config. Only what a profile owns is replaced:
+/// fan mode, curve points and manual duty, the power limits and the charge limit. Timing,
+/// calibration, safety and alert settings stay as configured, as do sections no profile defines.
+pub fn apply_to_config(resolved: &ResolvedProfile, cfg: &mut Config) {
+    if let Some(fan) = &resolved.fan {
+        let fan = &fan.value;
+        if fan.mode.is_some() {
+            cfg.fan.mode = fan.mode.clone();
+        }
+        if fan.manual.is_some() {
+            cfg.fan.manual = fan.manual.clone();
+        }
+        merge_curve(&mut cfg.fan.curve, &fan.curve);
+        merge_curve(&mut cfg.fan.curve_ac, &fan.curve_ac);
+        merge_curve(&mut cfg.fan.curve_battery, &fan.curve_battery);
+    }
+    if let Some(power) = &resolved.power {
+        merge_power(&mut cfg.power.ac, &power.value.ac);
+        merge_power(&mut cfg.power.battery, &power.value.battery);
+    }
+    if let Some(battery) = &resolved.battery {
+        let battery = &battery.value;
+        if battery.charge_limit_max_pct.is_some() {
+            cfg.battery.charge_limit_max_pct = battery.charge_limit_max_pct.clone();
+            cfg.battery.charge_limit_min_pct = battery.charge_limit_min_pct;
+        }
+    }
+}
+
+/// Take the profile curve's points and interpolation, keeping the live curve's timing
+fn merge_curve(live: &mut Option<CurveConfig>, profile: &Option<CurveConfig>) {
+    if let Some(profile) = profile {
+        let live = live.get_or_insert_with(Default::default);
+        live.points = profile.points.clone();
+        live.interpolation = profile.interpolation;
+    }
+}
+
+fn merge_power(live: &mut Option<PowerProfile>, profile: &Option<PowerProfile>) {
+    let Some(profile) = profile else {
+        return;
+    };
+    let live = live.get_or_insert_with(Default::default);
+    for (live, profile) in [
+        (&mut live.tdp_watts, &profile.tdp_watts),
+        (&mut live.fast_limit_w, &profile.fast_limit_w),
+        (&mut live.slow_limit_w, &profile.slow_limit_w),
+        (&mut live.thermal_limit_c, &profile.thermal_limit_c),
+    ] {
+        if profile.is_some() {
+            *live = profile.clone();
+        }
+    }
+}
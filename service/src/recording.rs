@@ -0,0 +1,120 @@
+// Explicit telemetry recording sessions, written as self-contained JSON Lines files:
+// one header line, one line per sample, and a footer once the session is stopped
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::TelemetrySample;
+
+/// Where the machine and app stood when the recording started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingHeader {
+    pub label: String,
+    pub started_ms: i64,
+    pub app_version: String,
+    pub cpu: String,
+    pub ec_version: String,
+    pub bios_version: String,
+    /// Fan and power settings active at the start
+    pub settings: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingFooter {
+    pub stopped_ms: i64,
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingLine {
+    Header(RecordingHeader),
+    Sample(TelemetrySample),
+    Footer(RecordingFooter),
+}
+
+/// An open recording; dropping it without `stop` leaves a file without a footer
+pub struct Recorder {
+    pub path: PathBuf,
+    pub label: String,
+    pub started: Instant,
+    pub samples: usize,
+    writer: BufWriter<File>,
+}
+
+/// Recordings live next to the config file
+pub fn recordings_dir() -> PathBuf {
+    crate::config::config_path()
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default()
+        .join("recordings")
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+impl Recorder {
+    pub fn start(mut header: RecordingHeader) -> Result<Self, String> {
+        let dir = recordings_dir();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Can't create {}: {}", dir.display(), e))?;
+        header.started_ms = now_ms();
+        let slug: String = header
+            .label
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let path = dir.join(format!(
+            "{}-{}.jsonl",
+            header.started_ms,
+            slug.trim_matches('-')
+        ));
+        let file =
+            File::create(&path).map_err(|e| format!("Can't create {}: {}", path.display(), e))?;
+
+        let mut recorder = Self {
+            path,
+            label: header.label.clone(),
+            started: Instant::now(),
+            samples: 0,
+            writer: BufWriter::new(file),
+        };
+        recorder.write_line(&RecordingLine::Header(header))?;
+        Ok(recorder)
+    }
+
+    /// Append one sample; flushed straight away so a crash loses at most this line
+    pub fn record(&mut self, sample: &TelemetrySample) -> Result<(), String> {
+        self.write_line(&RecordingLine::Sample(sample.clone()))?;
+        self.samples += 1;
+        Ok(())
+    }
+
+    /// Write the footer and sync the file to disk
+    pub fn stop(mut self) -> Result<(PathBuf, usize), String> {
+        self.write_line(&RecordingLine::Footer(RecordingFooter {
+            stopped_ms: now_ms(),
+            samples: self.samples,
+        }))?;
+        self.writer
+            .get_ref()
+            .sync_all()
+            .map_err(|e| format!("Can't sync {}: {}", self.path.display(), e))?;
+        Ok((self.path, self.samples))
+    }
+
+    fn write_line(&mut self, line: &RecordingLine) -> Result<(), String> {
+        let json = serde_json::to_string(line).map_err(|e| e.to_string())?;
+        writeln!(self.writer, "{}", json)
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| format!("Can't write {}: {}", self.path.display(), e))
+    }
+}
@@ -0,0 +1,250 @@
+// Windows service mode: the GUI's background tasks (fan curve, power and battery limits)
+// without a window, so settings keep applying before anyone logs in
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::types::Config;
+use crate::{activity, config, ipc, tasks, AppState};
+
+/// Must match the name the MSI registers in manage.ps1
+pub const SERVICE_NAME: &str = "FrameworkControl";
+
+/// Argument the service control manager starts the executable with
+pub const RUN_FLAG: &str = "--run-as-service";
+
+/// How often the config file is checked for edits made by the GUI
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Register the service to start automatically, pointed at this executable and at the
+/// config file the GUI writes. Needs administrator rights.
+pub fn install() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Can't locate executable: {}", e))?;
+    platform::install(&exe, &config::config_path())
+}
+
+/// Stop the service if it is running and remove it
+pub fn uninstall() -> Result<(), String> {
+    platform::uninstall()
+}
+
+/// Whether the service is installed and running; false when it can't be queried
+pub fn is_running() -> bool {
+    platform::is_running()
+}
+
+/// Hand the process to the service control manager; returns once the service has stopped
+pub fn run() -> Result<(), String> {
+    crate::logging::init("service");
+    platform::run()
+}
+
+/// Start the background tasks and keep them running until `stop` resolves; the state is
+/// handed back for the shutdown steps
+#[cfg_attr(not(windows), allow(dead_code))]
+async fn serve(stop: impl std::future::Future<Output = ()>) -> AppState {
+    let state = AppState::initialize().await;
+    tasks::boot(&state).await;
+    tracing::info!(
+        "Service started, config {}",
+        config::config_path().display()
+    );
+    tokio::spawn(watch_config(state.config.clone(), state.activity.clone()));
+    // Lets the GUI go through this process instead of opening the EC alongside it
+    ipc::serve(state.clone());
+    stop.await;
+    tracing::info!("Service stopping");
+    state
+}
+
+/// The background tasks read `config` on every step, so swapping in the file's contents is
+/// enough for a curve or limit changed in the GUI to take effect. The web dashboard and
+/// hotkey bindings are only read at startup and still need a service restart.
+#[cfg_attr(not(windows), allow(dead_code))]
+async fn watch_config(config: Arc<RwLock<Config>>, activity: activity::Activity) {
+    let mut seen = config::last_modified();
+    loop {
+        tokio::time::sleep(RELOAD_INTERVAL).await;
+        let modified = config::last_modified();
+        if modified == seen {
+            continue;
+        }
+        match config::try_load() {
+            Ok(loaded) => {
+                seen = modified;
+                AppState::apply_sensor_settings(&loaded);
+                *config.write().await = loaded;
+                tracing::info!("Config reloaded");
+                activity::push(&activity, "Settings reloaded from config file").await;
+            }
+            // Keep running on the last good settings until the file is fixed
+            Err(e) => tracing::debug!("Config not reloaded: {}", e),
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::ffi::OsString;
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use windows_service::service::{
+        ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+        ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+    };
+    use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+    use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+    use windows_service::{define_windows_service, service_dispatcher};
+
+    use super::{RUN_FLAG, SERVICE_NAME};
+
+    const DISPLAY_NAME: &str = "Framework Control Service";
+    const DESCRIPTION: &str = "Manages Framework Laptop hardware settings";
+
+    define_windows_service!(ffi_service_main, service_main);
+
+    pub fn install(exe: &Path, config: &Path) -> Result<(), String> {
+        let manager = ServiceManager::local_computer(
+            None::<&str>,
+            ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+        )
+        .map_err(|e| format!("Can't open service manager (run as administrator): {}", e))?;
+        let info = ServiceInfo {
+            name: OsString::from(SERVICE_NAME),
+            display_name: OsString::from(DISPLAY_NAME),
+            service_type: ServiceType::OWN_PROCESS,
+            start_type: ServiceStartType::AutoStart,
+            error_control: ServiceErrorControl::Normal,
+            executable_path: exe.to_path_buf(),
+            // Keeps a FRAMEWORK_CONTROL_CONFIG override in effect at install
+            launch_arguments: vec![
+                OsString::from(RUN_FLAG),
+                OsString::from("--config"),
+                config.as_os_str().to_owned(),
+            ],
+            dependencies: vec![],
+            account_name: None,
+            account_password: None,
+        };
+        let service = manager
+            .create_service(&info, ServiceAccess::CHANGE_CONFIG | ServiceAccess::START)
+            .map_err(|e| format!("Can't create service {}: {}", SERVICE_NAME, e))?;
+        let _ = service.set_description(DESCRIPTION);
+        service
+            .start::<&str>(&[])
+            .map_err(|e| format!("Service installed but didn't start: {}", e))
+    }
+
+    pub fn uninstall() -> Result<(), String> {
+        let manager =
+            ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+                .map_err(|e| format!("Can't open service manager (run as administrator): {}", e))?;
+        let service = manager
+            .open_service(
+                SERVICE_NAME,
+                ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+            )
+            .map_err(|e| format!("Can't open service {}: {}", SERVICE_NAME, e))?;
+        let status = service.query_status().map_err(|e| e.to_string())?;
+        if status.current_state != ServiceState::Stopped {
+            service
+                .stop()
+                .map_err(|e| format!("Can't stop service: {}", e))?;
+        }
+        // Removal completes once the last handle to the service closes
+        service
+            .delete()
+            .map_err(|e| format!("Can't delete service: {}", e))
+    }
+
+    pub fn is_running() -> bool {
+        ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+            .and_then(|manager| manager.open_service(SERVICE_NAME, ServiceAccess::QUERY_STATUS))
+            .and_then(|service| service.query_status())
+            .is_ok_and(|status| status.current_state == ServiceState::Running)
+    }
+
+    pub fn run() -> Result<(), String> {
+        service_dispatcher::start(SERVICE_NAME, ffi_service_main)
+            .map_err(|e| format!("Not started by the service control manager: {}", e))
+    }
+
+    fn status(state: ServiceState, controls: ServiceControlAccept) -> ServiceStatus {
+        ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted: controls,
+            exit_code: ServiceExitCode::Win32(0),
+            checkpoint: 0,
+            wait_hint: Duration::from_secs(5),
+            process_id: None,
+        }
+    }
+
+    fn service_main(_arguments: Vec<OsString>) {
+        if let Err(e) = run_service() {
+            tracing::error!("Service failed: {}", e);
+        }
+    }
+
+    fn run_service() -> Result<(), String> {
+        let stop = Arc::new(tokio::sync::Notify::new());
+        let handler_stop = stop.clone();
+        let handle =
+            service_control_handler::register(SERVICE_NAME, move |control| match control {
+                ServiceControl::Stop | ServiceControl::Shutdown => {
+                    handler_stop.notify_one();
+                    ServiceControlHandlerResult::NoError
+                }
+                ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+                _ => ServiceControlHandlerResult::NotImplemented,
+            })
+            .map_err(|e| e.to_string())?;
+
+        let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+        handle
+            .set_service_status(status(
+                ServiceState::Running,
+                ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+            ))
+            .map_err(|e| e.to_string())?;
+
+        let state = runtime.block_on(super::serve(stop.notified()));
+
+        // Report the stop as under way before touching the EC, which can be slow to answer
+        let _ = handle.set_service_status(status(
+            ServiceState::StopPending,
+            ServiceControlAccept::empty(),
+        ));
+        runtime.block_on(state.release_fans("service stop"));
+        // Background tasks loop forever; dropping the runtime cancels them
+        runtime.shutdown_timeout(Duration::from_secs(2));
+        handle
+            .set_service_status(status(ServiceState::Stopped, ServiceControlAccept::empty()))
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use std::path::Path;
+
+    const UNSUPPORTED: &str = "Service mode is only supported on Windows";
+
+    pub fn install(_exe: &Path, _config: &Path) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn uninstall() -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn is_running() -> bool {
+        false
+    }
+
+    pub fn run() -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+}
@@ -0,0 +1,60 @@
+// Labeled telemetry summaries for comparing settings before and after a change
+use crate::types::TelemetrySample;
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub label: String,
+    /// Settings active when the snapshot was taken
+    pub settings: String,
+    pub samples: usize,
+    pub duration_s: f32,
+    pub max_temp_c: f32,
+    pub avg_temp_c: f32,
+    pub avg_fan_rpm: f32,
+    pub avg_noise_db: f32,
+    pub avg_power_w: Option<f32>,
+}
+
+fn mean(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, n) = values.fold((0.0, 0usize), |(s, n), v| (s + v, n + 1));
+    (n > 0).then(|| sum / n as f32)
+}
+
+/// Summarize the samples from the last `window_s` seconds of `history`
+pub fn capture(
+    label: &str,
+    settings: String,
+    history: &[TelemetrySample],
+    window_s: u64,
+    noise_map: &[[u32; 2]],
+) -> Option<Snapshot> {
+    let newest = history.last()?.ts_ms;
+    let cutoff = newest - (window_s as i64 * 1000);
+    let window: Vec<&TelemetrySample> = history.iter().filter(|s| s.ts_ms >= cutoff).collect();
+
+    let hottest: Vec<f32> = window
+        .iter()
+        .filter_map(|s| s.temps.iter().map(|t| t.1).reduce(f32::max))
+        .collect();
+    let fan_rpm: Vec<f32> = window
+        .iter()
+        .filter_map(|s| s.fan_rpms.iter().copied().reduce(f32::max))
+        .collect();
+
+    Some(Snapshot {
+        label: label.to_string(),
+        settings,
+        samples: window.len(),
+        duration_s: (newest - window.first()?.ts_ms) as f32 / 1000.0,
+        max_temp_c: hottest.iter().copied().reduce(f32::max)?,
+        avg_temp_c: mean(hottest.iter().copied())?,
+        avg_fan_rpm: mean(fan_rpm.iter().copied()).unwrap_or(0.0),
+        avg_noise_db: mean(
+            fan_rpm
+                .iter()
+                .map(|rpm| crate::noise::estimate_db(*rpm, noise_map)),
+        )
+        .unwrap_or(0.0),
+        avg_power_w: mean(window.iter().filter_map(|s| s.power_w)),
+    })
+}
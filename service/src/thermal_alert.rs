@@ -0,0 +1,101 @@
+// Overheat alerts: a sensor that has stayed above the configured temperature for the
+// configured time, judged from the telemetry history
+use crate::types::{AlertsConfig, TelemetrySample};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThermalAlert {
+    pub sensor: String,
+    pub temp_c: f32,
+    pub threshold_c: f32,
+    pub for_secs: u64,
+}
+
+impl ThermalAlert {
+    pub fn message(&self) -> String {
+        format!(
+            "{} at {:.0}°C, above {:.0}°C for over {}s",
+            self.sensor, self.temp_c, self.threshold_c, self.for_secs
+        )
+    }
+}
+
+/// Sensors above the threshold in every sample of the last `duration_s`. Nothing is
+/// reported until the history spans that long.
+pub fn check(history: &[TelemetrySample], cfg: &AlertsConfig) -> Vec<ThermalAlert> {
+    let (Some(oldest), Some(newest)) = (history.first(), history.last()) else {
+        return Vec::new();
+    };
+    if !cfg.enabled {
+        return Vec::new();
+    }
+    let start = newest.ts_ms - (cfg.duration_s as i64 * 1000);
+    if oldest.ts_ms > start {
+        return Vec::new();
+    }
+    let window: Vec<&TelemetrySample> = history.iter().filter(|s| s.ts_ms >= start).collect();
+    let above = |sample: &TelemetrySample, sensor: &str| {
+        sample
+            .temps
+            .iter()
+            .any(|(name, temp)| name == sensor && *temp > cfg.temp_c)
+    };
+    newest
+        .temps
+        .iter()
+        .filter(|(name, _)| window.iter().all(|s| above(s, name)))
+        .map(|(name, temp)| ThermalAlert {
+            sensor: name.clone(),
+            temp_c: *temp,
+            threshold_c: cfg.temp_c,
+            for_secs: cfg.duration_s,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(ts_ms: i64, cpu_c: f32) -> TelemetrySample {
+        TelemetrySample {
+            ts_ms,
+            temps: vec![("CPU".to_string(), cpu_c), ("Battery".to_string(), 35.0)],
+            fan_rpms: Vec::new(),
+            fan_duty_pct: None,
+            battery_percent: None,
+            battery_percents: Vec::new(),
+            charging: None,
+            power_w: None,
+            charger_w: None,
+            battery_w: None,
+            cpu_mhz: None,
+            cpu_max_mhz: None,
+            tdp_w: None,
+        }
+    }
+
+    #[test]
+    fn alerts_only_after_the_whole_window_is_hot() {
+        let cfg = AlertsConfig {
+            enabled: true,
+            temp_c: 90.0,
+            duration_s: 10,
+        };
+        let hot: Vec<_> = (0..=10).map(|s| sample(s * 1000, 95.0)).collect();
+        let alerts = check(&hot, &cfg);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].sensor, "CPU");
+
+        // Too little history to judge yet
+        assert!(check(&hot[5..], &cfg).is_empty());
+        // One cool sample inside the window clears it
+        let mut dipped = hot.clone();
+        dipped[7] = sample(7000, 80.0);
+        assert!(check(&dipped, &cfg).is_empty());
+        let disabled = AlertsConfig {
+            enabled: false,
+            ..cfg
+        };
+        assert!(check(&hot, &disabled).is_empty());
+    }
+}
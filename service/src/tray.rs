@@ -0,0 +1,182 @@
+// Notification-area icon with quick actions, so the app can keep running with its
+// window closed
+use eframe::egui;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub enum TrayAction {
+    /// Bring the window back
+    Show,
+    /// Hand the fans back to the EC
+    AutoFan,
+    /// Run the fans at full speed
+    MaxFan,
+    /// Turn the configured charge limit on or off
+    ToggleChargeLimit,
+    Quit,
+}
+
+/// Create the tray icon. Menu picks arrive on the returned channel and wake `ctx`, so they
+/// are handled on the next frame even while the window is hidden.
+pub fn create(
+    ctx: egui::Context,
+    charge_limit_on: bool,
+) -> Result<(Tray, mpsc::UnboundedReceiver<TrayAction>), String> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let tray = platform::create(ctx, charge_limit_on, tx)?;
+    Ok((tray, rx))
+}
+
+pub use platform::Tray;
+
+/// Restore and focus the app's window, even one hidden to the tray or owned by another
+/// running copy
+pub fn show_window() {
+    platform::show_window()
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::TrayAction;
+    use eframe::egui;
+    use tokio::sync::mpsc;
+    use tray_icon::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+    use tray_icon::{
+        Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
+    };
+
+    const MENU: [(&str, TrayAction); 5] = [
+        ("show", TrayAction::Show),
+        ("auto_fan", TrayAction::AutoFan),
+        ("max_fan", TrayAction::MaxFan),
+        ("charge_limit", TrayAction::ToggleChargeLimit),
+        ("quit", TrayAction::Quit),
+    ];
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn FindWindowW(class: *const u16, title: *const u16) -> isize;
+        fn ShowWindow(hwnd: isize, cmd: i32) -> i32;
+        fn SetForegroundWindow(hwnd: isize) -> i32;
+    }
+    const SW_RESTORE: i32 = 9;
+
+    /// Owns the icon; it disappears from the tray when this is dropped
+    pub struct Tray {
+        icon: TrayIcon,
+        charge_limit: CheckMenuItem,
+    }
+
+    impl Tray {
+        pub fn set_tooltip(&self, text: &str) {
+            let _ = self.icon.set_tooltip(Some(text));
+        }
+
+        pub fn set_charge_limit(&self, on: bool) {
+            if self.charge_limit.is_checked() != on {
+                self.charge_limit.set_checked(on);
+            }
+        }
+    }
+
+    // A hidden window gets no frames to act on a repaint request, so "Show" goes to the
+    // window directly and the rest follows once it is painting again
+    pub fn show_window() {
+        let title: Vec<u16> = "Framework Control".encode_utf16().chain(Some(0)).collect();
+        unsafe {
+            let hwnd = FindWindowW(std::ptr::null(), title.as_ptr());
+            if hwnd != 0 {
+                ShowWindow(hwnd, SW_RESTORE);
+                SetForegroundWindow(hwnd);
+            }
+        }
+    }
+
+    fn icon() -> Result<Icon, String> {
+        let image = image::load_from_memory(include_bytes!("../icon.png"))
+            .map_err(|e| format!("Can't decode tray icon: {}", e))?
+            .into_rgba8();
+        let (width, height) = image.dimensions();
+        Icon::from_rgba(image.into_raw(), width, height).map_err(|e| e.to_string())
+    }
+
+    pub fn create(
+        ctx: egui::Context,
+        charge_limit_on: bool,
+        tx: mpsc::UnboundedSender<TrayAction>,
+    ) -> Result<Tray, String> {
+        let item = |id: &str, text: &str| MenuItem::with_id(id, text, true, None);
+        let charge_limit =
+            CheckMenuItem::with_id("charge_limit", "Charge limit", true, charge_limit_on, None);
+        let menu = Menu::new();
+        menu.append_items(&[
+            &item("show", "Show"),
+            &PredefinedMenuItem::separator(),
+            &item("auto_fan", "Auto fan"),
+            &item("max_fan", "Max fan"),
+            &charge_limit,
+            &PredefinedMenuItem::separator(),
+            &item("quit", "Quit"),
+        ])
+        .map_err(|e| e.to_string())?;
+
+        let icon = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_tooltip("Framework Control")
+            .with_icon(icon()?)
+            .build()
+            .map_err(|e| format!("Can't create tray icon: {}", e))?;
+
+        let send = move |action: TrayAction| {
+            if action == TrayAction::Show {
+                show_window();
+            }
+            let _ = tx.send(action);
+            ctx.request_repaint();
+        };
+        let on_click = send.clone();
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            if let Some((_, action)) = MENU.iter().find(|(id, _)| event.id == *id) {
+                send(*action);
+            }
+        }));
+        TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                on_click(TrayAction::Show);
+            }
+        }));
+
+        Ok(Tray { icon, charge_limit })
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use super::TrayAction;
+    use eframe::egui;
+    use tokio::sync::mpsc;
+
+    pub struct Tray;
+
+    pub fn show_window() {}
+
+    impl Tray {
+        pub fn set_tooltip(&self, _text: &str) {}
+
+        pub fn set_charge_limit(&self, _on: bool) {}
+    }
+
+    pub fn create(
+        _ctx: egui::Context,
+        _charge_limit_on: bool,
+        _tx: mpsc::UnboundedSender<TrayAction>,
+    ) -> Result<Tray, String> {
+        Err("The tray icon is only supported on Windows".to_string())
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // Core config types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +14,76 @@ pub struct Config {
     pub ui: UiConfig,
     #[serde(default)]
     pub start_on_boot: bool,
+    /// Closing the window hides it to the tray instead of quitting
+    #[serde(default = "default_true")]
+    pub minimize_to_tray: bool,
+    /// Last app version whose "what's new" notes were dismissed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen_version: Option<String>,
+    /// Named fan/power/battery bundles that can be applied in one click
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<Profile>,
+    /// Named raw EC command sequences, run from the Advanced panel
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ec_macros: Vec<EcMacro>,
+    /// Per-sensor calibration offsets in °C, keyed by sensor name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sensor_offsets: BTreeMap<String, f32>,
+    #[serde(default)]
+    pub hotkeys: HotkeyConfig,
+    /// Seconds the AC state must hold before switching between AC and battery power limits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ac_debounce_s: Option<u64>,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub alerts: AlertsConfig,
+    #[serde(default)]
+    pub temp_conversion: TempConversionConfig,
+    /// Telemetry samples kept in memory; one per poll, so the default is an hour at 1s
+    #[serde(default = "default_telemetry_max_samples")]
+    pub telemetry_max_samples: usize,
+    /// How often the EC is sampled for the dashboard and history (default 1000ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll_interval_ms: Option<u64>,
+    /// Hand the fans back to the EC when the app or service exits; off keeps the last
+    /// manual duty or curve point running
+    #[serde(default = "default_true")]
+    pub restore_auto_on_exit: bool,
+}
+
+/// Faster polling keeps the EC busy without showing anything new
+pub const MIN_POLL_INTERVAL_MS: u64 = 250;
+const DEFAULT_POLL_INTERVAL_MS: u64 = 1000;
+
+impl Config {
+    /// Time between EC samples, never below `MIN_POLL_INTERVAL_MS`
+    pub fn poll_interval(&self) -> std::time::Duration {
+        let ms = self
+            .poll_interval_ms
+            .unwrap_or(DEFAULT_POLL_INTERVAL_MS)
+            .max(MIN_POLL_INTERVAL_MS);
+        std::time::Duration::from_millis(ms)
+    }
+}
+
+fn default_telemetry_max_samples() -> usize {
+    3600
+}
+fn default_true() -> bool {
+    true
+}
+
+/// Overrides for turning raw EC temperature bytes into °C, for boards the built-in
+/// platform table gets wrong
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TempConversionConfig {
+    /// Subtracted from the raw byte; unset uses the platform's offset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i16>,
+    /// [min, max] °C; readings outside are dropped as sensor faults
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_range_c: Option<[f32; 2]>,
 }
 
 impl Default for Config {
@@ -23,6 +94,19 @@ impl Default for Config {
             battery: BatteryConfig::default(),
             ui: UiConfig::default(),
             start_on_boot: false,
+            minimize_to_tray: true,
+            last_seen_version: None,
+            profiles: Vec::new(),
+            ec_macros: Vec::new(),
+            sensor_offsets: BTreeMap::new(),
+            hotkeys: HotkeyConfig::default(),
+            ac_debounce_s: None,
+            api: ApiConfig::default(),
+            alerts: AlertsConfig::default(),
+            temp_conversion: TempConversionConfig::default(),
+            telemetry_max_samples: default_telemetry_max_samples(),
+            poll_interval_ms: None,
+            restore_auto_on_exit: true,
         }
     }
 }
@@ -49,8 +133,131 @@ pub struct FanControlConfig {
     pub manual: Option<ManualConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub curve: Option<CurveConfig>,
+    /// Curve used while on AC power instead of `curve`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve_ac: Option<CurveConfig>,
+    /// Curve used while on battery instead of `curve`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve_battery: Option<CurveConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub calibration: Option<FanCalibration>,
+    /// RPM -> estimated loudness (dB) points used for the noise gauge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise_map: Option<Vec<[u32; 2]>>,
+    /// Duration of the temporary 100% fan boost (default 60s)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boost_secs: Option<u64>,
+    /// Upper bound on the duty the app commands, traded for higher temperatures.
+    /// Ignored above the emergency temperature; a manual boost is not capped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duty_pct: Option<u32>,
+    /// Alert when a fan runs this many percent below its calibrated RPM (default 30)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpm_alert_tolerance_pct: Option<u32>,
+    /// Pulse a stopped fan to a higher duty so it overcomes stiction at low targets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spin_up_kick: Option<SpinUpKick>,
+    /// Duty (%) held in curve mode while no sensor reports a usable temperature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_duty_pct: Option<u32>,
+    /// Curves for individual fans; fans without one follow `curve`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fan_curves: Vec<FanCurve>,
+    /// Hand the fans back to EC auto control once no fan command has succeeded for this
+    /// long (default 30s, 0 disables); stretched to cover two curve polls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watchdog_secs: Option<u64>,
+}
+
+impl FanControlConfig {
+    /// Longest time between curve steps across the shared, AC and battery curves
+    pub fn longest_poll(&self) -> std::time::Duration {
+        [&self.curve, &self.curve_ac, &self.curve_battery]
+            .into_iter()
+            .flatten()
+            .map(CurveConfig::poll_interval)
+            .max()
+            .unwrap_or_else(|| CurveConfig::default().poll_interval())
+    }
+
+    /// Whether the AC or battery curve is set, so the power source matters
+    pub fn has_power_source_curves(&self) -> bool {
+        self.curve_ac.is_some() || self.curve_battery.is_some()
+    }
+
+    /// The curve for the power source (`Some(true)` on AC); `curve` when that source has
+    /// none of its own or the source is unknown
+    pub fn curve_for(&self, on_ac: Option<bool>) -> Option<&CurveConfig> {
+        let own = match on_ac {
+            Some(true) => self.curve_ac.as_ref(),
+            Some(false) => self.curve_battery.as_ref(),
+            None => None,
+        };
+        own.or(self.curve.as_ref())
+    }
+
+    /// Normalize the shared, AC, battery and per-fan curves, describing each correction
+    pub fn validate_and_normalize_curves(&mut self) -> Vec<String> {
+        let mut fixes = Vec::new();
+        for (name, curve) in [
+            ("Fan curve", &mut self.curve),
+            ("AC fan curve", &mut self.curve_ac),
+            ("Battery fan curve", &mut self.curve_battery),
+        ] {
+            fixes.extend(
+                curve
+                    .iter_mut()
+                    .flat_map(|c| c.validate_and_normalize())
+                    .map(|fix| format!("{}: {}", name, fix)),
+            );
+        }
+        for fc in &mut self.fan_curves {
+            fixes.extend(
+                normalize_points(&mut fc.points)
+                    .into_iter()
+                    .map(|fix| format!("Fan {} curve: {}", fc.fan, fix)),
+            );
+        }
+        fixes
+    }
+}
+
+/// A curve that drives one fan, by EC fan index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanCurve {
+    pub fan: u32,
+    pub points: Vec<[u32; 2]>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpinUpKick {
+    #[serde(default = "default_kick_duty_pct")]
+    pub duty_pct: u32,
+    #[serde(default = "default_kick_ms")]
+    pub duration_ms: u64,
+    /// Kicks that fail to start the fan before giving up until it is seen spinning again
+    #[serde(default = "default_kick_max_attempts")]
+    pub max_attempts: u32,
+}
+
+fn default_kick_duty_pct() -> u32 {
+    40
+}
+fn default_kick_ms() -> u64 {
+    1500
+}
+fn default_kick_max_attempts() -> u32 {
+    3
+}
+
+impl Default for SpinUpKick {
+    fn default() -> Self {
+        Self {
+            duty_pct: default_kick_duty_pct(),
+            duration_ms: default_kick_ms(),
+            max_attempts: default_kick_max_attempts(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,10 +271,52 @@ pub struct CurveConfig {
     pub points: Vec<[u32; 2]>,
     #[serde(default = "default_poll_ms")]
     pub poll_ms: u64,
+    /// Drops smaller than this (°C) since the curve was last evaluated are ignored
     #[serde(default = "default_hysteresis_c")]
     pub hysteresis_c: u32,
-    #[serde(default = "default_rate_limit_pct_per_step")]
-    pub rate_limit_pct_per_step: u32,
+    /// Seconds the duty is held after rising before it may come down again
+    #[serde(default = "default_min_dwell_s")]
+    pub min_dwell_s: u32,
+    /// Max duty increase per poll; reads the old symmetric `rate_limit_pct_per_step` key
+    #[serde(
+        default = "default_rate_limit_up_pct_per_step",
+        alias = "rate_limit_pct_per_step"
+    )]
+    pub rate_limit_up_pct_per_step: u32,
+    /// Max duty decrease per poll; slower than the ramp up to avoid noise pumping
+    #[serde(default = "default_rate_limit_down_pct_per_step")]
+    pub rate_limit_down_pct_per_step: u32,
+    #[serde(default)]
+    pub interpolation: Interpolation,
+    /// Weight of each new reading in the moving average the curve is evaluated at
+    /// (0.05-1); lower is calmer but slower to follow, 1 disables smoothing
+    #[serde(default = "default_smoothing_alpha")]
+    pub smoothing_alpha: f32,
+}
+
+/// How duty is filled in between curve points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interpolation {
+    /// Straight lines; audible changes of slope at each point
+    #[default]
+    Linear,
+    /// Smooth spline through every point
+    CatmullRom,
+    /// Eases in and out of each point, flat at the points themselves
+    Smoothstep,
+}
+
+impl Interpolation {
+    pub const ALL: [Self; 3] = [Self::Linear, Self::CatmullRom, Self::Smoothstep];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::CatmullRom => "Smooth (Catmull-Rom)",
+            Self::Smoothstep => "Smoothstep",
+        }
+    }
 }
 
 fn default_points() -> Vec<[u32; 2]> {
@@ -79,9 +328,18 @@ fn default_poll_ms() -> u64 {
 fn default_hysteresis_c() -> u32 {
     2
 }
-fn default_rate_limit_pct_per_step() -> u32 {
+fn default_min_dwell_s() -> u32 {
+    10
+}
+fn default_smoothing_alpha() -> f32 {
+    1.0
+}
+fn default_rate_limit_up_pct_per_step() -> u32 {
     100
 }
+fn default_rate_limit_down_pct_per_step() -> u32 {
+    5
+}
 
 impl Default for CurveConfig {
     fn default() -> Self {
@@ -89,18 +347,239 @@ impl Default for CurveConfig {
             points: default_points(),
             poll_ms: default_poll_ms(),
             hysteresis_c: default_hysteresis_c(),
-            rate_limit_pct_per_step: default_rate_limit_pct_per_step(),
+            min_dwell_s: default_min_dwell_s(),
+            rate_limit_up_pct_per_step: default_rate_limit_up_pct_per_step(),
+            rate_limit_down_pct_per_step: default_rate_limit_down_pct_per_step(),
+            interpolation: Interpolation::default(),
+            smoothing_alpha: default_smoothing_alpha(),
         }
     }
 }
 
+/// Hottest temperature (°C) a curve point may sit at
+pub const MAX_CURVE_TEMP_C: u32 = 110;
+/// Below this the average takes minutes to follow a real rise
+pub const MIN_SMOOTHING_ALPHA: f32 = 0.05;
+
+/// Sort `points` by temperature, clamp them to 0..=`MAX_CURVE_TEMP_C` °C and 0..=100%,
+/// and merge points at the same temperature, keeping the higher duty. Fewer than two
+/// points left can't form a curve, so the default one is used instead. Returns what was
+/// corrected, empty when the points were already valid.
+pub fn normalize_points(points: &mut Vec<[u32; 2]>) -> Vec<String> {
+    let mut fixes = Vec::new();
+    if points.iter().any(|p| p[0] > MAX_CURVE_TEMP_C || p[1] > 100) {
+        fixes.push("points clamped to valid temperature and duty".to_string());
+    }
+    let mut normalized: Vec<[u32; 2]> = points
+        .iter()
+        .map(|p| [p[0].min(MAX_CURVE_TEMP_C), p[1].min(100)])
+        .collect();
+    normalized.sort_by_key(|p| p[0]);
+    if normalized.windows(2).any(|w| w[0][0] == w[1][0]) {
+        fixes.push("points at the same temperature merged".to_string());
+    }
+    normalized.dedup_by(|next, kept| {
+        let same = next[0] == kept[0];
+        if same {
+            kept[1] = kept[1].max(next[1]);
+        }
+        same
+    });
+    if normalized.len() < 2 {
+        fixes.push("fewer than two points, default curve restored".to_string());
+        normalized = default_points();
+    }
+    if fixes.is_empty() && normalized != *points {
+        fixes.push("points sorted by temperature".to_string());
+    }
+    *points = normalized;
+    fixes
+}
+
+impl CurveConfig {
+    /// Time between curve steps, kept to 0.5-60s
+    pub fn poll_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.poll_ms.clamp(500, 60_000))
+    }
+
+    /// Make the curve safe to evaluate: normalized points (see [`normalize_points`]) and
+    /// step sizes that can still reach the target. Returns what was corrected.
+    pub fn validate_and_normalize(&mut self) -> Vec<String> {
+        let mut fixes = normalize_points(&mut self.points);
+        if self.rate_limit_up_pct_per_step == 0 || self.rate_limit_down_pct_per_step == 0 {
+            fixes.push("zero rate limit raised to 1%".to_string());
+        }
+        self.rate_limit_up_pct_per_step = self.rate_limit_up_pct_per_step.clamp(1, 100);
+        self.rate_limit_down_pct_per_step = self.rate_limit_down_pct_per_step.clamp(1, 100);
+        if !(MIN_SMOOTHING_ALPHA..=1.0).contains(&self.smoothing_alpha) {
+            fixes.push("smoothing alpha limited to 0.05-1".to_string());
+            self.smoothing_alpha = if self.smoothing_alpha.is_nan() {
+                default_smoothing_alpha()
+            } else {
+                self.smoothing_alpha.clamp(MIN_SMOOTHING_ALPHA, 1.0)
+            };
+        }
+        fixes
+    }
+
+    /// Move from the last applied duty towards `target`, limited by the directional step sizes
+    pub fn rate_limit(&self, last: f32, target: f32) -> f32 {
+        if target > last {
+            target.min(last + self.rate_limit_up_pct_per_step as f32)
+        } else {
+            target.max(last - self.rate_limit_down_pct_per_step as f32)
+        }
+    }
+
+    /// Temperature to evaluate the curve at: rises follow `temp` at once, but a drop only
+    /// counts once it is `hysteresis_c` below the temperature `held` from earlier polls
+    pub fn hysteresis(&self, held: Option<f32>, temp: f32) -> f32 {
+        match held {
+            Some(held) if temp < held && temp > held - self.hysteresis_c as f32 => held,
+            _ => temp,
+        }
+    }
+
+    /// Exponential moving average of the curve temperature: `temp` weighted by
+    /// `smoothing_alpha` against the `previous` average
+    pub fn smooth(&self, previous: Option<f32>, temp: f32) -> f32 {
+        match previous {
+            Some(previous) => previous + self.smoothing_alpha * (temp - previous),
+            None => temp,
+        }
+    }
+}
+
+/// One point of telemetry history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetrySample {
+    /// Milliseconds since the Unix epoch
+    pub ts_ms: i64,
+    pub temps: Vec<(String, f32)>,
+    pub fan_rpms: Vec<f32>,
+    /// Duty last commanded by the app; None while the EC runs the fans itself
+    pub fan_duty_pct: Option<u32>,
+    pub battery_percent: Option<f32>,
+    /// Per-pack charge, recorded only on machines with more than one battery
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub battery_percents: Vec<f32>,
+    pub charging: Option<bool>,
+    /// System power draw in watts, when it can be derived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power_w: Option<f32>,
+    /// Estimated charger input (W), an upper bound; None on battery
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charger_w: Option<f32>,
+    /// Battery power (W): positive while charging, negative while discharging
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battery_w: Option<f32>,
+    /// Average CPU clock across cores (MHz)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_mhz: Option<f32>,
+    /// Rated maximum CPU clock (MHz), to judge how far it has clocked down
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_max_mhz: Option<f32>,
+    /// TDP last applied by the app (W); None until one has been set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tdp_w: Option<u32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct UiConfig {
     /// Preferred UI theme (matches DaisyUI theme names)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub theme: Option<String>,
+    /// Per-category overrides of the temperature color breakpoints
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub temp_thresholds: BTreeMap<SensorCategory, TempThresholds>,
+    /// Refresh interval while the window is focused (default: the poll interval)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repaint_visible_ms: Option<u64>,
+    /// Refresh interval while unfocused or minimized (default 10000ms; 0 = only on input)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repaint_hidden_ms: Option<u64>,
+    /// Ask to keep new power limits, reverting after `value` seconds without an answer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm_power_changes: Option<SettingU32>,
+    /// Show temperatures, fans and battery level as dials instead of text
+    #[serde(default)]
+    pub gauge_view: bool,
+    /// Show tools for inspecting the EC, such as the raw memory dump
+    #[serde(default)]
+    pub advanced_tools: bool,
+}
+
+impl UiConfig {
+    /// How long to wait before the next repaint; `None` means wait for input. A focused
+    /// window follows `poll`, since repainting faster shows nothing new.
+    pub fn repaint_interval(
+        &self,
+        focused: bool,
+        poll: std::time::Duration,
+    ) -> Option<std::time::Duration> {
+        let ms = if focused {
+            self.repaint_visible_ms.unwrap_or(poll.as_millis() as u64)
+        } else {
+            self.repaint_hidden_ms.unwrap_or(10_000)
+        };
+        (ms > 0).then(|| std::time::Duration::from_millis(ms))
+    }
+
+    pub fn thresholds_for(&self, sensor_name: &str) -> TempThresholds {
+        let category = SensorCategory::from_name(sensor_name);
+        self.temp_thresholds
+            .get(&category)
+            .copied()
+            .unwrap_or_else(|| category.default_thresholds())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SensorCategory {
+    Cpu,
+    Gpu,
+    Ssd,
+    Battery,
+    Ambient,
+    Other,
 }
 
+impl SensorCategory {
+    pub fn from_name(name: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        if name.contains("cpu") || name.contains("apu") {
+            Self::Cpu
+        } else if name.contains("gpu") {
+            Self::Gpu
+        } else if name.contains("ssd") || name.contains("nvme") {
+            Self::Ssd
+        } else if name.contains("battery") {
+            Self::Battery
+        } else if name.contains("ambient") {
+            Self::Ambient
+        } else {
+            Self::Other
+        }
+    }
+
+    pub fn default_thresholds(self) -> TempThresholds {
+        let (warn_c, hot_c) = match self {
+            Self::Cpu | Self::Gpu | Self::Other => (75.0, 85.0),
+            Self::Ssd => (60.0, 70.0),
+            Self::Battery => (40.0, 50.0),
+            Self::Ambient => (40.0, 50.0),
+        };
+        TempThresholds { warn_c, hot_c }
+    }
+}
+
+/// Above `warn_c` a reading is shown orange, above `hot_c` red
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TempThresholds {
+    pub warn_c: f32,
+    pub hot_c: f32,
+}
 
 // Fan calibration types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -118,10 +597,31 @@ pub struct SettingU32 {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PowerProfile {
+    /// Sustained limit (W); ryzenadj's STAPM limit
     pub tdp_watts: Option<SettingU32>,
+    /// Short-burst limit (W), ryzenadj's fast limit; follows `tdp_watts` unless enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fast_limit_w: Option<SettingU32>,
+    /// Limit (W) over tens of seconds, ryzenadj's slow limit; follows `tdp_watts` unless enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slow_limit_w: Option<SettingU32>,
     pub thermal_limit_c: Option<SettingU32>,
 }
 
+impl PowerProfile {
+    /// (STAPM, fast, slow) limits in watts, when a TDP is set
+    pub fn power_limits_w(&self) -> Option<(u32, u32, u32)> {
+        let stapm = self.tdp_watts.as_ref().filter(|s| s.enabled)?.value;
+        let or_stapm =
+            |s: &Option<SettingU32>| s.as_ref().filter(|s| s.enabled).map_or(stapm, |s| s.value);
+        Some((
+            stapm,
+            or_stapm(&self.fast_limit_w),
+            or_stapm(&self.slow_limit_w),
+        ))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PowerConfig {
     /// Profile used when AC power is present (plugged in / charging)
@@ -152,10 +652,277 @@ pub struct BatteryConfig {
     /// EC charge limit maximum percent (25-100)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub charge_limit_max_pct: Option<SettingU8>,
+    /// Charging resumes below this percent; defaults to max minus the recommended deadband
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charge_limit_min_pct: Option<u8>,
+    /// Notify once when charging on AC reaches the charge limit
+    #[serde(default)]
+    pub notify_at_limit: bool,
+    /// Profile whose power limits are applied when the battery goes critical
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub critical_profile: Option<String>,
+    /// Absolute charge current cap in mA; combined with the rate-C limit, the lower wins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charge_current_limit_ma: Option<SettingU32>,
     /// Charge rate in C (0.0 - 1.0). When disabled, use 1.0C to approximate no limit.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub charge_rate_c: Option<SettingF32>,
     /// Optional SoC threshold (%) for rate limiting
     #[serde(skip_serializing_if = "Option::is_none")]
     pub charge_rate_soc_threshold_pct: Option<u8>,
+    /// Charge to 100% once, e.g. before a trip, then return to the limit above. Saved so a
+    /// restart mid-charge keeps it until the battery reports full.
+    #[serde(default)]
+    pub charge_to_full_once: bool,
+}
+
+/// Warn when a sensor stays hot, e.g. from a blocked vent or a failing fan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertsConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Temperature (°C) any sensor must stay above to raise an alert
+    #[serde(default = "default_alert_temp_c")]
+    pub temp_c: f32,
+    /// How long (s) it must stay there, so short load spikes don't alert
+    #[serde(default = "default_alert_duration_s")]
+    pub duration_s: u64,
+}
+
+fn default_alert_temp_c() -> f32 {
+    95.0
+}
+fn default_alert_duration_s() -> u64 {
+    30
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            temp_c: default_alert_temp_c(),
+            duration_s: default_alert_duration_s(),
+        }
+    }
+}
+
+/// System-wide hotkeys such as "Ctrl+Alt+F"; an empty binding disables it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HotkeyConfig {
+    /// Force every fan to 100% until restored
+    #[serde(default = "default_panic_hotkey")]
+    pub panic_fans: String,
+    /// Return fans to the configured mode
+    #[serde(default = "default_restore_hotkey")]
+    pub restore_fans: String,
+}
+
+fn default_panic_hotkey() -> String {
+    "Ctrl+Alt+F".to_string()
+}
+fn default_restore_hotkey() -> String {
+    "Ctrl+Alt+R".to_string()
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            panic_fans: default_panic_hotkey(),
+            restore_fans: default_restore_hotkey(),
+        }
+    }
+}
+
+/// Read-only HTTP API and web dashboard; off by default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Listen address; use 0.0.0.0 to reach it from other devices on the network
+    #[serde(default = "default_api_bind")]
+    pub bind: String,
+}
+
+fn default_api_bind() -> String {
+    "127.0.0.1:8787".to_string()
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_api_bind(),
+        }
+    }
+}
+
+// Named profiles; a missing section is taken from the `inherits` base
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Profile {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inherits: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fan: Option<FanControlConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power: Option<PowerConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battery: Option<BatteryConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EcMacro {
+    pub name: String,
+    pub steps: Vec<EcMacroStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EcMacroStep {
+    pub command: u16,
+    #[serde(default)]
+    pub version: u8,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub data: Vec<u8>,
+    /// Pause after this step before sending the next one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay_ms: Option<u64>,
+}
+
+/// Smallest allowed gap between the charge-limit min and max (%)
+pub const MIN_CHARGE_DEADBAND_PCT: u8 = 3;
+/// Gap below which the UI warns about frequent charge cycling (%)
+pub const RECOMMENDED_CHARGE_DEADBAND_PCT: u8 = 5;
+
+/// Effective (min, max) charge window with the minimum deadband enforced
+pub fn charge_window(max_pct: u8, min_pct: Option<u8>) -> (u8, u8) {
+    let ceiling = max_pct.saturating_sub(MIN_CHARGE_DEADBAND_PCT);
+    let min = min_pct
+        .unwrap_or(max_pct.saturating_sub(RECOMMENDED_CHARGE_DEADBAND_PCT))
+        .min(ceiling);
+    (min, max_pct)
+}
+
+impl BatteryConfig {
+    /// The (min, max) window to program when the charge limit is enabled
+    pub fn charge_window(&self) -> Option<(u8, u8)> {
+        let max = self.charge_limit_max_pct.as_ref().filter(|s| s.enabled)?;
+        if self.charge_to_full_once {
+            return Some(charge_window(100, None));
+        }
+        Some(charge_window(max.value, self.charge_limit_min_pct))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(curve: &CurveConfig, from: f32, to: f32) -> usize {
+        let mut duty = from;
+        let mut steps = 0;
+        while duty != to {
+            duty = curve.rate_limit(duty, to);
+            steps += 1;
+        }
+        steps
+    }
+
+    #[test]
+    fn increasing_target_ramps_faster_than_decreasing() {
+        let curve = CurveConfig {
+            rate_limit_up_pct_per_step: 20,
+            rate_limit_down_pct_per_step: 5,
+            ..Default::default()
+        };
+        assert_eq!(curve.rate_limit(30.0, 90.0), 50.0);
+        assert_eq!(curve.rate_limit(90.0, 30.0), 85.0);
+        assert!(ramp(&curve, 30.0, 90.0) < ramp(&curve, 90.0, 30.0));
+    }
+
+    #[test]
+    fn rate_limit_does_not_overshoot_target() {
+        let curve = CurveConfig::default();
+        assert_eq!(curve.rate_limit(40.0, 42.0), 42.0);
+        assert_eq!(curve.rate_limit(42.0, 40.0), 40.0);
+        assert_eq!(curve.rate_limit(40.0, 40.0), 40.0);
+    }
+
+    #[test]
+    fn default_ramps_down_slower_than_up() {
+        let curve = CurveConfig::default();
+        assert!(curve.rate_limit_down_pct_per_step < curve.rate_limit_up_pct_per_step);
+    }
+
+    #[test]
+    fn smoothing_damps_jitter_and_off_at_alpha_one() {
+        let curve = CurveConfig {
+            smoothing_alpha: 0.25,
+            ..Default::default()
+        };
+        assert_eq!(curve.smooth(None, 60.0), 60.0);
+        assert_eq!(curve.smooth(Some(60.0), 68.0), 62.0);
+        assert_eq!(CurveConfig::default().smooth(Some(60.0), 68.0), 68.0);
+    }
+
+    #[test]
+    fn power_source_curve_falls_back_to_shared() {
+        let curve = |points: Vec<[u32; 2]>| CurveConfig {
+            points,
+            ..Default::default()
+        };
+        let mut fan = FanControlConfig {
+            curve: Some(curve(vec![[40, 20], [80, 80]])),
+            curve_battery: Some(curve(vec![[50, 10], [90, 60]])),
+            ..Default::default()
+        };
+        let points = |on_ac| fan.curve_for(on_ac).map(|c| c.points.clone());
+        assert_eq!(points(Some(true)), Some(vec![[40, 20], [80, 80]]));
+        assert_eq!(points(Some(false)), Some(vec![[50, 10], [90, 60]]));
+        assert_eq!(points(None), Some(vec![[40, 20], [80, 80]]));
+        fan.curve = None;
+        assert!(fan.curve_for(Some(true)).is_none());
+    }
+
+    #[test]
+    fn normalize_sorts_merges_and_clamps_points() {
+        let mut points = vec![[70, 50], [40, 10], [70, 80], [130, 150]];
+        let fixes = normalize_points(&mut points);
+        assert_eq!(points, vec![[40, 10], [70, 80], [MAX_CURVE_TEMP_C, 100]]);
+        assert_eq!(fixes.len(), 2);
+        assert!(normalize_points(&mut points).is_empty());
+    }
+
+    #[test]
+    fn normalize_restores_default_when_too_few_points() {
+        let mut points = vec![[60, 30], [60, 40]];
+        normalize_points(&mut points);
+        assert_eq!(points, default_points());
+    }
+
+    #[test]
+    fn burst_limits_follow_tdp_unless_enabled() {
+        let setting = |enabled, value| Some(SettingU32 { enabled, value });
+        let mut profile = PowerProfile {
+            tdp_watts: setting(true, 15),
+            fast_limit_w: setting(false, 25),
+            ..Default::default()
+        };
+        assert_eq!(profile.power_limits_w(), Some((15, 15, 15)));
+        profile.fast_limit_w = setting(true, 25);
+        profile.slow_limit_w = setting(true, 20);
+        assert_eq!(profile.power_limits_w(), Some((15, 25, 20)));
+        profile.tdp_watts = setting(false, 15);
+        assert_eq!(profile.power_limits_w(), None);
+    }
+
+    #[test]
+    fn legacy_symmetric_key_sets_up_rate() {
+        let curve: CurveConfig =
+            serde_json::from_str(r#"{"rate_limit_pct_per_step": 10}"#).unwrap();
+        assert_eq!(curve.rate_limit_up_pct_per_step, 10);
+        assert_eq!(
+            curve.rate_limit_down_pct_per_step,
+            default_rate_limit_down_pct_per_step()
+        );
+    }
 }
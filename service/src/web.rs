@@ -0,0 +1,94 @@
+// Optional read-only HTTP API with a built-in dashboard, for watching the machine from a
+// phone or another computer. Plain std networking on its own thread keeps it out of the
+// GUI and control loops.
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::RwLock;
+
+use crate::types::TelemetrySample;
+
+const DASHBOARD: &str = include_str!("dashboard.html");
+
+type Samples = Arc<RwLock<VecDeque<TelemetrySample>>>;
+
+/// Bind `addr` and serve requests until the process exits.
+/// Returns the address actually bound, or why it couldn't be.
+pub fn serve(addr: &str, samples: Samples) -> Result<String, String> {
+    let listener =
+        TcpListener::bind(addr).map_err(|e| format!("Can't listen on {}: {}", addr, e))?;
+    let bound = listener
+        .local_addr()
+        .map(|a| a.to_string())
+        .unwrap_or_else(|_| addr.to_string());
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let samples = samples.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = handle(stream, &samples) {
+                    tracing::debug!("HTTP request failed: {}", e);
+                }
+            });
+        }
+    });
+    Ok(bound)
+}
+
+fn handle(mut stream: TcpStream, samples: &Samples) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain headers; no request this API serves has a body
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let target = parts.next().unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/") | ("GET", "/index.html") => {
+            ("200 OK", "text/html; charset=utf-8", DASHBOARD.to_string())
+        }
+        ("GET", "/telemetry") => {
+            let since = query
+                .split('&')
+                .find_map(|kv| kv.strip_prefix("since="))
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(i64::MIN);
+            let newer: Vec<TelemetrySample> = samples
+                .blocking_read()
+                .iter()
+                .filter(|s| s.ts_ms > since)
+                .cloned()
+                .collect();
+            match serde_json::to_string(&newer) {
+                Ok(json) => ("200 OK", "application/json", json),
+                Err(e) => ("500 Internal Server Error", "text/plain", e.to_string()),
+            }
+        }
+        ("GET", _) => ("404 Not Found", "text/plain", "Not found".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "Read-only API".to_string(),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}